@@ -1,6 +1,11 @@
-use clap::Args;
+use clap::{Args, ValueEnum};
 use std::path::PathBuf;
 
+use super::frozen_time::{self, FrozenTime};
+
+// Each bool is an independent command line flag, so grouping them would only
+// make the clap definitions harder to follow
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Args)]
 pub struct Arguments {
     /// The spec files to run
@@ -23,7 +28,7 @@ pub struct Arguments {
     pub workspace_init_command: Option<String>,
 
     /// The shell command used to execute script blocks
-    #[clap(long, default_value_t = String::from("bash -c"))]
+    #[clap(long, env = "SPECDOWN_SHELL", default_value_t = String::from("bash -c"))]
     pub shell_command: String,
 
     /// Set an environment variable (format: 'VAR_NAME=value')
@@ -35,7 +40,117 @@ pub struct Arguments {
     #[clap(long)]
     pub unset_env: Vec<String>,
 
+    /// Run scripts without inheriting any environment variables except PATH
+    #[clap(long)]
+    pub clean_env: bool,
+
+    /// Do not inherit PATH either when running with --clean-env
+    #[clap(long, requires = "clean_env")]
+    pub drop_path: bool,
+
     /// Adds the given directory to PATH
     #[clap(long)]
     pub add_path: Vec<String>,
+
+    /// Give scripts a fixed ISO 8601 timestamp to use as the current time
+    #[clap(long, value_name = "TIMESTAMP", value_parser = frozen_time::parse)]
+    pub freeze_time: Option<FrozenTime>,
+
+    /// Fail if scripts leave files in the running directory which the spec did not create
+    #[clap(long)]
+    pub assert_clean: bool,
+
+    /// Fail before running any specs if the running directory contains any files
+    #[clap(long)]
+    pub require_empty_running_dir: bool,
+
+    /// Keep the temporary directories created for scripts run with workdir=temp
+    #[clap(long)]
+    pub keep_script_workdirs: bool,
+
+    /// Fail specs which require a newer version of specdown than this binary
+    #[clap(long)]
+    pub version_check: bool,
+
+    /// Treat warnings as failures
+    #[clap(long, visible_alias = "strict")]
+    pub fail_on_warning: bool,
+
+    /// Only run the spec files which have changed since the given git ref
+    #[clap(long, value_name = "REF")]
+    pub changed_since: Option<String>,
+
+    /// Only run the scripts which have one of the given tags
+    #[clap(long = "tag", alias = "tags", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Do not run the scripts which have one of the given tags
+    #[clap(long = "skip-tag", alias = "skip-tags", value_name = "TAG")]
+    pub skip_tags: Vec<String>,
+
+    /// Display the verified output of verifications which succeed
+    #[clap(long)]
+    pub show_output_on_pass: bool,
+
+    /// List every line which differs when an exact verification fails
+    #[clap(long)]
+    pub full_diff: bool,
+
+    /// Explain how each function was checked and why it passed or failed
+    #[clap(long)]
+    pub explain: bool,
+
+    /// After the run, report which blocks were executed and which were skipped
+    #[clap(long)]
+    pub collect_coverage: bool,
+
+    /// Fail scripts which write to stderr unless their stderr is verified
+    #[clap(long)]
+    pub no_unexpected_stderr: bool,
+
+    /// Only print the totals and a one-line entry for each failure
+    #[clap(long, conflicts_with_all = ["show_output_on_pass", "full_diff", "explain"])]
+    pub summary_only: bool,
+
+    /// The format to print the results in
+    #[clap(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Browse the results in an interactive terminal interface once the specs have run
+    #[clap(long, conflicts_with_all = ["summary_only", "output"])]
+    pub tui: bool,
+
+    /// Re-run a spec file which fails up to the given number of times
+    #[clap(long, value_name = "RETRIES", default_value_t = 0)]
+    pub retry_file: u32,
+
+    /// Run each script the given number of times and report timing statistics
+    #[clap(long, value_name = "ITERATIONS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub bench: Option<u32>,
+
+    /// Print which verifications depend on which scripts instead of running the specs
+    #[clap(long)]
+    pub print_plan: bool,
+
+    /// Print every tag used by the scripts and how often instead of running the specs
+    #[clap(long)]
+    pub list_tags: bool,
+
+    /// Write a JSON description of every action to the given file instead of running the specs
+    #[clap(long, value_name = "FILE")]
+    pub export_plan: Option<PathBuf>,
+
+    /// Log each decision the runner makes to stderr
+    #[clap(long)]
+    pub trace: bool,
+
+    /// Print the markdown syntax tree of each spec file instead of running it
+    #[clap(long, hide = true)]
+    pub dump_ast: bool,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Ndjson,
 }
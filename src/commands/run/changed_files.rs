@@ -0,0 +1,123 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::runner::{Error, ExecutionOptions, Executor};
+use crate::types::ScriptCode;
+
+pub fn list(
+    executor: &dyn Executor,
+    git_ref: &str,
+    directory: &Path,
+) -> Result<BTreeSet<PathBuf>, Error> {
+    let directory_arg = quote(&directory.display().to_string());
+    let command = format!(
+        "git -C {directory_arg} rev-parse --git-dir >/dev/null && git -C {directory_arg} diff --name-only --relative {} --",
+        quote(git_ref)
+    );
+
+    let output = executor.execute(&ScriptCode(command), &ExecutionOptions::default())?;
+
+    if output.exit_code != Some(0) {
+        return Err(Error::ChangedFilesUnavailable {
+            git_ref: git_ref.to_string(),
+            message: output.stderr.trim().to_string(),
+        });
+    }
+
+    Ok(output
+        .stdout
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| directory.join(line))
+        .collect())
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, quote};
+    use crate::runner::executor::Output;
+    use crate::runner::{Error, ExecutionOptions, Executor};
+    use crate::types::ScriptCode;
+    use std::cell::RefCell;
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+
+    struct StubExecutor {
+        stdout: &'static str,
+        stderr: &'static str,
+        exit_code: i32,
+        commands: RefCell<Vec<String>>,
+    }
+
+    impl StubExecutor {
+        fn new(stdout: &'static str, stderr: &'static str, exit_code: i32) -> Self {
+            Self {
+                stdout,
+                stderr,
+                exit_code,
+                commands: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl Executor for StubExecutor {
+        fn execute(&self, script: &ScriptCode, _: &ExecutionOptions) -> Result<Output, Error> {
+            let ScriptCode(command) = script;
+            self.commands.borrow_mut().push(command.clone());
+            Ok(Output::from_bytes(
+                self.stdout.as_bytes().to_vec(),
+                self.stderr.as_bytes().to_vec(),
+                Some(self.exit_code),
+            ))
+        }
+    }
+
+    #[test]
+    fn runs_git_diff_against_the_ref_in_the_directory() {
+        let executor = StubExecutor::new("", "", 0);
+
+        list(&executor, "main", Path::new("/start/dir")).unwrap();
+
+        assert_eq!(
+            *executor.commands.borrow(),
+            vec!["git -C '/start/dir' rev-parse --git-dir >/dev/null && git -C '/start/dir' diff --name-only --relative 'main' --".to_string()]
+        );
+    }
+
+    #[test]
+    fn returns_the_changed_files_relative_to_the_directory() {
+        let executor = StubExecutor::new("docs/a.md\ndocs/b.md\n", "", 0);
+
+        assert_eq!(
+            list(&executor, "main", Path::new("/start")),
+            Ok(vec![
+                PathBuf::from("/start/docs/a.md"),
+                PathBuf::from("/start/docs/b.md")
+            ]
+            .into_iter()
+            .collect::<BTreeSet<_>>())
+        );
+    }
+
+    #[test]
+    fn fails_with_the_git_error_when_git_fails() {
+        let executor = StubExecutor::new("", "fatal: bad revision 'nope'\n", 128);
+
+        assert_eq!(
+            list(&executor, "nope", Path::new("/start")),
+            Err(Error::ChangedFilesUnavailable {
+                git_ref: "nope".to_string(),
+                message: "fatal: bad revision 'nope'".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn quote_escapes_single_quotes() {
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use crate::results::{ActionResult, BlockCoverage, BlockStatus};
+use crate::runner::{describe_action, RunEvent};
+use crate::types::{Action, SectionPath};
+
+// The runner emits one event for each action it is given, so the events line
+// up with the selected actions in order
+pub fn build(
+    spec_file: &Path,
+    actions: &[Action],
+    sections: &[SectionPath],
+    selected: &[bool],
+    events: &[RunEvent],
+) -> Vec<BlockCoverage> {
+    let mut events = events.iter();
+
+    actions
+        .iter()
+        .zip(sections)
+        .zip(selected)
+        .enumerate()
+        .map(|(index, ((action, section), selected))| {
+            let event = if *selected { events.next() } else { None };
+            BlockCoverage {
+                spec_file: spec_file.to_path_buf(),
+                block: index + 1,
+                section: section.clone(),
+                description: describe_action(action),
+                status: status(event),
+            }
+        })
+        .collect()
+}
+
+fn status(event: Option<&RunEvent>) -> BlockStatus {
+    match event {
+        Some(RunEvent::TestCompleted(result)) if matches!(**result, ActionResult::Skipped(_)) => {
+            BlockStatus::Skipped
+        }
+        Some(RunEvent::TestCompleted(result)) if result.success() => BlockStatus::Passed,
+        Some(
+            RunEvent::TestCompleted(_) | RunEvent::FailureAllowed(_) | RunEvent::ErrorOccurred(_),
+        ) => BlockStatus::Failed,
+        _ => BlockStatus::Skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::build;
+    use crate::results::{ActionResult, BlockCoverage, BlockStatus, NoteResult, SkippedResult};
+    use crate::runner::{Error, RunEvent};
+    use crate::types::{
+        Action, NoteAction, OutputExpectation, ScriptAction, ScriptCode, ScriptName, ScriptOptions,
+        SectionPath,
+    };
+
+    fn script(name: &str) -> Action {
+        Action::Script(ScriptAction {
+            script_name: Some(ScriptName(name.to_string())),
+            script_code: ScriptCode("true".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        })
+    }
+
+    fn note() -> Action {
+        Action::Note(NoteAction {
+            text: "a note".to_string(),
+        })
+    }
+
+    fn block(block: usize, description: &str, status: BlockStatus) -> BlockCoverage {
+        BlockCoverage {
+            spec_file: PathBuf::from("one.md"),
+            block,
+            section: SectionPath::default(),
+            description: description.to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn distinguishes_executed_blocks_from_skipped_ones() {
+        let actions = vec![script("build"), script("deploy"), note(), script("check")];
+        let events = vec![
+            RunEvent::ErrorOccurred(Error::ScriptOutputMissing {
+                missing_script_name: "build".to_string(),
+            }),
+            RunEvent::TestCompleted(Box::new(ActionResult::Note(NoteResult {
+                action: NoteAction {
+                    text: "a note".to_string(),
+                },
+            }))),
+            RunEvent::Warning("Skipped on this platform".to_string()),
+        ];
+
+        assert_eq!(
+            build(
+                Path::new("one.md"),
+                &actions,
+                &vec![SectionPath::default(); 4],
+                &[true, false, true, true],
+                &events
+            ),
+            vec![
+                block(1, "script 'build'", BlockStatus::Failed),
+                block(2, "script 'deploy'", BlockStatus::Skipped),
+                block(3, "note \"a note\"", BlockStatus::Passed),
+                block(4, "script 'check'", BlockStatus::Skipped),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_blocks_skipped_by_the_spec_as_skipped() {
+        let events = vec![RunEvent::TestCompleted(Box::new(ActionResult::Skipped(
+            SkippedResult {
+                action: script("build"),
+                reason: Some("needs network access".to_string()),
+            },
+        )))];
+
+        assert_eq!(
+            build(
+                Path::new("one.md"),
+                &[script("build")],
+                &[SectionPath::default()],
+                &[true],
+                &events
+            ),
+            vec![block(1, "script 'build'", BlockStatus::Skipped)]
+        );
+    }
+
+    #[test]
+    fn marks_every_block_as_skipped_when_the_spec_did_not_run() {
+        assert_eq!(
+            build(
+                Path::new("one.md"),
+                &[script("build")],
+                &[SectionPath::default()],
+                &[true],
+                &[]
+            ),
+            vec![block(1, "script 'build'", BlockStatus::Skipped)]
+        );
+    }
+
+    #[test]
+    fn labels_each_block_with_its_section() {
+        let sections = vec![SectionPath(vec![
+            "Installation".to_string(),
+            "From source".to_string(),
+        ])];
+
+        assert_eq!(
+            build(
+                Path::new("one.md"),
+                &[script("build")],
+                &sections,
+                &[true],
+                &[]
+            ),
+            vec![BlockCoverage {
+                section: sections[0].clone(),
+                ..block(1, "script 'build'", BlockStatus::Skipped)
+            }]
+        );
+    }
+}
@@ -1,15 +1,16 @@
 use crate::exit_codes::ExitCode;
 use crate::runner::{Error, RunEvent};
 
-pub fn from_events(events: &[RunEvent]) -> ExitCode {
+pub fn from_events(events: &[RunEvent], fail_on_warning: bool) -> ExitCode {
     let mut exit_code = ExitCode::Success;
 
     for event in events {
         match event {
-            RunEvent::SpecFileCompleted { success: false } => {
-                if exit_code == ExitCode::Success {
-                    exit_code = ExitCode::TestFailed;
-                }
+            RunEvent::SpecFileCompleted { success: false } if exit_code == ExitCode::Success => {
+                exit_code = ExitCode::TestFailed;
+            }
+            RunEvent::Warning(_) if fail_on_warning && exit_code == ExitCode::Success => {
+                exit_code = ExitCode::TestFailed;
             }
             RunEvent::ErrorOccurred(error) => {
                 return match error {
@@ -23,3 +24,53 @@ pub fn from_events(events: &[RunEvent]) -> ExitCode {
 
     exit_code
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{from_events, ExitCode, RunEvent};
+
+    fn events_with_warning() -> Vec<RunEvent> {
+        vec![
+            RunEvent::Warning("something looks wrong".to_string()),
+            RunEvent::SpecFileCompleted { success: true },
+        ]
+    }
+
+    #[test]
+    fn warnings_do_not_affect_the_exit_code_by_default() {
+        assert_eq!(
+            from_events(&events_with_warning(), false),
+            ExitCode::Success
+        );
+    }
+
+    #[test]
+    fn warnings_fail_the_run_when_fail_on_warning_is_set() {
+        assert_eq!(
+            from_events(&events_with_warning(), true),
+            ExitCode::TestFailed
+        );
+    }
+
+    #[test]
+    fn info_does_not_fail_the_run_when_fail_on_warning_is_set() {
+        assert_eq!(
+            from_events(
+                &[
+                    RunEvent::Info("The spec passed on attempt 2 of 3".to_string()),
+                    RunEvent::SpecFileCompleted { success: true },
+                ],
+                true
+            ),
+            ExitCode::Success
+        );
+    }
+
+    #[test]
+    fn succeeds_without_warnings_when_fail_on_warning_is_set() {
+        assert_eq!(
+            from_events(&[RunEvent::SpecFileCompleted { success: true }], true),
+            ExitCode::Success
+        );
+    }
+}
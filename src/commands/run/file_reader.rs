@@ -10,11 +10,15 @@ impl FileReader {
         Self { directory }
     }
 
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
     pub fn read_file(&self, spec_file: &Path) -> String {
         fs::read_to_string(self.to_absolute(spec_file)).expect("failed to read spec file")
     }
 
-    fn to_absolute(&self, path: &Path) -> PathBuf {
+    pub fn to_absolute(&self, path: &Path) -> PathBuf {
         if path.has_root() {
             path.to_path_buf()
         } else {
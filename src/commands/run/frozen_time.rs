@@ -0,0 +1,195 @@
+use regex::Regex;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrozenTime {
+    timestamp: String,
+    utc: String,
+}
+
+impl FrozenTime {
+    // libfaketime reads FAKETIME in the local timezone, so scripts are run in UTC
+    pub fn environment(&self) -> Vec<(String, String)> {
+        vec![
+            ("SPECDOWN_NOW".to_string(), self.timestamp.clone()),
+            ("FAKETIME".to_string(), format!("@{}", self.utc)),
+            ("TZ".to_string(), "UTC".to_string()),
+        ]
+    }
+}
+
+pub fn parse(timestamp: &str) -> Result<FrozenTime, String> {
+    let pattern = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})(\.\d+)?(Z|([+-])(\d{2}):(\d{2}))?$",
+    )
+    .expect("the timestamp pattern to be valid");
+
+    let invalid =
+        || format!("'{timestamp}' is not an ISO 8601 timestamp (e.g. 2024-01-31T09:30:00Z)");
+
+    let captures = pattern.captures(timestamp).ok_or_else(invalid)?;
+    let number = |index: usize| {
+        captures.get(index).map_or(0, |digits| {
+            digits
+                .as_str()
+                .parse::<i64>()
+                .expect("the pattern to only match digits")
+        })
+    };
+    let (year, month, day) = (number(1), number(2), number(3));
+    let (hour, minute, second) = (number(4), number(5), number(6));
+    let (offset_hours, offset_minutes) = (number(10), number(11));
+
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+        || offset_hours > 23
+        || offset_minutes > 59
+    {
+        return Err(invalid());
+    }
+
+    // A timestamp without an offset is taken to be in UTC, which scripts are run in
+    let offset = match captures.get(9).map(|sign| sign.as_str()) {
+        Some("-") => -(offset_hours * 60 + offset_minutes) * 60,
+        Some(_) => (offset_hours * 60 + offset_minutes) * 60,
+        None => 0,
+    };
+    let seconds =
+        days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let time_of_day = seconds.rem_euclid(86_400);
+
+    Ok(FrozenTime {
+        timestamp: timestamp.to_string(),
+        utc: format!(
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}{}",
+            time_of_day / 3_600,
+            time_of_day / 60 % 60,
+            time_of_day % 60,
+            captures.get(7).map_or("", |fraction| fraction.as_str())
+        ),
+    })
+}
+
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01, using the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    fn faketime(timestamp: &str) -> String {
+        parse(timestamp)
+            .unwrap()
+            .environment()
+            .into_iter()
+            .find(|(name, _)| name == "FAKETIME")
+            .map(|(_, value)| value)
+            .unwrap()
+    }
+
+    #[test]
+    fn sets_the_timestamp_and_an_equivalent_faketime_in_utc() {
+        let frozen_time = parse("2024-01-31T09:30:00Z").unwrap();
+
+        assert_eq!(
+            frozen_time.environment(),
+            vec![
+                (
+                    "SPECDOWN_NOW".to_string(),
+                    "2024-01-31T09:30:00Z".to_string()
+                ),
+                ("FAKETIME".to_string(), "@2024-01-31 09:30:00".to_string()),
+                ("TZ".to_string(), "UTC".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_offsets_to_utc() {
+        assert_eq!(
+            faketime("2024-01-31T09:30:00+01:00"),
+            "@2024-01-31 08:30:00"
+        );
+        assert_eq!(
+            faketime("2024-12-31T22:15:00-05:30"),
+            "@2025-01-01 03:45:00"
+        );
+        assert_eq!(
+            faketime("2024-03-01T00:30:00+02:00"),
+            "@2024-02-29 22:30:00"
+        );
+    }
+
+    #[test]
+    fn keeps_fractional_seconds() {
+        assert_eq!(
+            faketime("2024-01-31T09:30:00.123+01:00"),
+            "@2024-01-31 08:30:00.123"
+        );
+    }
+
+    #[test]
+    fn treats_a_timestamp_without_an_offset_as_utc() {
+        assert_eq!(faketime("2024-01-31T09:30:00"), "@2024-01-31 09:30:00");
+    }
+
+    #[test]
+    fn rejects_timestamps_which_are_not_iso_8601() {
+        assert_eq!(
+            parse("31/01/2024"),
+            Err(
+                "'31/01/2024' is not an ISO 8601 timestamp (e.g. 2024-01-31T09:30:00Z)".to_string()
+            )
+        );
+        assert!(parse("2024-13-01T00:00:00Z").is_err());
+        assert!(parse("2024-01-31T24:00:00Z").is_err());
+        assert!(parse("2024-01-31T09:30:00+25:00").is_err());
+    }
+
+    #[test]
+    fn rejects_dates_which_do_not_exist() {
+        assert!(parse("2024-02-31T00:00:00Z").is_err());
+        assert!(parse("2023-02-29T00:00:00Z").is_err());
+        assert!(parse("2024-04-31T00:00:00Z").is_err());
+        assert!(parse("2024-02-29T00:00:00Z").is_ok());
+        assert!(parse("2000-02-29T00:00:00Z").is_ok());
+        assert!(parse("1900-02-29T00:00:00Z").is_err());
+    }
+}
@@ -1,38 +1,197 @@
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 
 pub use arguments::Arguments;
+use arguments::OutputFormat;
 use file_reader::FileReader;
-use run_command::RunCommand;
+use run_command::{RunCommand, RunningDirChecks};
 
 use crate::config::Config;
 use crate::exit_codes::ExitCode;
+use crate::parsers;
 use crate::results::basic_printer::BasicPrinter;
-use crate::results::Printer;
-use crate::runner::shell_executor::ShellExecutor;
+use crate::results::{NdjsonPrinter, Printer, SummaryPrinter, TuiPrinter};
+use crate::runner::shell_executor::{EnvInheritance, ShellExecutor};
 use crate::runner::{Error, RunEvent};
+use crate::trace;
+use crate::types::Action;
 use crate::workspace::{ExistingDir, TemporaryDirectory, Workspace};
 
 mod arguments;
+mod changed_files;
+mod coverage;
 mod exit_code;
 mod file_reader;
+mod frozen_time;
+mod plan;
+mod plan_export;
 mod run_command;
+mod tag_filter;
+mod version_check;
 
 pub fn execute(config: &Config, args: &Arguments) {
-    let events = create_run_command(args).map_or_else(
-        |err| vec![RunEvent::ErrorOccurred(err)],
-        |command| command.execute(),
-    );
+    if args.trace {
+        trace::enable();
+    }
+
+    if args.dump_ast {
+        dump_ast(&args.spec_files);
+    }
+
+    if args.print_plan {
+        print_plan(config, &args.spec_files);
+    }
+
+    if args.list_tags {
+        list_tags(config, &args.spec_files, args.output);
+    }
 
-    let mut printer = BasicPrinter::new(config.colour);
-    for event in &events {
-        printer.print(event);
+    if let Some(plan_file) = &args.export_plan {
+        export_plan(config, &args.spec_files, plan_file);
+    }
+
+    if args.tui && !(io::stdin().is_terminal() && io::stdout().is_terminal()) {
+        exit_with_error(
+            config,
+            "--tui is only available when running in an interactive terminal".to_string(),
+        );
+    }
+
+    let mut printer = create_printer(config, args);
+
+    let mut events = vec![];
+    let mut emit = |event: RunEvent| {
+        printer.print(&event);
+        events.push(event);
+    };
+    match create_run_command(args) {
+        Ok(command) => command.execute(&mut emit),
+        Err(err) => emit(RunEvent::ErrorOccurred(err)),
     }
+    printer.finish();
 
-    let exit_code = exit_code::from_events(&events);
+    let exit_code = exit_code::from_events(&events, args.fail_on_warning);
 
     std::process::exit(exit_code as i32)
 }
 
+fn create_printer(config: &Config, args: &Arguments) -> Box<dyn Printer> {
+    if args.tui {
+        return Box::new(TuiPrinter::new());
+    }
+
+    match (args.output, args.summary_only) {
+        (OutputFormat::Ndjson, _) => Box::new(NdjsonPrinter::new()),
+        (OutputFormat::Text, true) => Box::new(SummaryPrinter::new(config.colour)),
+        (OutputFormat::Text, false) => Box::new(
+            BasicPrinter::new(config.colour)
+                .show_output_on_pass(args.show_output_on_pass)
+                .full_diff(args.full_diff)
+                .explain(args.explain),
+        ),
+    }
+}
+
+fn dump_ast(spec_files: &[PathBuf]) -> ! {
+    for spec_file in spec_files {
+        let contents = fs::read_to_string(spec_file).expect("failed to read spec file");
+        println!(
+            "{}:\n{}\n",
+            spec_file.display(),
+            parsers::dump_ast(&contents)
+        );
+    }
+
+    std::process::exit(ExitCode::Success as i32)
+}
+
+fn print_plan(config: &Config, spec_files: &[PathBuf]) -> ! {
+    let mut exit_code = ExitCode::Success;
+    for spec_file in spec_files {
+        let contents = fs::read_to_string(spec_file).expect("failed to read spec file");
+        match parsers::parse(&contents, spec_directory(spec_file)) {
+            Ok(spec) => println!(
+                "{}:\n{}\n",
+                spec_file.display(),
+                plan::format(&plan::build(&spec.actions))
+            ),
+            Err(err) => {
+                BasicPrinter::new(config.colour).print(&RunEvent::ErrorOccurred(
+                    Error::RunFailed {
+                        message: err.to_string(),
+                    },
+                ));
+                exit_code = ExitCode::ErrorOccurred;
+            }
+        }
+    }
+
+    std::process::exit(exit_code as i32)
+}
+
+fn list_tags(config: &Config, spec_files: &[PathBuf], output: OutputFormat) -> ! {
+    let mut actions = vec![];
+    for spec_file in spec_files {
+        let contents = fs::read_to_string(spec_file).expect("failed to read spec file");
+        match parsers::parse(&contents, spec_directory(spec_file)) {
+            Ok(spec) => actions.extend(spec.actions),
+            Err(err) => exit_with_error(config, err.to_string()),
+        }
+    }
+
+    let counts = tag_filter::count(&actions);
+    match output {
+        OutputFormat::Ndjson => println!("{}", serde_json::json!({ "tags": counts })),
+        OutputFormat::Text => {
+            for (tag, count) in &counts {
+                println!("{tag}: {count}");
+            }
+        }
+    }
+
+    std::process::exit(ExitCode::Success as i32)
+}
+
+fn export_plan(config: &Config, spec_files: &[PathBuf], plan_file: &Path) -> ! {
+    let mut specs = vec![];
+    for spec_file in spec_files {
+        let contents = fs::read_to_string(spec_file).expect("failed to read spec file");
+        match parsers::parse(&contents, spec_directory(spec_file)) {
+            Ok(spec) => specs.push((spec_file.as_path(), spec.actions)),
+            Err(err) => exit_with_error(config, err.to_string()),
+        }
+    }
+
+    let specs: Vec<(&Path, &[Action])> = specs
+        .iter()
+        .map(|(file, actions)| (*file, actions.as_slice()))
+        .collect();
+    let plan = serde_json::to_string_pretty(&plan_export::build(&specs))
+        .expect("the plan to serialize to JSON");
+
+    if let Err(err) = fs::write(plan_file, format!("{plan}\n")) {
+        exit_with_error(
+            config,
+            format!(
+                "Failed to write the plan to {} (Error: {err})",
+                plan_file.display()
+            ),
+        );
+    }
+
+    std::process::exit(ExitCode::Success as i32)
+}
+
+fn spec_directory(spec_file: &Path) -> &Path {
+    spec_file.parent().unwrap_or_else(|| Path::new(""))
+}
+
+fn exit_with_error(config: &Config, message: String) -> ! {
+    BasicPrinter::new(config.colour).print(&RunEvent::ErrorOccurred(Error::RunFailed { message }));
+    std::process::exit(ExitCode::ErrorOccurred as i32)
+}
+
 fn create_run_command(args: &Arguments) -> Result<RunCommand, Error> {
     let temp_workspace_dir = args.temporary_workspace_dir;
     let workspace_init_command = args.workspace_init_command.clone();
@@ -79,15 +238,51 @@ fn create_run_command(args: &Arguments) -> Result<RunCommand, Error> {
             .expect("failed to convert working dir into a string"),
     ));
 
+    env.push((
+        "SPECDOWN_VERSION".to_string(),
+        env!("CARGO_PKG_VERSION").to_string(),
+    ));
+
+    if let Some(frozen_time) = &args.freeze_time {
+        env.extend(frozen_time.environment());
+    }
+
     let new_command = |e| RunCommand {
         spec_files: args.spec_files.clone(),
         executor: Box::new(e),
         working_dir: actual_working_dir,
         workspace_init_command,
         file_reader,
+        variables: env.clone(),
+        home_dir: std::env::var("HOME").ok(),
+        running_dir_checks: RunningDirChecks {
+            require_empty: args.require_empty_running_dir,
+            assert_clean: args.assert_clean,
+        },
+        keep_script_workdirs: args.keep_script_workdirs,
+        on_version_mismatch: if args.version_check {
+            version_check::OnMismatch::Fail
+        } else {
+            version_check::OnMismatch::Warn
+        },
+        changed_since: args.changed_since.clone(),
+        tags: args.tags.clone(),
+        skip_tags: args.skip_tags.clone(),
+        collect_coverage: args.collect_coverage,
+        bench: args.bench,
+        no_unexpected_stderr: args.no_unexpected_stderr,
+        retry_file: args.retry_file,
+    };
+
+    let env_inheritance = match (args.clean_env, args.drop_path) {
+        (false, _) => EnvInheritance::All,
+        (true, false) => EnvInheritance::PathOnly,
+        (true, true) => EnvInheritance::Nothing,
     };
 
-    ShellExecutor::new(&shell_cmd, &env, &unset_env, &paths).map(new_command)
+    ShellExecutor::new(&shell_cmd, &env, &unset_env, &paths)
+        .map(|executor| executor.env_inheritance(env_inheritance))
+        .map(new_command)
 }
 
 fn create_workspace(
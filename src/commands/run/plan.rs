@@ -0,0 +1,170 @@
+use crate::types::{Action, ScriptAction, Stream, VerifyAction};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ScriptNode {
+    pub name: Option<String>,
+    pub verifies: Vec<String>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Plan {
+    pub scripts: Vec<ScriptNode>,
+    pub unresolved: Vec<String>,
+}
+
+pub fn build(actions: &[Action]) -> Plan {
+    let mut plan = Plan::default();
+
+    for action in actions {
+        match action {
+            Action::Script(ScriptAction { script_name, .. }) => plan.scripts.push(ScriptNode {
+                name: script_name.as_ref().map(Into::into),
+                verifies: vec![],
+            }),
+            Action::Verify(VerifyAction { source, .. }) => {
+                let stream = match source.stream {
+                    Stream::StdOut => "stdout",
+                    Stream::StdErr => "stderr",
+                    Stream::Output => "output",
+                };
+                let name: Option<String> = source.name.as_ref().map(Into::into);
+                let script = match &name {
+                    Some(name) => plan
+                        .scripts
+                        .iter_mut()
+                        .rev()
+                        .find(|script| script.name.as_ref() == Some(name)),
+                    None => plan.scripts.last_mut(),
+                };
+
+                match (script, name) {
+                    (Some(script), _) => script.verifies.push(format!("verify {stream}")),
+                    (None, Some(name)) => plan
+                        .unresolved
+                        .push(format!("verify {stream} from '{name}'")),
+                    (None, None) => plan.unresolved.push(format!("verify {stream}")),
+                }
+            }
+            Action::VerifyDirectory(_)
+            | Action::VerifyFile(_)
+            | Action::CreateFile(_)
+            | Action::Note(_)
+            | Action::SetEnv(_)
+            | Action::Stdin(_)
+            | Action::Skip(_) => {}
+        }
+    }
+
+    plan
+}
+
+pub fn format(plan: &Plan) -> String {
+    let scripts = plan.scripts.iter().flat_map(|script| {
+        std::iter::once(format!(
+            "  script '{}'",
+            script.name.as_deref().unwrap_or("<unnamed>")
+        ))
+        .chain(script.verifies.iter().map(|verify| format!("    {verify}")))
+    });
+    let unresolved = plan
+        .unresolved
+        .iter()
+        .map(|verify| format!("  unresolved {verify}"));
+
+    scripts.chain(unresolved).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build, format, Plan, ScriptNode};
+    use crate::types::{
+        Action, OutputExpectation, ScriptAction, ScriptCode, ScriptName, ScriptOptions, Source,
+        Stream, VerifyAction, VerifyOptions, VerifyValue,
+    };
+
+    fn script(name: Option<&str>) -> Action {
+        Action::Script(ScriptAction {
+            script_name: name.map(|name| ScriptName(name.to_string())),
+            script_code: ScriptCode(String::new()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        })
+    }
+
+    fn verify(name: Option<&str>, stream: Stream) -> Action {
+        Action::Verify(VerifyAction {
+            source: Source {
+                name: name.map(|name| ScriptName(name.to_string())),
+                stream,
+            },
+            expected_value: VerifyValue(String::new()),
+            options: VerifyOptions::default(),
+        })
+    }
+
+    #[test]
+    fn lists_each_script_with_its_dependent_verifies() {
+        let actions = vec![
+            script(Some("build")),
+            verify(Some("build"), Stream::StdOut),
+            script(Some("test")),
+            verify(None, Stream::StdOut),
+            verify(Some("build"), Stream::StdErr),
+        ];
+
+        assert_eq!(
+            build(&actions),
+            Plan {
+                scripts: vec![
+                    ScriptNode {
+                        name: Some("build".to_string()),
+                        verifies: vec!["verify stdout".to_string(), "verify stderr".to_string()],
+                    },
+                    ScriptNode {
+                        name: Some("test".to_string()),
+                        verifies: vec!["verify stdout".to_string()],
+                    },
+                ],
+                unresolved: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn lists_verifies_which_do_not_refer_to_an_earlier_script_as_unresolved() {
+        let actions = vec![
+            verify(None, Stream::StdOut),
+            verify(Some("missing"), Stream::StdErr),
+        ];
+
+        assert_eq!(
+            build(&actions).unresolved,
+            vec![
+                "verify stdout".to_string(),
+                "verify stderr from 'missing'".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_the_plan_as_an_indented_tree() {
+        let actions = vec![
+            script(Some("build")),
+            verify(Some("build"), Stream::StdOut),
+            script(None),
+            verify(Some("missing"), Stream::StdOut),
+        ];
+
+        assert_eq!(
+            format(&build(&actions)),
+            [
+                "  script 'build'",
+                "    verify stdout",
+                "  script '<unnamed>'",
+                "  unresolved verify stdout from 'missing'",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::types::{
+    Action, CreateFileAction, DirectoryExpectation, ExpectedExitCode, NoteAction,
+    OutputExpectation, ScriptAction, SetEnvAction, SkipAction, StdinAction, Stream, VerifyAction,
+    VerifyDirectoryAction, VerifyFileAction,
+};
+
+pub fn build(specs: &[(&Path, &[Action])]) -> Value {
+    json!({
+        "version": 1,
+        "specs": specs
+            .iter()
+            .map(|(file, actions)| json!({
+                "file": file.display().to_string(),
+                "actions": actions.iter().map(describe).collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn describe(action: &Action) -> Value {
+    match action {
+        Action::Script(ScriptAction {
+            script_name,
+            script_code,
+            expected_exit_code,
+            expected_output,
+            ..
+        }) => json!({
+            "type": "script",
+            "script_name": script_name.as_ref().map(String::from),
+            "body_sha256": sha256(&script_code.0),
+            "expected_exit_code": expected_exit_code.as_ref().map(|expected| match expected {
+                ExpectedExitCode::Exactly(code) => json!(code.0),
+                ExpectedExitCode::Range { .. } => json!(expected.to_string()),
+                ExpectedExitCode::OneOf(codes) => {
+                    json!(codes.iter().map(|code| code.0).collect::<Vec<_>>())
+                }
+            }),
+            "expected_output": match expected_output {
+                OutputExpectation::Any => "any",
+                OutputExpectation::StdOut => "stdout",
+                OutputExpectation::StdErr => "stderr",
+                OutputExpectation::None => "none",
+            },
+        }),
+        Action::Verify(VerifyAction {
+            source,
+            expected_value,
+            ..
+        }) => json!({
+            "type": "verify",
+            "script_name": source.name.as_ref().map(String::from),
+            "stream": match source.stream {
+                Stream::StdOut => "stdout",
+                Stream::StdErr => "stderr",
+                Stream::Output => "output",
+            },
+            "expected_sha256": sha256(&expected_value.0),
+        }),
+        Action::VerifyFile(VerifyFileAction {
+            file_path,
+            expected_value,
+            ..
+        }) => json!({
+            "type": "verify_file",
+            "path": file_path.0,
+            "expected_sha256": sha256(&expected_value.0),
+        }),
+        Action::VerifyDirectory(VerifyDirectoryAction {
+            directory,
+            expected: DirectoryExpectation::Golden(golden),
+        }) => json!({
+            "type": "verify_directory",
+            "directory": directory.0,
+            "golden": golden.0,
+        }),
+        Action::VerifyDirectory(VerifyDirectoryAction {
+            directory,
+            expected: DirectoryExpectation::Tree(paths),
+        }) => json!({
+            "type": "verify_tree",
+            "directory": directory.0,
+            "paths": paths,
+        }),
+        Action::CreateFile(CreateFileAction {
+            file_path,
+            file_content,
+            ..
+        }) => json!({
+            "type": "create_file",
+            "path": file_path.0,
+            "content_sha256": sha256(&file_content.0),
+        }),
+        Action::Note(NoteAction { text }) => json!({
+            "type": "note",
+            "text": text,
+        }),
+        Action::SetEnv(SetEnvAction {
+            variables,
+            unset,
+            reset,
+        }) => json!({
+            "type": "set_env",
+            "names": variables.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            "unset": unset,
+            "reset": reset,
+        }),
+        Action::Stdin(StdinAction { script_name, input }) => json!({
+            "type": "stdin",
+            "script_name": String::from(script_name.clone()),
+            "content_sha256": sha256(input),
+        }),
+        Action::Skip(SkipAction { reason }) => json!({
+            "type": "skip",
+            "reason": reason,
+        }),
+    }
+}
+
+fn sha256(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use serde_json::json;
+
+    use super::build;
+    use crate::types::{
+        Action, ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode,
+        ScriptName, ScriptOptions, Source, Stream, VerifyAction, VerifyOptions, VerifyValue,
+    };
+
+    #[test]
+    fn describes_each_action_with_hashes_of_its_content() {
+        let actions = vec![
+            Action::Script(ScriptAction {
+                script_name: Some(ScriptName("greet".to_string())),
+                script_code: ScriptCode("echo hi\n".to_string()),
+                expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+                expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
+            }),
+            Action::Verify(VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("greet".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue("hi\n".to_string()),
+                options: VerifyOptions::default(),
+            }),
+        ];
+
+        assert_eq!(
+            build(&[(Path::new("greet.md"), &actions)]),
+            json!({
+                "version": 1,
+                "specs": [{
+                    "file": "greet.md",
+                    "actions": [
+                        {
+                            "type": "script",
+                            "script_name": "greet",
+                            "body_sha256": "ab08508fdf5ca4da5c4995987bc41c56c048aaa5eeb046417ae4049b7d40286e",
+                            "expected_exit_code": 0,
+                            "expected_output": "any",
+                        },
+                        {
+                            "type": "verify",
+                            "script_name": "greet",
+                            "stream": "stdout",
+                            "expected_sha256": "98ea6e4f216f2fb4b69fff9b3a44842c38686ca685f3f55dc48c5d3fb1107be4",
+                        },
+                    ],
+                }],
+            })
+        );
+    }
+}
@@ -1,10 +1,25 @@
-use std::path::{Path, PathBuf};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 
 use crate::parsers;
-use crate::runner::{Error, Executor, RunEvent, Runner, State};
-use crate::types::ScriptCode;
+use crate::results::{BlockCoverage, Coverage};
+use crate::runner::{
+    directory_listing, Error, ExecutionOptions, Executor, RunEvent, Runner, State,
+};
+use crate::types::{Metadata, ScriptCode, SectionPath};
 
+use super::changed_files;
+use super::coverage;
 use super::file_reader::FileReader;
+use super::tag_filter;
+use super::version_check;
+
+// Checks made on the running directory before and after each spec file
+pub struct RunningDirChecks {
+    pub require_empty: bool,
+    pub assert_clean: bool,
+}
 
 pub struct RunCommand {
     pub spec_files: Vec<PathBuf>,
@@ -12,53 +27,280 @@ pub struct RunCommand {
     pub working_dir: PathBuf,
     pub workspace_init_command: Option<String>,
     pub file_reader: FileReader,
+    pub variables: Vec<(String, String)>,
+    pub home_dir: Option<String>,
+    pub running_dir_checks: RunningDirChecks,
+    pub keep_script_workdirs: bool,
+    pub on_version_mismatch: version_check::OnMismatch,
+    pub changed_since: Option<String>,
+    pub tags: Vec<String>,
+    pub skip_tags: Vec<String>,
+    pub collect_coverage: bool,
+    pub bench: Option<u32>,
+    pub no_unexpected_stderr: bool,
+    pub retry_file: u32,
 }
 
 impl RunCommand {
-    pub fn execute(&self) -> Vec<RunEvent> {
-        self.change_to_working_directory();
+    pub fn execute(&self, emit: &mut dyn FnMut(RunEvent)) {
+        if let Err(err) = self.change_to_working_directory() {
+            return emit(RunEvent::ErrorOccurred(err));
+        }
 
         self.initialise_workspace();
 
-        self.spec_files
+        let spec_files = match self.spec_files_to_run() {
+            Ok(spec_files) => spec_files,
+            Err(err) => return emit(RunEvent::ErrorOccurred(err)),
+        };
+
+        let mut coverage = Coverage::default();
+        for spec_file in &spec_files {
+            let (events, blocks) = self.run_spec_file_with_retries(spec_file);
+            events.into_iter().for_each(&mut *emit);
+            coverage.blocks.extend(blocks);
+        }
+
+        if self.collect_coverage {
+            emit(RunEvent::CoverageCollected(coverage));
+        }
+    }
+
+    fn run_spec_file_with_retries(&self, spec_file: &Path) -> (Vec<RunEvent>, Vec<BlockCoverage>) {
+        let attempts = self.retry_file + 1;
+        let mut attempt = 1;
+        let (mut events, mut blocks) = self.run_spec_file(spec_file);
+        while !passed(&events) && attempt < attempts {
+            attempt += 1;
+            (events, blocks) = self.run_spec_file(spec_file);
+        }
+
+        let message = match (passed(&events), attempt) {
+            (true, 1) => return (events, blocks),
+            (true, _) => format!("The spec passed on attempt {attempt} of {attempts}"),
+            (false, _) if attempts > 1 => format!("The spec failed on all {attempts} attempts"),
+            (false, _) => return (events, blocks),
+        };
+        events.insert(1, RunEvent::Info(message));
+        (events, blocks)
+    }
+
+    fn spec_files_to_run(&self) -> Result<Vec<PathBuf>, Error> {
+        let Some(git_ref) = &self.changed_since else {
+            return Ok(self.spec_files.clone());
+        };
+
+        let changed = changed_files::list(&*self.executor, git_ref, self.file_reader.directory())?;
+
+        Ok(self
+            .spec_files
             .iter()
-            .flat_map(|spec_file| self.run_spec_file(spec_file))
-            .collect()
+            .filter(|spec_file| changed.contains(&self.file_reader.to_absolute(spec_file)))
+            .cloned()
+            .collect())
     }
 
     fn initialise_workspace(&self) {
         if let Some(command) = self.workspace_init_command.clone() {
             self.executor
-                .execute(&ScriptCode(command))
+                .execute(&ScriptCode(command), &ExecutionOptions::default())
                 .expect("Failed to initialise workspace");
         }
     }
 
-    fn run_spec_file(&self, spec_file: &Path) -> Vec<RunEvent> {
+    fn run_spec_file(&self, spec_file: &Path) -> (Vec<RunEvent>, Vec<BlockCoverage>) {
+        let files_before = self
+            .running_dir_checks
+            .assert_clean
+            .then(|| directory_listing::list_files(&self.working_dir));
+
         let mut state = State::new();
-        let mut runner = Runner::create(&*self.executor, &mut state);
+        for (name, value) in &self.variables {
+            state.set_variable(name, value);
+        }
+        self.add_path_masks(&mut state, spec_file);
+        if let Some(iterations) = self.bench {
+            state.set_bench_iterations(iterations);
+        }
+        state.set_forbids_unexpected_stderr(self.no_unexpected_stderr);
 
         let start_events = vec![RunEvent::SpecFileStarted(spec_file.to_path_buf())];
         let contents = self.file_reader.read_file(spec_file);
-        let run_events = parsers::parse(&contents)
-            .map_err(|err| Error::RunFailed {
-                message: err.to_string(),
+        let mut blocks = vec![];
+        let spec_path = self.file_reader.to_absolute(spec_file);
+        let spec_directory = spec_path.parent().unwrap_or_else(|| Path::new(""));
+        let spec = parsers::parse(&contents, spec_directory).map_err(|err| Error::RunFailed {
+            message: err.to_string(),
+        });
+        if let Ok(spec) = &spec {
+            apply_file_settings(&mut state, &spec.metadata);
+        }
+        let mut runner = Runner::create(&*self.executor, &mut state);
+        let run_events = spec
+            .and_then(|spec| {
+                let selected = tag_filter::selected(&spec.actions, &self.tags, &self.skip_tags);
+                let actions = tag_filter::keep(&spec.actions, &selected);
+                let sections = tag_filter::keep(&spec.sections, &selected);
+                let version_mismatch =
+                    version_check::check(&spec.metadata, &version_check::current_version());
+                let (warning, action_events) = match version_mismatch {
+                    Some(message)
+                        if self.on_version_mismatch == version_check::OnMismatch::Fail =>
+                    {
+                        blocks = coverage::build(
+                            spec_file,
+                            &spec.actions,
+                            &spec.sections,
+                            &selected,
+                            &[],
+                        );
+                        return Err(Error::RunFailed { message });
+                    }
+                    Some(message) => (Some(RunEvent::Warning(message)), runner.run(&actions)),
+                    None => (None, runner.run(&actions)),
+                };
+                blocks = coverage::build(
+                    spec_file,
+                    &spec.actions,
+                    &spec.sections,
+                    &selected,
+                    &action_events,
+                );
+                Ok(warning
+                    .into_iter()
+                    .chain(with_sections(action_events, &sections))
+                    .collect())
             })
-            .map(|action_list| runner.run(&action_list))
             .or_else::<Error, _>(|err| Ok(vec![RunEvent::ErrorOccurred(err)]))
             .unwrap();
+        let clean_events = files_before
+            .map(|files| self.check_clean(&files, &state))
+            .unwrap_or_default();
+        if !self.keep_script_workdirs {
+            remove_workdirs(&state);
+        }
         let end_events = vec![RunEvent::SpecFileCompleted {
-            success: state.is_success(),
+            success: state.is_success() && clean_events.is_empty(),
         }];
 
-        start_events
+        let events = start_events
+            .into_iter()
+            .chain(run_events)
+            .chain(clean_events)
+            .chain(end_events)
+            .collect();
+        (events, blocks)
+    }
+
+    fn add_path_masks(&self, state: &mut State, spec_file: &Path) {
+        if let Some(home_dir) = &self.home_dir {
+            state.add_path_mask("<HOME>", home_dir);
+        }
+
+        state.add_path_mask("<RUNNING_DIR>", &self.working_dir.display().to_string());
+
+        if let Some(spec_dir) = self.file_reader.to_absolute(spec_file).parent() {
+            state.add_path_mask("<SPEC_DIR>", &spec_dir.display().to_string());
+        }
+    }
+
+    fn check_clean(&self, files_before: &BTreeSet<PathBuf>, state: &State) -> Vec<RunEvent> {
+        let created_files: BTreeSet<PathBuf> = state
+            .created_files()
+            .iter()
+            .map(|path| self.relative_to_working_dir(Path::new(path)))
+            .collect();
+
+        let stray_files: Vec<String> = directory_listing::list_files(&self.working_dir)
             .into_iter()
-            .chain(run_events.into_iter())
-            .chain(end_events.into_iter())
+            .filter(|file| !files_before.contains(file) && !created_files.contains(file))
+            .map(|file| file.display().to_string())
+            .collect();
+
+        if stray_files.is_empty() {
+            vec![]
+        } else {
+            vec![RunEvent::ErrorOccurred(Error::RunFailed {
+                message: format!(
+                    "The spec left unexpected files in the running directory: {}",
+                    stray_files.join(", ")
+                ),
+            })]
+        }
+    }
+
+    fn relative_to_working_dir(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.working_dir)
+            .unwrap_or(path)
+            .components()
+            .filter(|component| *component != Component::CurDir)
             .collect()
     }
 
-    fn change_to_working_directory(&self) {
+    fn change_to_working_directory(&self) -> Result<(), Error> {
+        if self.running_dir_checks.require_empty {
+            let entries = directory_entries(&self.working_dir);
+            if !entries.is_empty() {
+                return Err(Error::RunningDirNotEmpty { entries });
+            }
+        }
+
         std::env::set_current_dir(&self.working_dir).expect("Failed to set running directory");
+        Ok(())
     }
 }
+
+fn directory_entries(dir: &Path) -> Vec<String> {
+    let mut entries: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+// Settings from the spec file's front matter take precedence over the command line
+fn apply_file_settings(state: &mut State, metadata: &Metadata) {
+    if let Some(shell_command) = &metadata.shell {
+        state.set_shell_command(shell_command);
+    }
+    for (name, value) in &metadata.env {
+        state.set_file_env(name, value);
+    }
+}
+
+// Each run of results from the same document section is preceded by the section's path
+fn with_sections(events: Vec<RunEvent>, sections: &[SectionPath]) -> Vec<RunEvent> {
+    let mut current = &SectionPath::default();
+    let mut with_sections = Vec::with_capacity(events.len());
+
+    for (event, section) in events.into_iter().zip(sections) {
+        if section != current {
+            with_sections.push(RunEvent::SectionStarted(section.clone()));
+            current = section;
+        }
+        with_sections.push(event);
+    }
+
+    with_sections
+}
+
+fn remove_workdirs(state: &State) {
+    for workdir in state.workdirs() {
+        if let Err(err) = fs::remove_dir_all(workdir) {
+            log::trace!("failed to remove {}: {err}", workdir.display());
+        }
+    }
+}
+
+fn passed(events: &[RunEvent]) -> bool {
+    events.iter().all(|event| match event {
+        RunEvent::SpecFileCompleted { success } => *success,
+        RunEvent::ErrorOccurred(_) => false,
+        _ => true,
+    })
+}
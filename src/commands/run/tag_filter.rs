@@ -0,0 +1,228 @@
+use crate::types::{Action, ScriptAction, ScriptName, VerifyAction};
+use std::collections::BTreeMap;
+
+pub fn keep<T: Clone>(items: &[T], selected: &[bool]) -> Vec<T> {
+    items
+        .iter()
+        .zip(selected)
+        .filter(|(_, selected)| **selected)
+        .map(|(item, _)| item.clone())
+        .collect()
+}
+
+// A script runs when it has one of `tags`, or `tags` is empty, and none of `skip_tags`
+pub fn selected(actions: &[Action], tags: &[String], skip_tags: &[String]) -> Vec<bool> {
+    if tags.is_empty() && skip_tags.is_empty() {
+        return vec![true; actions.len()];
+    }
+
+    let mut scripts: Vec<(Option<&ScriptName>, bool)> = vec![];
+
+    actions
+        .iter()
+        .map(|action| match action {
+            Action::Script(ScriptAction {
+                script_name,
+                options,
+                ..
+            }) => {
+                let selected = (tags.is_empty()
+                    || options.tags.iter().any(|tag| tags.contains(tag)))
+                    && !options.tags.iter().any(|tag| skip_tags.contains(tag));
+                scripts.push((script_name.as_ref(), selected));
+                selected
+            }
+            // A verify is dropped along with the script it checks
+            Action::Verify(VerifyAction { source, .. }) => {
+                let script = match &source.name {
+                    Some(name) => scripts
+                        .iter()
+                        .rev()
+                        .find(|(script_name, _)| *script_name == Some(name)),
+                    None => scripts.last(),
+                };
+                script.is_none_or(|(_, selected)| *selected)
+            }
+            Action::VerifyDirectory(_)
+            | Action::VerifyFile(_)
+            | Action::CreateFile(_)
+            | Action::Note(_)
+            | Action::SetEnv(_)
+            | Action::Stdin(_)
+            | Action::Skip(_) => true,
+        })
+        .collect()
+}
+
+// The number of scripts which have each tag, including tags inherited from the file
+pub fn count<'a>(actions: impl IntoIterator<Item = &'a Action>) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+
+    for action in actions {
+        if let Action::Script(ScriptAction { options, .. }) = action {
+            for tag in &options.tags {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count, keep, selected};
+    use crate::parsers;
+    use crate::types::{
+        Action, CreateFileAction, CreateFileOptions, FileContent, FilePath, OutputExpectation,
+        ScriptAction, ScriptCode, ScriptName, ScriptOptions, Source, Stream, VerifyAction,
+        VerifyOptions, VerifyValue,
+    };
+    use std::path::Path;
+
+    fn script(name: &str, tags: &[&str]) -> Action {
+        Action::Script(ScriptAction {
+            script_name: Some(ScriptName(name.to_string())),
+            script_code: ScriptCode("true".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions {
+                tags: tags.iter().map(ToString::to_string).collect(),
+                ..ScriptOptions::default()
+            },
+        })
+    }
+
+    fn verify(name: Option<&str>) -> Action {
+        Action::Verify(VerifyAction {
+            source: Source {
+                name: name.map(|name| ScriptName(name.to_string())),
+                stream: Stream::StdOut,
+            },
+            expected_value: VerifyValue(String::new()),
+            options: VerifyOptions::default(),
+        })
+    }
+
+    fn create_file() -> Action {
+        Action::CreateFile(CreateFileAction {
+            file_path: FilePath("input.txt".to_string()),
+            file_content: FileContent(String::new()),
+            options: CreateFileOptions::default(),
+        })
+    }
+
+    fn filter(actions: &[Action], tags: &[String]) -> Vec<Action> {
+        keep(actions, &selected(actions, tags, &[]))
+    }
+
+    fn filter_skipping(actions: &[Action], tags: &[String], skip_tags: &[String]) -> Vec<Action> {
+        keep(actions, &selected(actions, tags, skip_tags))
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn keeps_every_action_when_no_tags_are_given() {
+        let actions = vec![script("build", &[]), verify(None)];
+
+        assert_eq!(filter(&actions, &[]), actions);
+    }
+
+    #[test]
+    fn keeps_only_scripts_with_one_of_the_tags_and_their_verifies() {
+        let actions = vec![
+            create_file(),
+            script("build", &["smoke"]),
+            verify(None),
+            script("migrate", &["db"]),
+            verify(None),
+            verify(Some("build")),
+            script("deploy", &["slow"]),
+            verify(Some("deploy")),
+        ];
+
+        assert_eq!(
+            filter(&actions, &tags(&["smoke", "db"])),
+            vec![
+                create_file(),
+                script("build", &["smoke"]),
+                verify(None),
+                script("migrate", &["db"]),
+                verify(None),
+                verify(Some("build")),
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_scripts_with_one_of_the_skipped_tags_and_their_verifies() {
+        let actions = vec![
+            script("build", &[]),
+            verify(None),
+            script("download", &["network"]),
+            verify(None),
+            script("deploy", &["slow", "network"]),
+            verify(Some("deploy")),
+        ];
+
+        assert_eq!(
+            filter_skipping(&actions, &[], &tags(&["network"])),
+            vec![script("build", &[]), verify(None)]
+        );
+    }
+
+    #[test]
+    fn skipped_tags_take_precedence_over_selected_tags() {
+        let actions = vec![
+            script("unit", &["fast"]),
+            script("integration", &["fast", "network"]),
+            script("deploy", &["slow"]),
+        ];
+
+        assert_eq!(
+            filter_skipping(&actions, &tags(&["fast"]), &tags(&["network"])),
+            vec![script("unit", &["fast"])]
+        );
+    }
+
+    #[test]
+    fn counts_the_scripts_with_each_tag() {
+        let actions = vec![
+            script("unit", &["fast"]),
+            verify(None),
+            script("integration", &["slow", "db"]),
+            script("migrate", &["db"]),
+            create_file(),
+        ];
+
+        assert_eq!(
+            count(&actions).into_iter().collect::<Vec<_>>(),
+            vec![
+                ("db".to_string(), 2),
+                ("fast".to_string(), 1),
+                ("slow".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_file_level_tag_selects_every_script_and_a_block_tag_adds_to_it() {
+        let spec = parsers::parse(
+            "---\ntags: smoke\n---\n\n\
+             ```shell,script(name=\"build\")\nmake\n```\n\n\
+             ```shell,script(name=\"deploy\", tags=[\"slow\"])\nmake deploy\n```\n",
+            Path::new("."),
+        )
+        .expect("the spec to parse");
+
+        assert_eq!(filter(&spec.actions, &tags(&["smoke"])), spec.actions);
+        assert_eq!(
+            filter(&spec.actions, &tags(&["slow"])),
+            vec![spec.actions[1].clone()]
+        );
+        assert_eq!(filter(&spec.actions, &tags(&["db"])), vec![]);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::parsers;
+use crate::types::{Metadata, Version};
+
+// What happens to a spec which requires a newer version of specdown
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OnMismatch {
+    Warn,
+    Fail,
+}
+
+pub fn current_version() -> Version {
+    parsers::parse_version(env!("CARGO_PKG_VERSION")).expect("Failed to parse the package version")
+}
+
+pub fn check(metadata: &Metadata, current: &Version) -> Option<String> {
+    match &metadata.min_specdown_version {
+        Some(required) if required > current => Some(format!(
+            "This spec requires specdown {required} or later, but this is version {current}"
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Metadata, Version};
+
+    const CURRENT: Version = Version::new(1, 2, 3);
+
+    fn requiring(major: u32, minor: u32, patch: u32) -> Metadata {
+        Metadata {
+            min_specdown_version: Some(Version::new(major, minor, patch)),
+            ..Metadata::default()
+        }
+    }
+
+    #[test]
+    fn succeeds_when_no_version_is_declared() {
+        assert_eq!(check(&Metadata::default(), &CURRENT), None);
+    }
+
+    #[test]
+    fn succeeds_when_the_declared_version_is_older_or_equal() {
+        assert_eq!(check(&requiring(1, 2, 3), &CURRENT), None);
+        assert_eq!(check(&requiring(1, 1, 9), &CURRENT), None);
+        assert_eq!(check(&requiring(0, 9, 9), &CURRENT), None);
+    }
+
+    #[test]
+    fn fails_when_the_declared_version_is_newer() {
+        assert_eq!(
+            check(&requiring(1, 10, 0), &CURRENT),
+            Some(
+                "This spec requires specdown 1.10.0 or later, but this is version 1.2.3"
+                    .to_string()
+            )
+        );
+    }
+}
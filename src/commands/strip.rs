@@ -8,10 +8,24 @@ pub struct Arguments {
     /// The spec file to strip specdown functions from
     #[clap()]
     spec_file: PathBuf,
+
+    /// Rename a code block language in the output (format: 'FROM=TO')
+    #[clap(long, value_parser = parse_language_mapping)]
+    map_language: Vec<(String, String)>,
 }
 
 pub fn execute(args: &Arguments) {
     let contents = fs::read_to_string(&args.spec_file).expect("failed to read spec file");
-    let stripped = parsers::strip(&contents);
+    let language_map = args.map_language.iter().cloned().collect();
+    let stripped = parsers::strip(&contents, &language_map);
     println!("{stripped}");
 }
+
+fn parse_language_mapping(mapping: &str) -> Result<(String, String), String> {
+    match mapping.split_once('=') {
+        Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+            Ok((from.to_string(), to.to_string()))
+        }
+        _ => Err("expected the format 'FROM=TO'".to_string()),
+    }
+}
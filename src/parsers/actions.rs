@@ -1,24 +1,220 @@
-use crate::parsers::code_block_type::{CodeBlockType, ScriptCodeBlock, VerifyCodeBlock};
+use crate::parsers::code_block_type::{
+    CodeBlockType, CreateFileCodeBlock, ScriptCodeBlock, SetEnvCodeBlock, VerifyCodeBlock,
+    VerifyFileCodeBlock,
+};
 use crate::types::{
-    Action, CreateFileAction, FileContent, ScriptAction, ScriptCode, TargetOs, VerifyAction,
-    VerifyValue,
+    Action, CreateFileAction, DirectoryExpectation, FileContent, NoteAction, ScriptAction,
+    ScriptCode, ScriptName, ScriptPhase, SetEnvAction, SkipAction, Source, StdinAction, Stream,
+    VerifyAction, VerifyDirectoryAction, VerifyFileAction, VerifyOptions, VerifyValue,
 };
-use std::env::consts::OS;
+use std::collections::{HashMap, HashSet};
+
+// A script with `expected_text` is followed by an implicit verify of its stdout
+pub fn create_actions(code_block_type: &CodeBlockType, literal: String) -> Vec<Action> {
+    let action = create_action(code_block_type, literal);
 
-pub fn create_action(code_block_type: &CodeBlockType, literal: String) -> Option<Action> {
+    match code_block_type {
+        CodeBlockType::Script(ScriptCodeBlock {
+            script_name,
+            expected_text: Some(output),
+            ..
+        }) => vec![
+            action,
+            Action::Verify(VerifyAction {
+                source: Source {
+                    name: script_name.clone(),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue(output.clone()),
+                options: VerifyOptions::default(),
+            }),
+        ],
+        _ => vec![action],
+    }
+}
+
+pub fn create_action(code_block_type: &CodeBlockType, literal: String) -> Action {
     match code_block_type {
         CodeBlockType::Script(script_code_block) => {
-            Some(Action::Script(to_script_action(script_code_block, literal)))
+            Action::Script(to_script_action(script_code_block, literal))
         }
-        CodeBlockType::Verify(verify_code_block) => {
-            to_verify_action(verify_code_block, literal).map(Action::Verify)
+        CodeBlockType::Verify(VerifyCodeBlock { source, options }) => {
+            Action::Verify(VerifyAction {
+                source: source.clone(),
+                expected_value: VerifyValue(literal),
+                options: options.clone(),
+            })
         }
-        CodeBlockType::CreateFile(ref file_path) => Some(Action::CreateFile(CreateFileAction {
+        CodeBlockType::VerifyDirectory(action) => Action::VerifyDirectory(action.clone()),
+        CodeBlockType::VerifyTree(directory) => Action::VerifyDirectory(VerifyDirectoryAction {
+            directory: directory.clone(),
+            expected: DirectoryExpectation::Tree(
+                literal
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from)
+                    .collect(),
+            ),
+        }),
+        CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+            file_path,
+            mode,
+            exists,
+            options,
+        }) => Action::VerifyFile(VerifyFileAction {
             file_path: file_path.clone(),
-            file_content: FileContent(literal),
-        })),
-        CodeBlockType::Skip() => None,
+            expected_value: VerifyValue(literal),
+            mode: *mode,
+            exists: *exists,
+            options: options.clone(),
+        }),
+        CodeBlockType::CreateFile(CreateFileCodeBlock { file_path, options }) => {
+            Action::CreateFile(CreateFileAction {
+                file_path: file_path.clone(),
+                file_content: FileContent(literal),
+                options: options.clone(),
+            })
+        }
+        CodeBlockType::Note(text) => Action::Note(NoteAction {
+            text: text
+                .clone()
+                .unwrap_or_else(|| literal.trim_end().to_string()),
+        }),
+        CodeBlockType::SetEnv(SetEnvCodeBlock {
+            variable,
+            unset,
+            reset,
+        }) => Action::SetEnv(SetEnvAction {
+            variables: variable
+                .clone()
+                .map_or_else(|| to_env_variables(&literal), |variable| vec![variable]),
+            unset: unset.clone(),
+            reset: *reset,
+        }),
+        CodeBlockType::Stdin(script_name) => Action::Stdin(StdinAction {
+            script_name: script_name.clone(),
+            input: literal,
+        }),
+        CodeBlockType::Skip(reason) => Action::Skip(SkipAction {
+            reason: reason.clone(),
+        }),
+        CodeBlockType::Include(_) => {
+            unreachable!("includes are expanded before actions are created")
+        }
+    }
+}
+
+// Each non-blank line is `NAME=value`; a line without `=` sets NAME to an empty value
+fn to_env_variables(literal: &str) -> Vec<(String, String)> {
+    literal
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line.split_once('=').unwrap_or((line, ""));
+            (name.trim_end().to_string(), value.to_string())
+        })
+        .collect()
+}
+
+pub fn group_alternatives<T: AsMut<Action> + AsRef<Action>>(actions: Vec<T>) -> Vec<T> {
+    let mut grouped: Vec<T> = Vec::with_capacity(actions.len());
+
+    for action in actions {
+        if let (Some(Action::Verify(previous)), Action::Verify(alternative)) =
+            (grouped.last_mut().map(AsMut::as_mut), action.as_ref())
+        {
+            if previous.options.any_of
+                && alternative.options.any_of
+                && previous.source == alternative.source
+            {
+                previous
+                    .options
+                    .alternatives
+                    .push(alternative.expected_value.clone());
+                continue;
+            }
+        }
+        grouped.push(action);
     }
+
+    grouped
+}
+
+// Cleanup scripts keep their order but are moved after every other action
+pub fn move_cleanup_to_end<T: AsRef<Action>>(actions: Vec<T>) -> Vec<T> {
+    let (cleanup, mut actions): (Vec<_>, Vec<_>) =
+        actions.into_iter().partition(|action| {
+            matches!(action.as_ref(), Action::Script(script) if script.options.phase == ScriptPhase::Cleanup)
+        });
+    actions.extend(cleanup);
+    actions
+}
+
+pub fn inherit_tags<T: AsMut<Action>>(mut actions: Vec<T>, file_tags: &[String]) -> Vec<T> {
+    if file_tags.is_empty() {
+        return actions;
+    }
+
+    for action in &mut actions {
+        if let Action::Script(script) = action.as_mut() {
+            let block_tags = std::mem::take(&mut script.options.tags);
+            script.options.tags = file_tags.to_vec();
+            for tag in block_tags {
+                if !script.options.tags.contains(&tag) {
+                    script.options.tags.push(tag);
+                }
+            }
+        }
+    }
+
+    actions
+}
+
+// Names each unnamed script after the heading above it, e.g. `installing-1`
+pub fn name_scripts(actions: Vec<(Option<&str>, Action)>) -> Vec<Action> {
+    let mut used: HashSet<String> = actions
+        .iter()
+        .filter_map(|(_, action)| match action {
+            Action::Script(ScriptAction {
+                script_name: Some(name),
+                ..
+            }) => Some(String::from(name.clone())),
+            _ => None,
+        })
+        .collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    actions
+        .into_iter()
+        .map(|(heading, action)| match action {
+            Action::Script(mut script) if script.script_name.is_none() => {
+                let prefix = heading.map(slug).filter(|slug| !slug.is_empty());
+                let prefix = prefix.unwrap_or_else(|| "script".to_string());
+                let count = counts.entry(prefix.clone()).or_insert(0);
+                let name = loop {
+                    *count += 1;
+                    let name = format!("{prefix}-{count}");
+                    if used.insert(name.clone()) {
+                        break name;
+                    }
+                };
+                script.script_name = Some(ScriptName(name));
+                Action::Script(script)
+            }
+            action => action,
+        })
+        .collect()
+}
+
+fn slug(heading: &str) -> String {
+    heading
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
 fn to_script_action(code_block: &ScriptCodeBlock, literal: String) -> ScriptAction {
@@ -26,50 +222,31 @@ fn to_script_action(code_block: &ScriptCodeBlock, literal: String) -> ScriptActi
         script_name,
         expected_exit_code,
         expected_output,
+        options,
+        ..
     } = code_block;
 
     ScriptAction {
         script_name: script_name.clone(),
         script_code: ScriptCode(literal),
-        expected_exit_code: *expected_exit_code,
+        expected_exit_code: expected_exit_code.clone(),
         expected_output: expected_output.clone(),
+        options: options.clone(),
     }
 }
 
-fn to_verify_action(
-    VerifyCodeBlock { source, target_os }: &VerifyCodeBlock,
-    literal: String,
-) -> Option<VerifyAction> {
-    match target_os {
-        None => Some(VerifyAction {
-            source: source.clone(),
-            expected_value: VerifyValue(literal),
-        }),
-        Some(TargetOs(ref value)) if target_os_matches_current(value) => Some(VerifyAction {
-            source: source.clone(),
-            expected_value: VerifyValue(literal),
-        }),
-        Some(_) => None,
-    }
-}
-
-fn target_os_matches_current(target_os: &str) -> bool {
-    if target_os == OS {
-        return true;
-    }
-
-    target_os.starts_with('!') && target_os != format!("!{OS}")
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
-        create_action, Action, CodeBlockType, FileContent, ScriptCode, ScriptCodeBlock, VerifyValue,
+        create_action, create_actions, group_alternatives, inherit_tags, move_cleanup_to_end,
+        name_scripts, Action, CodeBlockType, DirectoryExpectation, FileContent, NoteAction,
+        ScriptCode, ScriptCodeBlock, ScriptPhase, SetEnvAction, SkipAction, StdinAction,
+        VerifyDirectoryAction, VerifyValue,
     };
-    use crate::parsers::code_block_type::VerifyCodeBlock;
+    use crate::parsers::code_block_type::{CreateFileCodeBlock, SetEnvCodeBlock, VerifyCodeBlock};
     use crate::types::{
-        CreateFileAction, FilePath, OutputExpectation, ScriptAction, ScriptName, Source, Stream,
-        TargetOs, VerifyAction,
+        CreateFileAction, CreateFileOptions, FilePath, OutputExpectation, ScriptAction, ScriptName,
+        ScriptOptions, Source, Stream, VerifyAction, VerifyOptions,
     };
 
     #[test]
@@ -80,15 +257,50 @@ mod tests {
                     script_name: Some(ScriptName("script-name".to_string())),
                     expected_exit_code: None,
                     expected_output: OutputExpectation::Any,
+                    expected_text: None,
+                    options: ScriptOptions::default(),
                 }),
                 "code".to_string(),
             ),
-            Some(Action::Script(ScriptAction {
+            Action::Script(ScriptAction {
                 script_name: Some(ScriptName("script-name".to_string())),
                 script_code: ScriptCode("code".to_string()),
                 expected_exit_code: None,
                 expected_output: OutputExpectation::Any,
-            }))
+                options: ScriptOptions::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn create_actions_adds_a_verify_for_inline_expected_output() {
+        let script = ScriptCodeBlock {
+            script_name: Some(ScriptName("greet".to_string())),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            expected_text: Some("hello\n".to_string()),
+            options: ScriptOptions::default(),
+        };
+
+        assert_eq!(
+            create_actions(&CodeBlockType::Script(script), "echo hello".to_string()),
+            vec![
+                Action::Script(ScriptAction {
+                    script_name: Some(ScriptName("greet".to_string())),
+                    script_code: ScriptCode("echo hello".to_string()),
+                    expected_exit_code: None,
+                    expected_output: OutputExpectation::Any,
+                    options: ScriptOptions::default(),
+                }),
+                Action::Verify(VerifyAction {
+                    source: Source {
+                        name: Some(ScriptName("greet".to_string())),
+                        stream: Stream::StdOut,
+                    },
+                    expected_value: VerifyValue("hello\n".to_string()),
+                    options: VerifyOptions::default(),
+                }),
+            ]
         );
     }
 
@@ -101,79 +313,322 @@ mod tests {
                         name: Some(ScriptName("script-name".to_string())),
                         stream: Stream::StdOut,
                     },
-                    target_os: None,
+                    options: VerifyOptions::default(),
                 }),
                 "value".to_string(),
             ),
-            Some(Action::Verify(VerifyAction {
+            Action::Verify(VerifyAction {
                 source: Source {
                     name: Some(ScriptName("script-name".to_string())),
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue("value".to_string()),
-            }))
+                options: VerifyOptions::default(),
+            })
         );
     }
 
     #[test]
-    fn create_action_for_verify_that_is_skipped() {
+    fn create_action_for_file() {
         assert_eq!(
             create_action(
-                &CodeBlockType::Verify(VerifyCodeBlock {
-                    source: Source {
-                        name: Some(ScriptName("script-name".to_string())),
-                        stream: Stream::StdOut,
-                    },
-                    target_os: Some(TargetOs("fake-os".to_string())),
+                &CodeBlockType::CreateFile(CreateFileCodeBlock {
+                    file_path: FilePath("file.txt".to_string()),
+                    options: CreateFileOptions::default(),
                 }),
-                "value".to_string(),
+                "content".to_string(),
             ),
-            None
+            Action::CreateFile(CreateFileAction {
+                file_path: FilePath("file.txt".to_string()),
+                file_content: FileContent("content".to_string()),
+                options: CreateFileOptions::default(),
+            })
         );
     }
 
     #[test]
-    fn create_action_for_verify_that_is_negated() {
+    fn create_action_for_note_with_text() {
         assert_eq!(
             create_action(
-                &CodeBlockType::Verify(VerifyCodeBlock {
-                    source: Source {
-                        name: Some(ScriptName("script-name".to_string())),
-                        stream: Stream::StdOut,
-                    },
-                    target_os: Some(TargetOs("!fake-os".to_string())),
+                &CodeBlockType::Note(Some("a note".to_string())),
+                "content".to_string(),
+            ),
+            Action::Note(NoteAction {
+                text: "a note".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_note_without_text_uses_the_content() {
+        assert_eq!(
+            create_action(&CodeBlockType::Note(None), "content\n".to_string()),
+            Action::Note(NoteAction {
+                text: "content".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_verify_tree_lists_the_non_blank_lines() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::VerifyTree(FilePath("dist".to_string())),
+                "index.html\n\n  assets/*.js\n".to_string(),
+            ),
+            Action::VerifyDirectory(VerifyDirectoryAction {
+                directory: FilePath("dist".to_string()),
+                expected: DirectoryExpectation::Tree(vec![
+                    "index.html".to_string(),
+                    "assets/*.js".to_string()
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_stdin_keeps_the_content_as_the_input() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::Stdin(ScriptName("sort".to_string())),
+                "b\na\n".to_string(),
+            ),
+            Action::Stdin(StdinAction {
+                script_name: ScriptName("sort".to_string()),
+                input: "b\na\n".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_env_with_a_name_and_value() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::SetEnv(SetEnvCodeBlock {
+                    variable: Some(("GREETING".to_string(), "hello".to_string())),
+                    ..SetEnvCodeBlock::default()
                 }),
-                "value".to_string(),
+                "ignored".to_string(),
             ),
-            Some(Action::Verify(VerifyAction {
+            Action::SetEnv(SetEnvAction {
+                variables: vec![("GREETING".to_string(), "hello".to_string())],
+                ..SetEnvAction::default()
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_env_reads_variables_from_the_content() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::SetEnv(SetEnvCodeBlock::default()),
+                "GREETING=hello world\n\nURL=http://x/?a=b\nEMPTY\n".to_string(),
+            ),
+            Action::SetEnv(SetEnvAction {
+                variables: vec![
+                    ("GREETING".to_string(), "hello world".to_string()),
+                    ("URL".to_string(), "http://x/?a=b".to_string()),
+                    ("EMPTY".to_string(), String::new()),
+                ],
+                ..SetEnvAction::default()
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_env_with_unset_and_reset() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::SetEnv(SetEnvCodeBlock {
+                    variable: None,
+                    unset: vec!["GREETING".to_string()],
+                    reset: true,
+                }),
+                String::new(),
+            ),
+            Action::SetEnv(SetEnvAction {
+                variables: vec![],
+                unset: vec!["GREETING".to_string()],
+                reset: true,
+            })
+        );
+    }
+
+    #[test]
+    fn create_action_for_skip() {
+        assert_eq!(
+            create_action(
+                &CodeBlockType::Skip(Some("needs network access".to_string())),
+                "content".to_string()
+            ),
+            Action::Skip(SkipAction {
+                reason: Some("needs network access".to_string()),
+            })
+        );
+    }
+
+    fn verify(name: &str, value: &str, any_of: bool) -> Action {
+        Action::Verify(VerifyAction {
+            source: Source {
+                name: Some(ScriptName(name.to_string())),
+                stream: Stream::StdOut,
+            },
+            expected_value: VerifyValue(value.to_string()),
+            options: VerifyOptions {
+                any_of,
+                ..VerifyOptions::default()
+            },
+        })
+    }
+
+    #[test]
+    fn group_alternatives_combines_consecutive_any_of_verifies_for_the_same_source() {
+        assert_eq!(
+            group_alternatives(vec![
+                verify("script-name", "one", true),
+                verify("script-name", "two", true),
+                verify("script-name", "three", true),
+            ]),
+            vec![Action::Verify(VerifyAction {
                 source: Source {
                     name: Some(ScriptName("script-name".to_string())),
                     stream: Stream::StdOut,
                 },
-                expected_value: VerifyValue("value".to_string()),
-            }))
+                expected_value: VerifyValue("one".to_string()),
+                options: VerifyOptions {
+                    any_of: true,
+                    alternatives: vec![
+                        VerifyValue("two".to_string()),
+                        VerifyValue("three".to_string())
+                    ],
+                    ..VerifyOptions::default()
+                },
+            })]
         );
     }
 
     #[test]
-    fn create_action_for_file() {
+    fn group_alternatives_does_not_combine_verifies_which_are_not_alternatives() {
+        let actions = || {
+            vec![
+                verify("script-name", "one", false),
+                verify("script-name", "two", true),
+                verify("other-script", "three", true),
+                verify("other-script", "four", false),
+            ]
+        };
+
+        assert_eq!(group_alternatives(actions()), actions());
+    }
+
+    fn tagged_script(tags: &[&str]) -> Action {
+        Action::Script(ScriptAction {
+            script_name: Some(ScriptName("script-name".to_string())),
+            script_code: ScriptCode("code".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions {
+                tags: tags.iter().map(ToString::to_string).collect(),
+                ..ScriptOptions::default()
+            },
+        })
+    }
+
+    #[test]
+    fn inherit_tags_adds_the_file_tags_to_every_script() {
+        let file_tags = vec!["smoke".to_string()];
+
         assert_eq!(
-            create_action(
-                &CodeBlockType::CreateFile(FilePath("file.txt".to_string())),
-                "content".to_string(),
+            inherit_tags(
+                vec![
+                    tagged_script(&[]),
+                    verify("script-name", "one", false),
+                    tagged_script(&["slow", "smoke"]),
+                ],
+                &file_tags
             ),
-            Some(Action::CreateFile(CreateFileAction {
-                file_path: FilePath("file.txt".to_string()),
-                file_content: FileContent("content".to_string()),
-            }))
+            vec![
+                tagged_script(&["smoke"]),
+                verify("script-name", "one", false),
+                tagged_script(&["smoke", "slow"]),
+            ]
         );
     }
 
+    fn cleanup_script(name: &str) -> Action {
+        Action::Script(ScriptAction {
+            script_name: Some(ScriptName(name.to_string())),
+            script_code: ScriptCode(String::new()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions {
+                phase: ScriptPhase::Cleanup,
+                ..ScriptOptions::default()
+            },
+        })
+    }
+
     #[test]
-    fn create_action_for_skip() {
+    fn move_cleanup_to_end_runs_cleanup_scripts_last_in_their_original_order() {
+        assert_eq!(
+            move_cleanup_to_end(vec![
+                cleanup_script("stop-container"),
+                script(Some("start")),
+                cleanup_script("remove-volume"),
+                verify("start", "one", false),
+            ]),
+            vec![
+                script(Some("start")),
+                verify("start", "one", false),
+                cleanup_script("stop-container"),
+                cleanup_script("remove-volume"),
+            ]
+        );
+    }
+
+    fn script(name: Option<&str>) -> Action {
+        Action::Script(ScriptAction {
+            script_name: name.map(|name| ScriptName(name.to_string())),
+            script_code: ScriptCode(String::new()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        })
+    }
+
+    #[test]
+    fn name_scripts_names_unnamed_scripts_after_their_heading() {
+        assert_eq!(
+            name_scripts(vec![
+                (None, script(None)),
+                (Some("Installing the CLI"), script(None)),
+                (Some("Installing the CLI"), script(Some("build"))),
+                (Some("Installing the CLI"), script(None)),
+                (Some("Running `specdown`!"), script(None)),
+            ]),
+            vec![
+                script(Some("script-1")),
+                script(Some("installing-the-cli-1")),
+                script(Some("build")),
+                script(Some("installing-the-cli-2")),
+                script(Some("running-specdown-1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn name_scripts_skips_names_which_are_already_used() {
         assert_eq!(
-            create_action(&CodeBlockType::Skip(), "content".to_string()),
-            None
+            name_scripts(vec![
+                (Some("Setup"), script(None)),
+                (Some("Setup"), script(Some("setup-2"))),
+                (Some("Setup"), script(None)),
+            ]),
+            vec![
+                script(Some("setup-1")),
+                script(Some("setup-2")),
+                script(Some("setup-3")),
+            ]
         );
     }
 }
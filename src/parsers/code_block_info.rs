@@ -6,8 +6,15 @@ use super::error::Result;
 use super::markdown::code_block_info::CodeBlockInfo;
 use crate::parsers::markdown::code_block_info;
 
-pub fn parse(input: &str) -> Result<CodeBlockInfo<CodeBlockType>> {
-    match code_block_info::parse(code_block_type::parse)(input) {
+pub fn parse(input: &str) -> Result<CodeBlockInfo<Vec<CodeBlockType>>> {
+    match from_attributes(input) {
+        Some(function_syntax) => parse_function_syntax(&function_syntax),
+        None => parse_function_syntax(input),
+    }
+}
+
+fn parse_function_syntax(input: &str) -> Result<CodeBlockInfo<Vec<CodeBlockType>>> {
+    match code_block_info::parse(code_block_type::parse_list)(input) {
         Ok((_, result)) => Ok(result),
         Err(err) => match err {
             Err::Incomplete(_) => panic!("code_block_info parser returned an Incomplete error"),
@@ -16,6 +23,61 @@ pub fn parse(input: &str) -> Result<CodeBlockInfo<CodeBlockType>> {
     }
 }
 
+// Rewrites `shell {specdown=script name="build"}` as `shell,script(name="build")`.
+// Attributes without a value, such as classes and ids, are ignored.
+fn from_attributes(input: &str) -> Option<String> {
+    let (language, attributes) = input.trim_end().strip_suffix('}')?.split_once('{')?;
+
+    let mut function = None;
+    let mut arguments = vec![];
+    for attribute in split_attributes(attributes) {
+        match attribute.split_once('=') {
+            Some(("specdown", name)) => function = Some(name.trim_matches('"')),
+            Some(_) => arguments.push(attribute),
+            None => {}
+        }
+    }
+
+    Some(format!(
+        "{},{}({})",
+        language.trim(),
+        function?,
+        arguments.join(", ")
+    ))
+}
+
+// Splits on whitespace which isn't inside a quoted string or a list
+fn split_attributes(attributes: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut depth = 0;
+
+    for (index, c) in attributes.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '[' if !quoted => depth += 1,
+            ']' if !quoted => depth -= 1,
+            c if c.is_whitespace() && !quoted && depth == 0 => {
+                if let Some(start) = start.take() {
+                    parts.push(&attributes[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+
+    if let Some(start) = start {
+        parts.push(&attributes[start..]);
+    }
+    parts
+}
+
 #[cfg(test)]
 mod tests {
     use super::{parse, CodeBlockInfo, CodeBlockType};
@@ -26,7 +88,12 @@ mod tests {
         mod script {
             use super::{parse, CodeBlockInfo, CodeBlockType};
             use crate::parsers::code_block_type::ScriptCodeBlock;
-            use crate::types::{ExitCode, OutputExpectation, ScriptName};
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+            use crate::types::{
+                ExitCode, ExpectedExitCode, FilePath, OutputExpectation, ScriptName, ScriptOptions,
+                ScriptPhase, ScriptWorkdir, TargetOs,
+            };
 
             #[test]
             fn succeeds_when_function_is_script_with_a_name() {
@@ -35,11 +102,13 @@ mod tests {
                     result,
                     Ok(CodeBlockInfo {
                         language: "shell".to_string(),
-                        extra: CodeBlockType::Script(ScriptCodeBlock {
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
                             script_name: Some(ScriptName("example-script".to_string())),
                             expected_exit_code: None,
                             expected_output: OutputExpectation::Any,
-                        }),
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
                     })
                 );
             }
@@ -51,11 +120,13 @@ mod tests {
                     result,
                     Ok(CodeBlockInfo {
                         language: "shell".to_string(),
-                        extra: CodeBlockType::Script(ScriptCodeBlock {
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
                             script_name: None,
                             expected_exit_code: None,
                             expected_output: OutputExpectation::Any,
-                        }),
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
                     })
                 );
             }
@@ -67,240 +138,1999 @@ mod tests {
                     result,
                     Ok(CodeBlockInfo {
                         language: "shell".to_string(),
-                        extra: CodeBlockType::Script(ScriptCodeBlock {
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
                             script_name: Some(ScriptName("example-script".to_string())),
-                            expected_exit_code: Some(ExitCode(2)),
+                            expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(2))),
                             expected_output: OutputExpectation::Any,
-                        }),
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn succeeds_when_function_is_script_with_expected_output_set_to_any() {
-                let result = parse("shell,script(name=\"example-script\", expected_output=any)");
+            fn succeeds_when_function_is_script_with_any_expected_exit_code() {
+                let result = parse("shell,script(name=\"example-script\", expected_exit_code=any)");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
                         language: "shell".to_string(),
-                        extra: CodeBlockType::Script(ScriptCodeBlock {
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
                             script_name: Some(ScriptName("example-script".to_string())),
                             expected_exit_code: None,
                             expected_output: OutputExpectation::Any,
-                        }),
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn succeeds_when_function_is_script_with_expected_output_set_to_stdout() {
-                let result = parse("shell,script(name=\"example-script\", expected_output=stdout)");
+            fn fails_when_expected_exit_code_is_an_unknown_token() {
+                let result =
+                    parse("shell,script(name=\"example-script\", expected_exit_code=some)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "script".to_string(),
+                        argument: "expected_exit_code".to_string(),
+                        expected: "an integer, a range such as \"1..5\", a list of integers or any"
+                            .to_string(),
+                        got: "some".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_script_with_a_range_of_expected_exit_codes() {
+                let result = parse("shell,script(expected_exit_code=\"1..5\")");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
                         language: "shell".to_string(),
-                        extra: CodeBlockType::Script(ScriptCodeBlock {
-                            script_name: Some(ScriptName("example-script".to_string())),
-                            expected_exit_code: None,
-                            expected_output: OutputExpectation::StdOut,
-                        }),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: Some(ExpectedExitCode::Range {
+                                from: ExitCode(1),
+                                to: ExitCode(5),
+                            }),
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
                     })
                 );
             }
-        }
 
-        mod verify {
-            use crate::parsers::code_block_type::VerifyCodeBlock;
-            use crate::parsers::error::Error;
-            use crate::types::{ScriptName, Source, Stream, TargetOs};
+            #[test]
+            fn fails_when_the_expected_exit_code_range_is_backwards() {
+                let result = parse("shell,script(expected_exit_code=\"5..1\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "script".to_string(),
+                        argument: "expected_exit_code".to_string(),
+                        expected: "an integer, a range such as \"1..5\", a list of integers or any"
+                            .to_string(),
+                        got: "5..1".to_string(),
+                    })
+                );
+            }
 
-            use super::{parse, CodeBlockInfo, CodeBlockType};
+            #[test]
+            fn succeeds_when_function_is_script_with_a_list_of_expected_exit_codes() {
+                let result = parse("shell,script(expected_exit_code=[1, 2, 4])");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: Some(ExpectedExitCode::OneOf(vec![
+                                ExitCode(1),
+                                ExitCode(2),
+                                ExitCode(4),
+                            ])),
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
+                    })
+                );
+            }
 
             #[test]
-            fn succeeds_when_function_is_verify_and_stream_is_stdout() {
-                let result = parse(",verify(script_name=\"example-script\", stream=stdout)");
+            fn succeeds_when_function_is_script_with_idempotent_and_changed_marker() {
+                let result = parse(
+                    "shell,script(name=\"example-script\", idempotent=true, changed_marker=\"changed\")",
+                );
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: String::new(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: Some(ScriptName("example-script".to_string())),
-                                stream: Stream::StdOut,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                idempotent: true,
+                                changed_marker: Some("changed".to_string()),
+                                ..ScriptOptions::default()
                             },
-                            target_os: None,
-                        }),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn succeeds_when_function_is_verify_and_stream_is_stderr() {
-                let result = parse(",verify(script_name=\"example-script\", stream=stderr)");
+            fn succeeds_when_function_is_script_with_tee() {
+                let result = parse("shell,script(name=\"example-script\", tee=\"logs/x.out\")");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: String::new(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: Some(ScriptName("example-script".to_string())),
-                                stream: Stream::StdErr,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                tee: Some(FilePath("logs/x.out".to_string())),
+                                ..ScriptOptions::default()
                             },
-                            target_os: None,
-                        }),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn succeeds_and_defaults_to_stdout_when_the_stream_is_missing() {
-                let result = parse(",verify(script_name=\"the-script\")");
+            fn succeeds_when_function_is_script_with_a_temporary_workdir() {
+                let result = parse("shell,script(name=\"example-script\", workdir=temp)");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: String::new(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: Some(ScriptName("the-script".to_string())),
-                                stream: Stream::StdOut,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                workdir: Some(ScriptWorkdir::Temporary),
+                                ..ScriptOptions::default()
                             },
-                            target_os: None,
-                        }),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn target_os_defaults_to_none_when_function_is_verify_and_target_os_is_unset() {
-                let result = parse(",verify(script_name=\"the-script\")");
+            fn succeeds_when_function_is_script_with_capture_env() {
+                let result = parse("shell,script(name=\"example-script\", capture_env=true)");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: String::new(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: Some(ScriptName("the-script".to_string())),
-                                stream: Stream::StdOut,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                capture_env: true,
+                                ..ScriptOptions::default()
                             },
-                            target_os: None,
-                        }),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn target_os_can_be_set_when_function_is_verify_and_target_os_is_unset() {
-                let result = parse(",verify(script_name=\"the-script\",target_os=\"some-os\")");
+            fn succeeds_when_function_is_script_with_tags() {
+                let result =
+                    parse("shell,script(name=\"example-script\", tags=[\"slow\", \"db\"])");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: String::new(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: Some(ScriptName("the-script".to_string())),
-                                stream: Stream::StdOut,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                tags: vec!["slow".to_string(), "db".to_string()],
+                                ..ScriptOptions::default()
                             },
-                            target_os: Some(TargetOs("some-os".to_string())),
-                        }),
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn fails_when_function_is_verify_and_stream_is_unknown() {
-                let result = parse(",verify(script_name=\"example-script\", stream=unknown)");
+            fn fails_when_function_is_script_with_a_tag_which_is_not_a_string() {
+                let result = parse("shell,script(name=\"example-script\", tags=[\"slow\", 1])");
                 assert_eq!(
                     result,
                     Err(Error::InvalidArgumentValue {
-                        function: "verify".to_string(),
-                        argument: "stream".to_string(),
-                        expected: "output, stdout or stderr".to_string(),
-                        got: "unknown".to_string(),
+                        function: "script".to_string(),
+                        argument: "tags".to_string(),
+                        expected: "a list of strings".to_string(),
+                        got: "a list containing a integer".to_string(),
                     })
                 );
             }
 
             #[test]
-            fn succeeds_when_script_name_is_not_present() {
-                let result = parse("text,verify(stream=stderr)");
+            fn succeeds_when_function_is_script_with_stable_runs() {
+                let result = parse("shell,script(name=\"example-script\", stable_runs=5)");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: "text".to_string(),
-                        extra: CodeBlockType::Verify(VerifyCodeBlock {
-                            source: Source {
-                                name: None,
-                                stream: Stream::StdErr,
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                stable_runs: Some(5),
+                                ..ScriptOptions::default()
                             },
-                            target_os: None,
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_script_with_an_expected_signal() {
+                for info in [
+                    "shell,script(expected_signal=SIGTERM)",
+                    "shell,script(expected_signal=\"TERM\")",
+                    "shell,script(expected_signal=15)",
+                ] {
+                    assert_eq!(
+                        parse(info),
+                        Ok(CodeBlockInfo {
+                            language: "shell".to_string(),
+                            extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                                script_name: None,
+                                expected_exit_code: None,
+                                expected_output: OutputExpectation::Any,
+                                expected_text: None,
+                                options: ScriptOptions {
+                                    expected_signal: Some(15),
+                                    ..ScriptOptions::default()
+                                },
+                            })],
                         }),
+                        "{info}"
+                    );
+                }
+            }
+
+            #[test]
+            fn fails_when_function_is_script_with_an_unknown_signal() {
+                let result = parse("shell,script(expected_signal=SIGFOO)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "script".to_string(),
+                        argument: "expected_signal".to_string(),
+                        expected: "a signal name such as SIGTERM or a signal number".to_string(),
+                        got: "SIGFOO".to_string(),
                     })
                 );
             }
-        }
 
-        mod file {
-            use crate::parsers::error::Error;
-            use crate::parsers::function_string_parser;
-            use crate::types::FilePath;
+            #[test]
+            fn succeeds_when_function_is_script_with_retries() {
+                let result = parse("shell,script(retries=2)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                retries: 2,
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
 
-            use super::{parse, CodeBlockInfo, CodeBlockType};
+            #[test]
+            fn succeeds_when_function_is_script_with_allow_failure() {
+                let result = parse("shell,script(allow_failure=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                allow_failure: true,
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
 
             #[test]
-            fn succeeds_when_function_is_file() {
-                let result = parse("text,file(path=\"example.txt\")");
+            fn succeeds_when_function_is_script_with_skip() {
+                let result = parse("shell,script(name=\"deploy\", skip=\"needs network access\")");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: "text".to_string(),
-                        extra: CodeBlockType::CreateFile(FilePath("example.txt".to_string())),
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("deploy".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                skip: Some("needs network access".to_string()),
+                                ..ScriptOptions::default()
+                            },
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn fails_when_path_is_missing() {
-                let result = parse("text,file()");
+            fn succeeds_when_function_is_script_with_a_list_of_target_os() {
+                let result = parse("shell,script(target_os=[\"linux\", \"macos\"])");
                 assert_eq!(
                     result,
-                    Err(Error::FunctionStringParser(
-                        function_string_parser::Error::MissingArgument {
-                            function: "file".to_string(),
-                            argument: "path".to_string(),
-                        }
-                    ))
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                target_os: Some(TargetOs(vec![
+                                    "linux".to_string(),
+                                    "macos".to_string()
+                                ])),
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
                 );
             }
-        }
 
-        mod skip {
-            use crate::parsers::error::Error;
-            use crate::parsers::function_string_parser;
+            #[test]
+            fn succeeds_when_function_is_script_with_a_list_of_unquoted_target_os() {
+                let result = parse("shell,script(target_os=[linux, macos], tags=[ci, \"slow\"])");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                target_os: Some(TargetOs(vec![
+                                    "linux".to_string(),
+                                    "macos".to_string()
+                                ])),
+                                tags: vec!["ci".to_string(), "slow".to_string()],
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
 
-            use super::{parse, CodeBlockInfo, CodeBlockType};
+            #[test]
+            fn succeeds_when_function_is_script_with_requires_command() {
+                let result = parse("shell,script(requires_command=\"docker\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                requires_command: vec!["docker".to_string()],
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
 
             #[test]
-            fn succeeds_when_function_is_skip() {
-                let result = parse("text,skip()");
+            fn succeeds_when_function_is_cleanup() {
+                let result = parse("shell,cleanup(name=\"stop-container\")");
                 assert_eq!(
                     result,
                     Ok(CodeBlockInfo {
-                        language: "text".to_string(),
-                        extra: CodeBlockType::Skip(),
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("stop-container".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                phase: ScriptPhase::Cleanup,
+                                ..ScriptOptions::default()
+                            },
+                        })],
                     })
                 );
             }
 
             #[test]
-            fn fails_when_path_is_missing() {
-                let result = parse("text,file()");
+            fn succeeds_when_function_is_script_with_env_conditions() {
+                let result = parse(
+                    "shell,script(only_if_env=\"CI\", skip_if_env=[\"OFFLINE\", \"NO_NETWORK\"])",
+                );
                 assert_eq!(
                     result,
-                    Err(Error::FunctionStringParser(
-                        function_string_parser::Error::MissingArgument {
-                            function: "file".to_string(),
-                            argument: "path".to_string(),
-                        }
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: None,
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                only_if_env: vec!["CI".to_string()],
+                                skip_if_env: vec!["OFFLINE".to_string(), "NO_NETWORK".to_string()],
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_script_with_fewer_than_two_stable_runs() {
+                let result = parse("shell,script(name=\"example-script\", stable_runs=1)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "script".to_string(),
+                        argument: "stable_runs".to_string(),
+                        expected: "an integer of at least 2".to_string(),
+                        got: "1".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_script_with_an_unknown_workdir() {
+                let result = parse("shell,script(name=\"example-script\", workdir=home)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "script".to_string(),
+                        argument: "workdir".to_string(),
+                        expected: "temp".to_string(),
+                        got: "home".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_script_with_expected_output_set_to_any() {
+                let result = parse("shell,script(name=\"example-script\", expected_output=any)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_script_with_expected_output_set_to_stdout() {
+                let result = parse("shell,script(name=\"example-script\", expected_output=stdout)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("example-script".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::StdOut,
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_script_with_expected_text() {
+                let result = parse(r#"shell,script(name="greet", expected_text="hello\n")"#);
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("greet".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: Some("hello\n".to_string()),
+                            options: ScriptOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_script_and_expected_output_is_quoted_text() {
+                let result = parse(r#"shell,script(name="greet", expected_output="hello\n")"#);
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::IncorrectArgumentType {
+                            function: "script".to_string(),
+                            argument: "expected_output".to_string(),
+                            expected: "token".to_string(),
+                            got: "string".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod verify {
+            use crate::parsers::code_block_type::{VerifyCodeBlock, VerifyFileCodeBlock};
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+            use crate::types::{
+                Case, Comparison, DirectoryExpectation, Encoding, FileMode, FilePath, Matcher,
+                OutputRange, Rendering, ScriptName, Source, Stream, StreamUsage, Substitution,
+                TargetOs, Tolerance, VerifyDirectoryAction, VerifyOptions, Version, Whitespace,
+            };
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_verify_and_stream_is_stdout() {
+                let result = parse(",verify(script_name=\"example-script\", stream=stdout)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("example-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_and_stream_is_stderr() {
+                let result = parse(",verify(script_name=\"example-script\", stream=stderr)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("example-script".to_string())),
+                                stream: Stream::StdErr,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_and_stream_is_output() {
+                let result = parse(",verify(script_name=\"example-script\", stream=output)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("example-script".to_string())),
+                                stream: Stream::Output,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_and_defaults_to_stdout_when_the_stream_is_missing() {
+                let result = parse(",verify(script_name=\"the-script\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn target_os_defaults_to_none_when_function_is_verify_and_target_os_is_unset() {
+                let result = parse(",verify(script_name=\"the-script\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn target_os_can_be_set_when_function_is_verify() {
+                let result = parse(",verify(script_name=\"the-script\",target_os=\"some-os\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                target_os: Some(TargetOs(vec!["some-os".to_string()])),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_and_stream_is_unknown() {
+                let result = parse(",verify(script_name=\"example-script\", stream=unknown)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "stream".to_string(),
+                        expected: "output, stdout or stderr".to_string(),
+                        got: "unknown".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_and_raw_is_true() {
+                let result = parse(",verify(script_name=\"the-script\", raw=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                rendering: Rendering::Raw,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_and_raw_is_not_a_boolean() {
+                let result = parse(",verify(script_name=\"the-script\", raw=maybe)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "raw".to_string(),
+                        expected: "true or false".to_string(),
+                        got: "maybe".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_and_raw_is_a_string() {
+                let result = parse(",verify(script_name=\"the-script\", raw=\"true\")");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::IncorrectArgumentType {
+                            function: "verify".to_string(),
+                            argument: "raw".to_string(),
+                            expected: "boolean".to_string(),
+                            got: "string".to_string(),
+                        }
+                    ))
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_fuzzy_matcher() {
+                let result =
+                    parse(",verify(script_name=\"the-script\", matcher=fuzzy, max_distance=3)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Fuzzy { max_distance: 3 },
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_fuzzy_matcher_and_no_max_distance() {
+                let result = parse(",verify(script_name=\"the-script\", matcher=fuzzy)");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "verify".to_string(),
+                            argument: "max_distance".to_string(),
+                        }
+                    ))
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_encoding() {
+                let result = parse(",verify(script_name=\"the-script\", encoding=\"UTF-8\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                encoding: Some(Encoding::Utf8),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_base64_encoding() {
+                let result = parse(",verify(script_name=\"the-script\", encoding=\"base64\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                encoding: Some(Encoding::Base64),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_any_of() {
+                let result = parse(",verify(script_name=\"the-script\", any_of=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                any_of: true,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_since_last() {
+                let result = parse(",verify(script_name=\"the-script\", since_last=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                output_range: OutputRange::SinceLast,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_allow_failure() {
+                let result = parse(",verify(script_name=\"the-script\", allow_failure=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                allow_failure: true,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_only_if_env() {
+                let result = parse(",verify(script_name=\"the-script\", only_if_env=\"CI\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                only_if_env: vec!["CI".to_string()],
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_env_changed() {
+                let result = parse(",verify(script_name=\"the-script\", env_changed=\"PATH\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                env_changed: Some("PATH".to_string()),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_normalize_with() {
+                let result = parse(",verify(script_name=\"the-script\", normalize_with=\"sort\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                normalize_with: Some("sort".to_string()),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_stream_usage() {
+                let result = parse(",verify(script_name=\"the-script\", stream_usage=stderr_only)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                stream_usage: Some(StreamUsage::StdErrOnly),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_substitutions() {
+                let result = parse(
+                    ",verify(script_name=\"the-script\", substitute=[{pattern=\"[0-9]+\", replacement=\"N\"}])",
+                );
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                substitutions: vec![Substitution {
+                                    pattern: "[0-9]+".to_string(),
+                                    replacement: "N".to_string(),
+                                }],
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_an_invalid_substitution_pattern() {
+                let result = parse(
+                    ",verify(script_name=\"the-script\", substitute=[{pattern=\"(\", replacement=\"N\"}])",
+                );
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "substitute".to_string(),
+                        expected: "a valid regular expression".to_string(),
+                        got: "(".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_unknown_encoding() {
+                let result = parse(",verify(script_name=\"the-script\", encoding=\"latin-1\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "encoding".to_string(),
+                        expected: "utf-8, ascii or base64".to_string(),
+                        got: "latin-1".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_anchored_matcher_and_trim() {
+                let result =
+                    parse(",verify(script_name=\"the-script\", matcher=starts_with, trim=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::StartsWith,
+                                whitespace: Whitespace::Trim,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_message() {
+                let result = parse(
+                    ",verify(script_name=\"the-script\", message=\"should list two services\")",
+                );
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("the-script".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                message: Some("should list two services".to_string()),
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_dir_and_golden() {
+                let result = parse(",verify(dir=\"out\", golden=\"golden\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::VerifyDirectory(VerifyDirectoryAction {
+                            directory: FilePath("out".to_string()),
+                            expected: DirectoryExpectation::Golden(FilePath("golden".to_string())),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_tree() {
+                let result = parse("text,verify_tree(path=\"dist\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::VerifyTree(FilePath("dist".to_string()))],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_include() {
+                let result = parse("markdown,include(path=\"common/setup.md\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "markdown".to_string(),
+                        extra: vec![CodeBlockType::Include(FilePath(
+                            "common/setup.md".to_string()
+                        ))],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_file_and_regex_matcher() {
+                let result = parse(",verify(file=\"nginx.conf\", matcher=regex)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+                            file_path: FilePath("nginx.conf".to_string()),
+                            mode: None,
+                            exists: None,
+                            options: VerifyOptions {
+                                matcher: Matcher::Regex,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_file_and_mode() {
+                let result = parse(",verify(file=\"bin/helper\", mode=\"755\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+                            file_path: FilePath("bin/helper".to_string()),
+                            mode: Some(FileMode(0o755)),
+                            exists: None,
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_assert_exists_or_assert_not_exists() {
+                for (info, exists) in [
+                    (",assert_exists(path=\"build/out.bin\")", true),
+                    (",assert_not_exists(path=\"build/out.bin\")", false),
+                ] {
+                    assert_eq!(
+                        parse(info),
+                        Ok(CodeBlockInfo {
+                            language: String::new(),
+                            extra: vec![CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+                                file_path: FilePath("build/out.bin".to_string()),
+                                mode: None,
+                                exists: Some(exists),
+                                options: VerifyOptions::default(),
+                            })],
+                        }),
+                        "{info}"
+                    );
+                }
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_file_and_an_invalid_mode() {
+                let result = parse(",verify(file=\"bin/helper\", mode=\"rwx\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "mode".to_string(),
+                        expected: "an octal file mode such as 755".to_string(),
+                        got: "rwx".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_file_and_an_option_for_script_output() {
+                for (info, argument) in [
+                    (",verify(file=\"a.txt\", mask_paths=true)", "mask_paths"),
+                    (",verify(file=\"a.txt\", encoding=\"base64\")", "encoding"),
+                    (",verify(file=\"a.txt\", any_of=true)", "any_of"),
+                    (
+                        ",verify(file=\"a.txt\", normalize_with=\"sort\")",
+                        "normalize_with",
+                    ),
+                    (",verify(file=\"a.txt\", display=\"cat\")", "display"),
+                    (
+                        ",verify(file=\"a.txt\", stream_usage=stdout_only)",
+                        "stream_usage",
+                    ),
+                    (",verify(file=\"a.txt\", since_last=true)", "since_last"),
+                    (
+                        ",verify(file=\"a.txt\", env_changed=\"PATH\")",
+                        "env_changed",
+                    ),
+                ] {
+                    assert_eq!(
+                        parse(info),
+                        Err(Error::UnsupportedArgument {
+                            function: "verify".to_string(),
+                            argument: argument.to_string(),
+                            context: "when verifying a file".to_string(),
+                        }),
+                        "{info}"
+                    );
+                }
+            }
+
+            #[test]
+            fn fails_when_function_is_assert_exists_with_an_option_for_script_output() {
+                assert_eq!(
+                    parse(",assert_exists(path=\"a.txt\", mask_paths=true)"),
+                    Err(Error::UnsupportedArgument {
+                        function: "assert_exists".to_string(),
+                        argument: "mask_paths".to_string(),
+                        context: "when verifying a file".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_dir_but_no_golden() {
+                let result = parse(",verify(dir=\"out\")");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "verify".to_string(),
+                            argument: "golden".to_string(),
+                        }
+                    ))
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_number_matcher() {
+                let result = parse(",verify(matcher=number, op=\">=\", value=100)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Number {
+                                    comparison: Comparison::GreaterThanOrEqual,
+                                    value: 100,
+                                },
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_wildcard_matcher() {
+                let result = parse(",verify(matcher=wildcard)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Wildcard,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_glob_matcher() {
+                let result = parse(",verify(matcher=glob)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Glob,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_json_matcher() {
+                let result = parse(",verify(matcher=json)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Json,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_trim_trailing_whitespace() {
+                let result = parse(",verify(trim_trailing_whitespace=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                whitespace: Whitespace::TrimTrailing,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_case_sensitive_false() {
+                let result = parse(",verify(case_sensitive=false)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                case: Case::Insensitive,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_contains() {
+                let result = parse(",verify(script_name=\"build\", contains=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("build".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Contains,
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_contains_and_a_matcher() {
+                let result = parse(",verify(contains=true, matcher=regex)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "contains".to_string(),
+                        expected: "false when a matcher is given".to_string(),
+                        got: "true".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_semver_matcher() {
+                let result = parse(",verify(matcher=semver, op=\"<\", value=\"2.0.0\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Semver {
+                                    comparison: Comparison::LessThan,
+                                    value: Version::new(2, 0, 0),
+                                },
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_semver_matcher_and_invalid_version() {
+                let result = parse(",verify(matcher=semver, op=\">\", value=\"2.0\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "value".to_string(),
+                        expected: "a version in the format MAJOR.MINOR.PATCH".to_string(),
+                        got: "2.0".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_numeric_matcher() {
+                let result = parse(",verify(matcher=numeric, tolerance=\"0.05\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Numeric {
+                                    tolerance: Tolerance(0.05),
+                                },
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_verify_with_numeric_matcher_and_unquoted_tolerance() {
+                let result = parse(",verify(matcher=numeric, tolerance=0.01)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: String::new(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions {
+                                matcher: Matcher::Numeric {
+                                    tolerance: Tolerance(0.01),
+                                },
+                                ..VerifyOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_numeric_matcher_and_negative_tolerance() {
+                let result = parse(",verify(matcher=numeric, tolerance=\"-1\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "tolerance".to_string(),
+                        expected: "a non-negative number".to_string(),
+                        got: "-1".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_unknown_comparison() {
+                let result = parse(",verify(matcher=number, op=\"=>\", value=100)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "op".to_string(),
+                        expected: ">, >=, <, <=, == or !=".to_string(),
+                        got: "=>".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_verify_with_unknown_matcher() {
+                let result = parse(",verify(script_name=\"the-script\", matcher=unknown)");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "verify".to_string(),
+                        argument: "matcher".to_string(),
+                        expected:
+                            "exact, fuzzy, starts_with, ends_with, number, semver, line_count, valid_json, json, regex, yaml, wildcard, glob or numeric"
+                                .to_string(),
+                        got: "unknown".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_script_name_is_not_present() {
+                let result = parse("text,verify(stream=stderr)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: None,
+                                stream: Stream::StdErr,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+        }
+
+        mod file {
+            use crate::parsers::code_block_type::CreateFileCodeBlock;
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+            use crate::types::{CreateFileOptions, FileMode, FilePath};
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_file() {
+                let result = parse("text,file(path=\"example.txt\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::CreateFile(CreateFileCodeBlock {
+                            file_path: FilePath("example.txt".to_string()),
+                            options: CreateFileOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_file_with_final_newline() {
+                let result = parse("text,file(path=\"example.txt\", final_newline=false)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::CreateFile(CreateFileCodeBlock {
+                            file_path: FilePath("example.txt".to_string()),
+                            options: CreateFileOptions {
+                                final_newline: Some(false),
+                                ..CreateFileOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_file_with_a_mode() {
+                for info in [
+                    "text,file(path=\"run.sh\", mode=\"755\")",
+                    "text,file(path=\"run.sh\", executable=true)",
+                ] {
+                    assert_eq!(
+                        parse(info),
+                        Ok(CodeBlockInfo {
+                            language: "text".to_string(),
+                            extra: vec![CodeBlockType::CreateFile(CreateFileCodeBlock {
+                                file_path: FilePath("run.sh".to_string()),
+                                options: CreateFileOptions {
+                                    mode: Some(FileMode(0o755)),
+                                    ..CreateFileOptions::default()
+                                },
+                            })],
+                        }),
+                        "{info}"
+                    );
+                }
+            }
+
+            #[test]
+            fn fails_when_function_is_file_with_an_invalid_mode() {
+                let result = parse("text,file(path=\"run.sh\", mode=\"rwx\")");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "file".to_string(),
+                        argument: "mode".to_string(),
+                        expected: "an octal file mode such as 755".to_string(),
+                        got: "rwx".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_file_with_append() {
+                let result = parse("text,file(path=\"example.txt\", append=true)");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::CreateFile(CreateFileCodeBlock {
+                            file_path: FilePath("example.txt".to_string()),
+                            options: CreateFileOptions {
+                                append: true,
+                                ..CreateFileOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_path_is_missing() {
+                let result = parse("text,file()");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "file".to_string(),
+                            argument: "path".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod env {
+            use crate::parsers::code_block_type::SetEnvCodeBlock;
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_env_with_unset_and_reset() {
+                for (info, unset) in [
+                    ("text,env(unset=\"A\", reset=true)", vec!["A"]),
+                    ("text,env(unset=[\"A\", \"B\"], reset=true)", vec!["A", "B"]),
+                ] {
+                    assert_eq!(
+                        parse(info),
+                        Ok(CodeBlockInfo {
+                            language: "text".to_string(),
+                            extra: vec![CodeBlockType::SetEnv(SetEnvCodeBlock {
+                                variable: None,
+                                unset: unset.into_iter().map(String::from).collect(),
+                                reset: true,
+                            })],
+                        }),
+                        "{info}"
+                    );
+                }
+            }
+
+            #[test]
+            fn fails_when_unset_is_a_list_of_integers() {
+                let result = parse("text,env(unset=[1])");
+                assert_eq!(
+                    result,
+                    Err(Error::InvalidArgumentValue {
+                        function: "env".to_string(),
+                        argument: "unset".to_string(),
+                        expected: "a string or a list of strings".to_string(),
+                        got: "a list containing a integer".to_string(),
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_env_without_arguments() {
+                let result = parse("text,env()");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::SetEnv(SetEnvCodeBlock::default())],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_env_with_name_and_value() {
+                let result = parse("text,env(name=\"GREETING\", value=\"hello\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::SetEnv(SetEnvCodeBlock {
+                            variable: Some(("GREETING".to_string(), "hello".to_string())),
+                            ..SetEnvCodeBlock::default()
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_function_is_env_with_a_name_but_no_value() {
+                let result = parse("text,env(name=\"GREETING\")");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "env".to_string(),
+                            argument: "value".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod note {
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_note_without_text() {
+                let result = parse("text,note()");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Note(None)],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_note_with_text() {
+                let result = parse("text,note(text=\"A note\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Note(Some("A note".to_string()))],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_text_is_not_a_string() {
+                let result = parse("text,note(text=1)");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::IncorrectArgumentType {
+                            function: "note".to_string(),
+                            argument: "text".to_string(),
+                            expected: "string".to_string(),
+                            got: "integer".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod stdin {
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+            use crate::types::ScriptName;
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_stdin() {
+                let result = parse("text,stdin(for=\"sort\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Stdin(ScriptName("sort".to_string()))],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_for_is_missing() {
+                let result = parse("text,stdin()");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "stdin".to_string(),
+                            argument: "for".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod skip {
+            use crate::parsers::error::Error;
+            use crate::parsers::function_string_parser;
+
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+
+            #[test]
+            fn succeeds_when_function_is_skip() {
+                let result = parse("text,skip()");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Skip(None)],
+                    })
+                );
+            }
+
+            #[test]
+            fn succeeds_when_function_is_skip_with_a_reason() {
+                let result = parse("text,skip(reason=\"needs network access\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Skip(Some(
+                            "needs network access".to_string()
+                        ))],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_path_is_missing() {
+                let result = parse("text,file()");
+                assert_eq!(
+                    result,
+                    Err(Error::FunctionStringParser(
+                        function_string_parser::Error::MissingArgument {
+                            function: "file".to_string(),
+                            argument: "path".to_string(),
+                        }
+                    ))
+                );
+            }
+        }
+
+        mod attributes {
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+            use crate::parsers::code_block_type::{ScriptCodeBlock, VerifyCodeBlock};
+            use crate::types::{
+                ExitCode, ExpectedExitCode, OutputExpectation, ScriptName, ScriptOptions, Source,
+                Stream, VerifyOptions,
+            };
+
+            #[test]
+            fn succeeds_when_the_function_is_given_as_attributes() {
+                let result = parse("shell {specdown=script name=\"build\" expected_exit_code=2}");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("build".to_string())),
+                            expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(2))),
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions::default(),
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn keeps_spaces_inside_quoted_values_and_lists() {
+                let result =
+                    parse("shell {.numberLines specdown=script name=\"a b\" tags=[\"x\", \"y\"]}");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "shell".to_string(),
+                        extra: vec![CodeBlockType::Script(ScriptCodeBlock {
+                            script_name: Some(ScriptName("a b".to_string())),
+                            expected_exit_code: None,
+                            expected_output: OutputExpectation::Any,
+                            expected_text: None,
+                            options: ScriptOptions {
+                                tags: vec!["x".to_string(), "y".to_string()],
+                                ..ScriptOptions::default()
+                            },
+                        })],
+                    })
+                );
+            }
+
+            #[test]
+            fn accepts_a_quoted_function_name() {
+                let result = parse("text {specdown=\"verify\" script_name=\"build\"}");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![CodeBlockType::Verify(VerifyCodeBlock {
+                            source: Source {
+                                name: Some(ScriptName("build".to_string())),
+                                stream: Stream::StdOut,
+                            },
+                            options: VerifyOptions::default(),
+                        })],
+                    })
+                );
+            }
+        }
+
+        mod multiple_functions {
+            use super::{parse, CodeBlockInfo, CodeBlockType};
+            use crate::parsers::code_block_type::{CreateFileCodeBlock, VerifyCodeBlock};
+            use crate::types::{
+                CreateFileOptions, FilePath, ScriptName, Source, Stream, VerifyOptions,
+            };
+
+            #[test]
+            fn succeeds_with_every_function_in_order() {
+                let result =
+                    parse("text,file(path=\"greeting.txt\"), verify(script_name=\"greet\")");
+                assert_eq!(
+                    result,
+                    Ok(CodeBlockInfo {
+                        language: "text".to_string(),
+                        extra: vec![
+                            CodeBlockType::CreateFile(CreateFileCodeBlock {
+                                file_path: FilePath("greeting.txt".to_string()),
+                                options: CreateFileOptions::default(),
+                            }),
+                            CodeBlockType::Verify(VerifyCodeBlock {
+                                source: Source {
+                                    name: Some(ScriptName("greet".to_string())),
+                                    stream: Stream::StdOut,
+                                },
+                                options: VerifyOptions::default(),
+                            }),
+                        ],
+                    })
+                );
+            }
+
+            #[test]
+            fn fails_when_any_function_is_unknown() {
+                let result = parse("text,note(),unknown()");
+                assert_eq!(
+                    result,
+                    Err(crate::parsers::error::Error::UnknownFunction(
+                        "unknown".to_string()
                     ))
                 );
             }
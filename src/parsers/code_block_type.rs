@@ -1,29 +1,77 @@
 use crate::parsers::error::{Error, Result};
 use crate::parsers::function_string_parser;
-use crate::parsers::function_string_parser::Function;
-use crate::types::{ExitCode, FilePath, OutputExpectation, ScriptName, Source, Stream, TargetOs};
-use nom::combinator::map_res;
+use crate::parsers::function_string_parser::{ArgumentValue, Function};
+use crate::parsers::version::parse_version;
+use crate::types::{
+    Case, Comparison, CreateFileOptions, DirectoryExpectation, Encoding, ExitCode,
+    ExpectedExitCode, FileMode, FilePath, Matcher, OutputExpectation, OutputRange, Rendering,
+    ScriptName, ScriptOptions, ScriptPhase, ScriptWorkdir, Source, Stream, StreamUsage,
+    Substitution, TargetOs, Tolerance, VerifyDirectoryAction, VerifyOptions, Whitespace,
+};
+use nom::bytes::complete::tag;
+use nom::character::complete::space0;
+use nom::combinator::{cut, map_res};
+use nom::multi::separated_list1;
+use nom::sequence::tuple;
 use nom::IResult;
+use regex::Regex;
+use std::convert::TryFrom;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct ScriptCodeBlock {
     pub script_name: Option<ScriptName>,
-    pub expected_exit_code: Option<ExitCode>,
+    pub expected_exit_code: Option<ExpectedExitCode>,
     pub expected_output: OutputExpectation,
+    pub expected_text: Option<String>,
+    pub options: ScriptOptions,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct VerifyCodeBlock {
     pub source: Source,
-    pub target_os: Option<TargetOs>,
+    pub options: VerifyOptions,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VerifyFileCodeBlock {
+    pub file_path: FilePath,
+    pub mode: Option<FileMode>,
+    pub exists: Option<bool>,
+    pub options: VerifyOptions,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct CreateFileCodeBlock {
+    pub file_path: FilePath,
+    pub options: CreateFileOptions,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct SetEnvCodeBlock {
+    pub variable: Option<(String, String)>,
+    pub unset: Vec<String>,
+    pub reset: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum CodeBlockType {
     Script(ScriptCodeBlock),
     Verify(VerifyCodeBlock),
-    CreateFile(FilePath),
-    Skip(),
+    VerifyDirectory(VerifyDirectoryAction),
+    VerifyTree(FilePath),
+    VerifyFile(VerifyFileCodeBlock),
+    CreateFile(CreateFileCodeBlock),
+    Note(Option<String>),
+    SetEnv(SetEnvCodeBlock),
+    Stdin(ScriptName),
+    Include(FilePath),
+    Skip(Option<String>),
+}
+
+// Each function in a list such as `file(path="a.txt"),verify(script_name="a")` is
+// applied to the same block
+pub fn parse_list(input: &str) -> IResult<&str, Vec<CodeBlockType>, Error> {
+    separated_list1(tuple((space0, tag(","), space0)), cut(parse))(input)
 }
 
 pub fn parse(input: &str) -> IResult<&str, CodeBlockType, Error> {
@@ -32,10 +80,17 @@ pub fn parse(input: &str) -> IResult<&str, CodeBlockType, Error> {
 
 fn from_function(f: Function) -> Result<CodeBlockType> {
     match &f.name[..] {
-        "script" => script_to_code_block_type(&f),
+        "script" | "cleanup" => script_to_code_block_type(&f),
         "verify" => verify_to_code_block_type(&f),
         "file" => file_to_code_block_type(&f),
-        "skip" => Ok(skip_to_code_block_type(&f)),
+        "note" => note_to_code_block_type(&f),
+        "env" => env_to_code_block_type(&f),
+        "stdin" => stdin_to_code_block_type(&f),
+        "include" => include_to_code_block_type(&f),
+        "verify_tree" => verify_tree_to_code_block_type(&f),
+        "assert_exists" => assert_exists_to_code_block_type(&f, true),
+        "assert_not_exists" => assert_exists_to_code_block_type(&f, false),
+        "skip" => skip_to_code_block_type(&f),
         _ => Err(Error::UnknownFunction(f.name)),
     }
 }
@@ -47,21 +102,208 @@ fn script_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
         None
     };
     let expected_exit_code = if f.has_argument("expected_exit_code") {
-        Some(ExitCode(f.get_integer_argument("expected_exit_code")?))
+        to_expected_exit_code(f)?
     } else {
         None
     };
-    let expected_output = f
-        .get_token_argument("expected_output")
-        .or_else(|_| Ok("any".to_string()))
-        .and_then(|s| to_expected_output(&s))?;
+    let expected_output = if f.has_argument("expected_output") {
+        to_expected_output(&f.get_token_argument("expected_output")?)?
+    } else {
+        OutputExpectation::Any
+    };
+    let expected_text = if f.has_argument("expected_text") {
+        Some(f.get_string_argument("expected_text")?)
+    } else {
+        None
+    };
+    let tee = if f.has_argument("tee") {
+        Some(FilePath(f.get_string_argument("tee")?))
+    } else {
+        None
+    };
+    let idempotent = get_boolean_argument(f, "idempotent", false)?;
+    let changed_marker = get_optional_string_argument(f, "changed_marker")?;
+    let workdir = if f.has_argument("workdir") {
+        Some(to_script_workdir(f, &f.get_token_argument("workdir")?)?)
+    } else {
+        None
+    };
+    let capture_env = get_boolean_argument(f, "capture_env", false)?;
+    let stable_runs = if f.has_argument("stable_runs") {
+        Some(to_stable_runs(f)?)
+    } else {
+        None
+    };
+    let retries = if f.has_argument("retries") {
+        let retries = f.get_integer_argument("retries")?;
+        u32::try_from(retries).map_err(|_| Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "retries".to_string(),
+            expected: "a non-negative integer".to_string(),
+            got: retries.to_string(),
+        })?
+    } else {
+        0
+    };
+    let allow_failure = get_boolean_argument(f, "allow_failure", false)?;
+    let skip = get_optional_string_argument(f, "skip")?;
+    let target_os = to_target_os(f)?;
+    let requires_command = get_string_or_list_argument(f, "requires_command")?;
+    let only_if_env = get_string_or_list_argument(f, "only_if_env")?;
+    let skip_if_env = get_string_or_list_argument(f, "skip_if_env")?;
+    let expected_signal = if f.has_argument("expected_signal") {
+        Some(to_signal(f)?)
+    } else {
+        None
+    };
+    let tags = if f.has_argument("tags") {
+        f.get_string_list_argument("tags")
+            .map_err(|err| to_list_item_error(err, "a list of strings"))?
+    } else {
+        Vec::new()
+    };
     Ok(CodeBlockType::Script(ScriptCodeBlock {
         script_name: name,
         expected_exit_code,
         expected_output,
+        expected_text,
+        options: ScriptOptions {
+            tee,
+            idempotent,
+            changed_marker,
+            workdir,
+            capture_env,
+            tags,
+            stable_runs,
+            expected_signal,
+            retries,
+            allow_failure,
+            skip,
+            target_os,
+            requires_command,
+            only_if_env,
+            skip_if_env,
+            phase: to_script_phase(f),
+        },
     }))
 }
 
+fn to_script_phase(f: &Function) -> ScriptPhase {
+    if f.name == "cleanup" {
+        ScriptPhase::Cleanup
+    } else {
+        ScriptPhase::Main
+    }
+}
+
+fn to_stable_runs(f: &Function) -> Result<u32> {
+    let runs = f.get_integer_argument("stable_runs")?;
+    u32::try_from(runs)
+        .ok()
+        .filter(|runs| *runs >= 2)
+        .ok_or_else(|| Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "stable_runs".to_string(),
+            expected: "an integer of at least 2".to_string(),
+            got: runs.to_string(),
+        })
+}
+
+fn to_expected_exit_code(f: &Function) -> Result<Option<ExpectedExitCode>> {
+    let invalid = |got: String| Error::InvalidArgumentValue {
+        function: f.name.clone(),
+        argument: "expected_exit_code".to_string(),
+        expected: "an integer, a range such as \"1..5\", a list of integers or any".to_string(),
+        got,
+    };
+
+    if let Ok(code) = f.get_integer_argument("expected_exit_code") {
+        return Ok(Some(ExpectedExitCode::Exactly(ExitCode(code))));
+    }
+    if let Ok(token) = f.get_token_argument("expected_exit_code") {
+        return if token == "any" {
+            Ok(None)
+        } else {
+            Err(invalid(token))
+        };
+    }
+    if let Ok(range) = f.get_string_argument("expected_exit_code") {
+        return to_exit_code_range(&range)
+            .map(Some)
+            .ok_or_else(|| invalid(range));
+    }
+
+    let codes: Vec<_> = f
+        .get_integer_list_argument("expected_exit_code")
+        .map_err(|err| match err {
+            function_string_parser::Error::IncorrectListItemType { got, .. } => {
+                invalid(format!("a list containing a {got}"))
+            }
+            err => err.into(),
+        })?
+        .into_iter()
+        .map(ExitCode)
+        .collect();
+    if codes.is_empty() {
+        return Err(invalid("an empty list".to_string()));
+    }
+    Ok(Some(ExpectedExitCode::OneOf(codes)))
+}
+
+fn to_exit_code_range(range: &str) -> Option<ExpectedExitCode> {
+    let (from, to) = range.split_once("..")?;
+    let from = from.trim().parse().ok()?;
+    let to = to.trim().parse().ok()?;
+    (from <= to).then_some(ExpectedExitCode::Range {
+        from: ExitCode(from),
+        to: ExitCode(to),
+    })
+}
+
+fn to_signal(f: &Function) -> Result<i32> {
+    if let Ok(number) = f.get_integer_argument("expected_signal") {
+        return Ok(number);
+    }
+
+    let name = f
+        .get_string_argument("expected_signal")
+        .or_else(|_| f.get_token_argument("expected_signal"))?;
+    signal_number(name.trim_start_matches("SIG")).ok_or_else(|| Error::InvalidArgumentValue {
+        function: f.name.clone(),
+        argument: "expected_signal".to_string(),
+        expected: "a signal name such as SIGTERM or a signal number".to_string(),
+        got: name.clone(),
+    })
+}
+
+// Only signals which have the same number on Linux and macOS
+fn signal_number(name: &str) -> Option<i32> {
+    match name {
+        "HUP" => Some(1),
+        "INT" => Some(2),
+        "QUIT" => Some(3),
+        "ABRT" => Some(6),
+        "KILL" => Some(9),
+        "SEGV" => Some(11),
+        "PIPE" => Some(13),
+        "ALRM" => Some(14),
+        "TERM" => Some(15),
+        _ => None,
+    }
+}
+
+fn to_script_workdir(f: &Function, name: &str) -> Result<ScriptWorkdir> {
+    match name {
+        "temp" => Ok(ScriptWorkdir::Temporary),
+        _ => Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "workdir".to_string(),
+            expected: "temp".to_string(),
+            got: name.to_string(),
+        }),
+    }
+}
+
 fn to_expected_output(s: &str) -> Result<OutputExpectation> {
     match s {
         "any" => Ok(OutputExpectation::Any),
@@ -77,16 +319,163 @@ fn to_expected_output(s: &str) -> Result<OutputExpectation> {
     }
 }
 
+fn verify_directory_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    Ok(CodeBlockType::VerifyDirectory(VerifyDirectoryAction {
+        directory: FilePath(f.get_string_argument("dir")?),
+        expected: DirectoryExpectation::Golden(FilePath(f.get_string_argument("golden")?)),
+    }))
+}
+
+fn verify_tree_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    Ok(CodeBlockType::VerifyTree(FilePath(
+        f.get_string_argument("path")?,
+    )))
+}
+
+fn include_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    Ok(CodeBlockType::Include(FilePath(
+        f.get_string_argument("path")?,
+    )))
+}
+
+// Options which only apply to script output, so cannot be used to check a file
+const SCRIPT_OUTPUT_ONLY_ARGUMENTS: [&str; 8] = [
+    "mask_paths",
+    "encoding",
+    "any_of",
+    "normalize_with",
+    "display",
+    "stream_usage",
+    "since_last",
+    "env_changed",
+];
+
+fn reject_script_output_only_arguments(f: &Function) -> Result<()> {
+    match SCRIPT_OUTPUT_ONLY_ARGUMENTS
+        .iter()
+        .find(|argument| f.has_argument(argument))
+    {
+        Some(argument) => Err(Error::UnsupportedArgument {
+            function: f.name.clone(),
+            argument: (*argument).to_string(),
+            context: "when verifying a file".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn verify_file_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    reject_script_output_only_arguments(f)?;
+    let mode = if f.has_argument("mode") {
+        Some(to_file_mode(f, &f.get_string_argument("mode")?)?)
+    } else {
+        None
+    };
+    Ok(CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+        file_path: FilePath(f.get_string_argument("file")?),
+        mode,
+        exists: None,
+        options: to_verify_options(f)?,
+    }))
+}
+
+fn assert_exists_to_code_block_type(f: &Function, exists: bool) -> Result<CodeBlockType> {
+    reject_script_output_only_arguments(f)?;
+    Ok(CodeBlockType::VerifyFile(VerifyFileCodeBlock {
+        file_path: FilePath(f.get_string_argument("path")?),
+        mode: None,
+        exists: Some(exists),
+        options: to_verify_options(f)?,
+    }))
+}
+
+fn to_file_mode(f: &Function, mode: &str) -> Result<FileMode> {
+    match mode.len() {
+        3 | 4 => u32::from_str_radix(mode, 8).ok(),
+        _ => None,
+    }
+    .map(FileMode)
+    .ok_or_else(|| Error::InvalidArgumentValue {
+        function: f.name.clone(),
+        argument: "mode".to_string(),
+        expected: "an octal file mode such as 755".to_string(),
+        got: mode.to_string(),
+    })
+}
+
 fn file_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
     let path = f.get_string_argument("path")?;
-    Ok(CodeBlockType::CreateFile(FilePath(path)))
+    let final_newline = if f.has_argument("final_newline") {
+        Some(get_boolean_argument(f, "final_newline", true)?)
+    } else {
+        None
+    };
+    let append = get_boolean_argument(f, "append", false)?;
+    let mode = if f.has_argument("mode") {
+        Some(to_file_mode(f, &f.get_string_argument("mode")?)?)
+    } else if get_boolean_argument(f, "executable", false)? {
+        Some(FileMode(0o755))
+    } else {
+        None
+    };
+    Ok(CodeBlockType::CreateFile(CreateFileCodeBlock {
+        file_path: FilePath(path),
+        options: CreateFileOptions {
+            final_newline,
+            append,
+            mode,
+        },
+    }))
+}
+
+fn note_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    let text = if f.has_argument("text") {
+        Some(f.get_string_argument("text")?)
+    } else {
+        None
+    };
+    Ok(CodeBlockType::Note(text))
+}
+
+fn env_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    let variable = if f.has_argument("name") || f.has_argument("value") {
+        Some((
+            f.get_string_argument("name")?,
+            f.get_string_argument("value")?,
+        ))
+    } else {
+        None
+    };
+    let unset = get_string_or_list_argument(f, "unset")?;
+    let reset = get_boolean_argument(f, "reset", false)?;
+    Ok(CodeBlockType::SetEnv(SetEnvCodeBlock {
+        variable,
+        unset,
+        reset,
+    }))
 }
 
-const fn skip_to_code_block_type(_f: &Function) -> CodeBlockType {
-    CodeBlockType::Skip()
+fn stdin_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    Ok(CodeBlockType::Stdin(ScriptName(
+        f.get_string_argument("for")?,
+    )))
+}
+
+fn skip_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    Ok(CodeBlockType::Skip(get_optional_string_argument(
+        f, "reason",
+    )?))
 }
 
 fn verify_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
+    if f.has_argument("dir") {
+        return verify_directory_to_code_block_type(f);
+    }
+
+    if f.has_argument("file") {
+        return verify_file_to_code_block_type(f);
+    }
+
     let name = if f.has_argument("script_name") {
         Some(ScriptName(f.get_string_argument("script_name")?))
     } else {
@@ -97,11 +486,6 @@ fn verify_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
     } else {
         "stdout".to_string()
     };
-    let target_os = if f.has_argument("target_os") {
-        Some(TargetOs(f.get_string_argument("target_os")?))
-    } else {
-        None
-    };
     let stream = to_stream(&stream_name).ok_or_else(|| Error::InvalidArgumentValue {
         function: f.name.to_string(),
         argument: "stream".to_string(),
@@ -110,14 +494,329 @@ fn verify_to_code_block_type(f: &Function) -> Result<CodeBlockType> {
     })?;
     Ok(CodeBlockType::Verify(VerifyCodeBlock {
         source: Source { name, stream },
-        target_os,
+        options: to_verify_options(f)?,
     }))
 }
 
+fn to_verify_options(f: &Function) -> Result<VerifyOptions> {
+    let rendering = if get_boolean_argument(f, "raw", false)? {
+        Rendering::Raw
+    } else {
+        Rendering::Template
+    };
+    let matcher = to_matcher(f)?;
+    let mask_paths = get_boolean_argument(f, "mask_paths", false)?;
+    let whitespace = if get_boolean_argument(f, "trim", false)? {
+        Whitespace::Trim
+    } else if get_boolean_argument(f, "trim_trailing_whitespace", false)? {
+        Whitespace::TrimTrailing
+    } else {
+        Whitespace::Keep
+    };
+    let case = if get_boolean_argument(f, "case_sensitive", true)? {
+        Case::Sensitive
+    } else {
+        Case::Insensitive
+    };
+    let any_of = get_boolean_argument(f, "any_of", false)?;
+    let output_range = if get_boolean_argument(f, "since_last", false)? {
+        OutputRange::SinceLast
+    } else {
+        OutputRange::All
+    };
+    let allow_failure = get_boolean_argument(f, "allow_failure", false)?;
+    let target_os = to_target_os(f)?;
+    let only_if_env = get_string_or_list_argument(f, "only_if_env")?;
+    let skip_if_env = get_string_or_list_argument(f, "skip_if_env")?;
+    let env_changed = if f.has_argument("env_changed") {
+        Some(f.get_string_argument("env_changed")?)
+    } else {
+        None
+    };
+    let message = if f.has_argument("message") {
+        Some(f.get_string_argument("message")?)
+    } else {
+        None
+    };
+    let encoding = if f.has_argument("encoding") {
+        Some(to_encoding(f, &f.get_string_argument("encoding")?)?)
+    } else {
+        None
+    };
+    let normalize_with = if f.has_argument("normalize_with") {
+        Some(f.get_string_argument("normalize_with")?)
+    } else {
+        None
+    };
+    let display = if f.has_argument("display") {
+        Some(f.get_string_argument("display")?)
+    } else {
+        None
+    };
+    let stream_usage = if f.has_argument("stream_usage") {
+        Some(to_stream_usage(f, &f.get_token_argument("stream_usage")?)?)
+    } else {
+        None
+    };
+    let substitutions = if f.has_argument("substitute") {
+        f.get_list_argument("substitute")?
+            .iter()
+            .map(|item| to_substitution(f, item))
+            .collect::<Result<_>>()?
+    } else {
+        Vec::new()
+    };
+    Ok(VerifyOptions {
+        rendering,
+        matcher,
+        mask_paths,
+        encoding,
+        whitespace,
+        case,
+        message,
+        any_of,
+        normalize_with,
+        display,
+        stream_usage,
+        substitutions,
+        output_range,
+        env_changed,
+        allow_failure,
+        target_os,
+        only_if_env,
+        skip_if_env,
+        ..VerifyOptions::default()
+    })
+}
+
+fn to_substitution(f: &Function, item: &ArgumentValue) -> Result<Substitution> {
+    let invalid = |expected: &str, got: String| Error::InvalidArgumentValue {
+        function: f.name.clone(),
+        argument: "substitute".to_string(),
+        expected: expected.to_string(),
+        got,
+    };
+    let fields = item
+        .object()
+        .map_err(|err| invalid("a list of {pattern, replacement} objects", err.got))?;
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .and_then(|value| value.string().ok())
+            .ok_or_else(|| {
+                invalid(
+                    &format!("objects with a {name} string"),
+                    "an object without one".to_string(),
+                )
+            })
+    };
+    let pattern = field("pattern")?;
+    let replacement = field("replacement")?;
+
+    if Regex::new(&pattern).is_err() {
+        return Err(invalid("a valid regular expression", pattern));
+    }
+
+    Ok(Substitution {
+        pattern,
+        replacement,
+    })
+}
+
+fn to_encoding(f: &Function, name: &str) -> Result<Encoding> {
+    match &name.to_lowercase()[..] {
+        "utf-8" | "utf8" => Ok(Encoding::Utf8),
+        "ascii" => Ok(Encoding::Ascii),
+        "base64" => Ok(Encoding::Base64),
+        _ => Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "encoding".to_string(),
+            expected: "utf-8, ascii or base64".to_string(),
+            got: name.to_string(),
+        }),
+    }
+}
+
+fn to_stream_usage(f: &Function, name: &str) -> Result<StreamUsage> {
+    match name {
+        "stdout_only" => Ok(StreamUsage::StdOutOnly),
+        "stderr_only" => Ok(StreamUsage::StdErrOnly),
+        _ => Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "stream_usage".to_string(),
+            expected: "stdout_only or stderr_only".to_string(),
+            got: name.to_string(),
+        }),
+    }
+}
+
+fn to_matcher(f: &Function) -> Result<Matcher> {
+    let contains = get_boolean_argument(f, "contains", false)?;
+    if contains && f.has_argument("matcher") {
+        return Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "contains".to_string(),
+            expected: "false when a matcher is given".to_string(),
+            got: "true".to_string(),
+        });
+    }
+    if contains {
+        return Ok(Matcher::Contains);
+    }
+    if !f.has_argument("matcher") {
+        return Ok(Matcher::Exact);
+    }
+
+    match &f.get_token_argument("matcher")?[..] {
+        "exact" => Ok(Matcher::Exact),
+        "starts_with" => Ok(Matcher::StartsWith),
+        "ends_with" => Ok(Matcher::EndsWith),
+        "line_count" => Ok(Matcher::LineCount),
+        "valid_json" => Ok(Matcher::ValidJson),
+        "json" => Ok(Matcher::Json),
+        "regex" => Ok(Matcher::Regex),
+        "yaml" => Ok(Matcher::Yaml),
+        "wildcard" => Ok(Matcher::Wildcard),
+        "glob" => Ok(Matcher::Glob),
+        "numeric" => {
+            let tolerance = f.get_number_argument("tolerance")?;
+            match tolerance.parse::<f64>() {
+                Ok(value) if value.is_finite() && value >= 0.0 => Ok(Matcher::Numeric {
+                    tolerance: Tolerance(value),
+                }),
+                _ => Err(Error::InvalidArgumentValue {
+                    function: f.name.clone(),
+                    argument: "tolerance".to_string(),
+                    expected: "a non-negative number".to_string(),
+                    got: tolerance,
+                }),
+            }
+        }
+        "fuzzy" => {
+            let max_distance = f.get_integer_argument("max_distance")?;
+            let max_distance =
+                u32::try_from(max_distance).map_err(|_| Error::InvalidArgumentValue {
+                    function: f.name.clone(),
+                    argument: "max_distance".to_string(),
+                    expected: "a non-negative integer".to_string(),
+                    got: max_distance.to_string(),
+                })?;
+            Ok(Matcher::Fuzzy { max_distance })
+        }
+        "number" => Ok(Matcher::Number {
+            comparison: to_comparison(f, &f.get_string_argument("op")?)?,
+            value: f.get_integer_argument("value")?,
+        }),
+        "semver" => {
+            let value = f.get_string_argument("value")?;
+            Ok(Matcher::Semver {
+                comparison: to_comparison(f, &f.get_string_argument("op")?)?,
+                value: parse_version(&value).map_err(|_| Error::InvalidArgumentValue {
+                    function: f.name.clone(),
+                    argument: "value".to_string(),
+                    expected: "a version in the format MAJOR.MINOR.PATCH".to_string(),
+                    got: value.clone(),
+                })?,
+            })
+        }
+        value => Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "matcher".to_string(),
+            expected:
+                "exact, fuzzy, starts_with, ends_with, number, semver, line_count, valid_json, json, regex, yaml, wildcard, glob or numeric"
+                    .to_string(),
+            got: value.to_string(),
+        }),
+    }
+}
+
+fn to_comparison(f: &Function, op: &str) -> Result<Comparison> {
+    match op {
+        ">" => Ok(Comparison::GreaterThan),
+        ">=" => Ok(Comparison::GreaterThanOrEqual),
+        "<" => Ok(Comparison::LessThan),
+        "<=" => Ok(Comparison::LessThanOrEqual),
+        "==" => Ok(Comparison::Equal),
+        "!=" => Ok(Comparison::NotEqual),
+        value => Err(Error::InvalidArgumentValue {
+            function: f.name.clone(),
+            argument: "op".to_string(),
+            expected: ">, >=, <, <=, == or !=".to_string(),
+            got: value.to_string(),
+        }),
+    }
+}
+
+fn get_boolean_argument(f: &Function, argument: &str, default: bool) -> Result<bool> {
+    if !f.has_argument(argument) {
+        return Ok(default);
+    }
+
+    f.get_boolean_argument(argument)
+        .map_err(|err| match f.get_token_argument(argument) {
+            Ok(token) => Error::InvalidArgumentValue {
+                function: f.name.clone(),
+                argument: argument.to_string(),
+                expected: "true or false".to_string(),
+                got: token,
+            },
+            Err(_) => err.into(),
+        })
+}
+
+fn get_optional_string_argument(f: &Function, argument: &str) -> Result<Option<String>> {
+    if f.has_argument(argument) {
+        Ok(Some(f.get_string_argument(argument)?))
+    } else {
+        Ok(None)
+    }
+}
+
+// An empty list is returned when the argument is not given
+fn get_string_or_list_argument(f: &Function, argument: &str) -> Result<Vec<String>> {
+    if !f.has_argument(argument) {
+        return Ok(Vec::new());
+    }
+
+    f.get_string_argument(argument)
+        .map(|value| vec![value])
+        .or_else(|_| {
+            f.get_string_list_argument(argument)
+                .map_err(|err| to_list_item_error(err, "a string or a list of strings"))
+        })
+}
+
+// Describes a list with an item of the wrong type in terms of everything the argument accepts
+fn to_list_item_error(err: function_string_parser::Error, expected: &str) -> Error {
+    match err {
+        function_string_parser::Error::IncorrectListItemType {
+            function,
+            argument,
+            got,
+            ..
+        } => Error::InvalidArgumentValue {
+            function,
+            argument,
+            expected: expected.to_string(),
+            got: format!("a list containing a {got}"),
+        },
+        err => err.into(),
+    }
+}
+
+fn to_target_os(f: &Function) -> Result<Option<TargetOs>> {
+    if f.has_argument("target_os") {
+        Ok(Some(TargetOs(get_string_or_list_argument(f, "target_os")?)))
+    } else {
+        Ok(None)
+    }
+}
+
 fn to_stream(stream_name: &str) -> Option<Stream> {
     match stream_name {
         "stdout" => Some(Stream::StdOut),
         "stderr" => Some(Stream::StdErr),
+        "output" => Some(Stream::Output),
         _ => None,
     }
 }
@@ -0,0 +1,108 @@
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{parse_document, Arena};
+
+use super::markdown;
+
+pub fn dump_ast(markdown: &str) -> String {
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &markdown::options());
+
+    let mut lines = vec![];
+    dump_node(root, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn dump_node<'a>(node: &'a AstNode<'a>, depth: usize, lines: &mut Vec<String>) {
+    lines.push(format!(
+        "{}{}",
+        "  ".repeat(depth),
+        describe(&node.data.borrow().value)
+    ));
+
+    for child in node.children() {
+        dump_node(child, depth + 1, lines);
+    }
+}
+
+fn describe(value: &NodeValue) -> String {
+    match value {
+        NodeValue::CodeBlock(block) if block.fenced => format!(
+            "CodeBlock (fenced, info: {:?})",
+            String::from_utf8_lossy(&block.info)
+        ),
+        NodeValue::CodeBlock(_) => "CodeBlock (indented)".to_string(),
+        other => type_name(other),
+    }
+}
+
+fn type_name(value: &NodeValue) -> String {
+    format!("{value:?}")
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dump_ast;
+    use indoc::indoc;
+
+    #[test]
+    fn lists_the_node_types_as_a_tree() {
+        let markdown = indoc!(
+            "
+            # Header
+
+            Some text
+            "
+        );
+
+        assert_eq!(
+            dump_ast(markdown),
+            indoc!(
+                "
+                Document
+                  Heading
+                    Text
+                  Paragraph
+                    Text"
+            )
+            .trim_start()
+        );
+    }
+
+    #[test]
+    fn lists_code_blocks_with_their_info_strings() {
+        let markdown = indoc!(
+            "
+            ```shell,script(name=\"example\")
+            echo hello
+            ```
+
+                indented code
+            "
+        );
+
+        assert_eq!(
+            dump_ast(markdown),
+            indoc!(
+                "
+                Document
+                  CodeBlock (fenced, info: \"shell,script(name=\\\"example\\\")\")
+                  CodeBlock (indented)"
+            )
+            .trim_start()
+        );
+    }
+
+    #[test]
+    fn lists_front_matter() {
+        let markdown = "---\nmin_specdown_version: 1.0.0\n---\n\n# Title\n";
+
+        assert_eq!(
+            dump_ast(markdown),
+            "Document\n  FrontMatter\n  Heading\n    Text"
+        );
+    }
+}
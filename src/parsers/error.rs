@@ -12,12 +12,29 @@ pub enum Error {
     MarkdownParser(markdown::Error),
     ParserFailed(String),
     UnknownFunction(String),
+    UnknownSectionMarker(String),
+    IncludeFailed {
+        path: String,
+        reason: String,
+    },
+    InvalidFrontMatter(String),
+    UnknownMetadata(String),
+    InvalidMetadata {
+        key: String,
+        expected: String,
+        got: String,
+    },
     InvalidArgumentValue {
         function: String,
         argument: String,
         expected: String,
         got: String,
     },
+    UnsupportedArgument {
+        function: String,
+        argument: String,
+        context: String,
+    },
 }
 
 impl From<function_string_parser::Error> for Error {
@@ -63,6 +80,18 @@ impl fmt::Display for Error {
             }
             Self::ParserFailed(msg) => write!(f, "The parser failed: {msg}"),
             Self::UnknownFunction(name) => write!(f, "Unknown function: {name}"),
+            Self::UnknownSectionMarker(marker) => write!(f, "Unknown section marker: {marker}"),
+            Self::IncludeFailed { path, reason } => {
+                write!(f, "Failed to include {path}: {reason}")
+            }
+            Self::InvalidFrontMatter(msg) => write!(f, "The front matter is not valid YAML: {msg}"),
+            Self::UnknownMetadata(key) => write!(
+                f,
+                "Unknown metadata {key}, expected one of min_specdown_version, tags, shell or env"
+            ),
+            Self::InvalidMetadata { key, expected, got } => {
+                write!(f, "Metadata {key} must be {expected}, got {got}")
+            }
             Self::FunctionStringParser(function_string_parser::Error::MissingArgument {
                 function,
                 argument,
@@ -78,6 +107,15 @@ impl fmt::Display for Error {
                 f,
                 "Function {function} requires argument {argument} to be a {expected}, got {got}"
             ),
+            Self::FunctionStringParser(function_string_parser::Error::IncorrectListItemType {
+                function,
+                argument,
+                expected,
+                got,
+            }) => write!(
+                f,
+                "Function {function} requires argument {argument} to be a list of {expected}s, got a list containing a {got}"
+            ),
             Self::InvalidArgumentValue {
                 function,
                 argument,
@@ -87,6 +125,14 @@ impl fmt::Display for Error {
                 f,
                 "Argument {argument} for function {function} must be {expected}, got {got}"
             ),
+            Self::UnsupportedArgument {
+                function,
+                argument,
+                context,
+            } => write!(
+                f,
+                "Argument {argument} for function {function} is not supported {context}"
+            ),
         }
     }
 }
@@ -133,6 +179,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_unknown_section_marker() {
+        assert_eq!(
+            format!("{}", Error::UnknownSectionMarker("before".to_string())),
+            "Unknown section marker: before"
+        );
+    }
+
+    #[test]
+    fn display_include_failed() {
+        assert_eq!(
+            format!(
+                "{}",
+                Error::IncludeFailed {
+                    path: "setup.md".to_string(),
+                    reason: "not found".to_string(),
+                }
+            ),
+            "Failed to include setup.md: not found"
+        );
+    }
+
+    #[test]
+    fn display_invalid_metadata() {
+        assert_eq!(
+            format!(
+                "{}",
+                Error::InvalidMetadata {
+                    key: "keyy".to_string(),
+                    expected: "a value".to_string(),
+                    got: "other".to_string(),
+                }
+            ),
+            "Metadata keyy must be a value, got other"
+        );
+    }
+
+    #[test]
+    fn display_invalid_front_matter() {
+        assert_eq!(
+            format!("{}", Error::InvalidFrontMatter("bad indent".to_string())),
+            "The front matter is not valid YAML: bad indent"
+        );
+    }
+
+    #[test]
+    fn display_unknown_metadata() {
+        assert_eq!(
+            format!("{}", Error::UnknownMetadata("timeout".to_string())),
+            "Unknown metadata timeout, expected one of min_specdown_version, tags, shell or env"
+        );
+    }
+
     #[test]
     fn display_missing_argument() {
         assert_eq!(
@@ -163,6 +262,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_incorrect_list_item_type() {
+        assert_eq!(
+            format!(
+                "{}",
+                Error::FunctionStringParser(function_string_parser::Error::IncorrectListItemType {
+                    function: "test_func".to_string(),
+                    argument: "test_arg".to_string(),
+                    expected: "integer".to_string(),
+                    got: "string".to_string()
+                })
+            ),
+            "Function test_func requires argument test_arg to be a list of integers, got a list containing a string"
+        );
+    }
+
     #[test]
     fn display_invalid_argument_value() {
         assert_eq!(
@@ -178,4 +293,19 @@ mod tests {
             "Argument arg for function func must be true or false, got maybe"
         );
     }
+
+    #[test]
+    fn display_unsupported_argument() {
+        assert_eq!(
+            format!(
+                "{}",
+                Error::UnsupportedArgument {
+                    function: "verify".to_string(),
+                    argument: "display".to_string(),
+                    context: "when verifying a file".to_string(),
+                }
+            ),
+            "Argument display for function verify is not supported when verifying a file"
+        );
+    }
 }
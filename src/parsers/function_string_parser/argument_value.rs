@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum ArgumentValue {
     Integer(i32),
+    Decimal(String),
+    Boolean(bool),
     String(String),
     Token(String),
+    List(Vec<ArgumentValue>),
+    Object(HashMap<String, ArgumentValue>),
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -15,24 +21,71 @@ impl ArgumentValue {
     pub fn integer(&self) -> Result<i32, IncorrectArgumentType> {
         match self {
             Self::Integer(num) => Ok(*num),
-            Self::String(_) => Self::incorrect_argument_type_error("integer", "string"),
-            Self::Token(_) => Self::incorrect_argument_type_error("integer", "token"),
+            other => Self::incorrect_argument_type_error("integer", other.type_name()),
+        }
+    }
+
+    // Numbers are returned as written so the caller decides how to parse them
+    pub fn number(&self) -> Result<String, IncorrectArgumentType> {
+        match self {
+            Self::Integer(num) => Ok(num.to_string()),
+            Self::Decimal(s) | Self::String(s) => Ok(s.clone()),
+            other => Self::incorrect_argument_type_error("number", other.type_name()),
+        }
+    }
+
+    pub fn boolean(&self) -> Result<bool, IncorrectArgumentType> {
+        match self {
+            Self::Boolean(value) => Ok(*value),
+            other => Self::incorrect_argument_type_error("boolean", other.type_name()),
         }
     }
 
     pub fn string(&self) -> Result<String, IncorrectArgumentType> {
         match self {
             Self::String(s) => Ok(s.clone()),
-            Self::Integer(_) => Self::incorrect_argument_type_error("string", "integer"),
-            Self::Token(_) => Self::incorrect_argument_type_error("string", "token"),
+            other => Self::incorrect_argument_type_error("string", other.type_name()),
+        }
+    }
+
+    // Strings which are simple words can be written without quotes in lists
+    pub fn string_or_token(&self) -> Result<String, IncorrectArgumentType> {
+        match self {
+            Self::String(s) | Self::Token(s) => Ok(s.clone()),
+            other => Self::incorrect_argument_type_error("string", other.type_name()),
         }
     }
 
     pub fn token(&self) -> Result<String, IncorrectArgumentType> {
         match self {
             Self::Token(t) => Ok(t.clone()),
-            Self::Integer(_) => Self::incorrect_argument_type_error("token", "integer"),
-            Self::String(_) => Self::incorrect_argument_type_error("token", "string"),
+            other => Self::incorrect_argument_type_error("token", other.type_name()),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<Self>, IncorrectArgumentType> {
+        match self {
+            Self::List(items) => Ok(items.clone()),
+            other => Self::incorrect_argument_type_error("list", other.type_name()),
+        }
+    }
+
+    pub fn object(&self) -> Result<HashMap<String, Self>, IncorrectArgumentType> {
+        match self {
+            Self::Object(fields) => Ok(fields.clone()),
+            other => Self::incorrect_argument_type_error("object", other.type_name()),
+        }
+    }
+
+    const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::Decimal(_) => "decimal",
+            Self::Boolean(_) => "boolean",
+            Self::String(_) => "string",
+            Self::Token(_) => "token",
+            Self::List(_) => "list",
+            Self::Object(_) => "object",
         }
     }
 
@@ -82,6 +135,54 @@ mod test {
         }
     }
 
+    mod boolean {
+        use super::{ArgumentValue, IncorrectArgumentType};
+
+        #[test]
+        fn returns_the_value_when_value_is_a_boolean() {
+            assert_eq!(Ok(true), ArgumentValue::Boolean(true).boolean());
+        }
+
+        #[test]
+        fn returns_error_when_value_is_a_token() {
+            assert_eq!(
+                Err(IncorrectArgumentType {
+                    expected: "boolean".to_string(),
+                    got: "token".to_string(),
+                }),
+                ArgumentValue::Token("yes".to_string()).boolean()
+            );
+        }
+    }
+
+    mod number {
+        use super::{ArgumentValue, IncorrectArgumentType};
+
+        #[test]
+        fn returns_the_text_of_an_integer_a_decimal_or_a_string() {
+            assert_eq!(Ok("2".to_string()), ArgumentValue::Integer(2).number());
+            assert_eq!(
+                Ok("0.01".to_string()),
+                ArgumentValue::Decimal("0.01".to_string()).number()
+            );
+            assert_eq!(
+                Ok("0.05".to_string()),
+                ArgumentValue::String("0.05".to_string()).number()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_value_is_a_token() {
+            assert_eq!(
+                Err(IncorrectArgumentType {
+                    expected: "number".to_string(),
+                    got: "token".to_string(),
+                }),
+                ArgumentValue::Token("small".to_string()).number()
+            );
+        }
+    }
+
     mod string {
         use super::{ArgumentValue, IncorrectArgumentType};
 
@@ -116,6 +217,33 @@ mod test {
         }
     }
 
+    mod string_or_token {
+        use super::{ArgumentValue, IncorrectArgumentType};
+
+        #[test]
+        fn returns_the_text_of_a_string_or_a_token() {
+            assert_eq!(
+                Ok("ci".to_string()),
+                ArgumentValue::String("ci".to_string()).string_or_token()
+            );
+            assert_eq!(
+                Ok("linux".to_string()),
+                ArgumentValue::Token("linux".to_string()).string_or_token()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_value_is_an_integer() {
+            assert_eq!(
+                Err(IncorrectArgumentType {
+                    expected: "string".to_string(),
+                    got: "integer".to_string(),
+                }),
+                ArgumentValue::Integer(1).string_or_token()
+            );
+        }
+    }
+
     mod token {
         use super::{ArgumentValue, IncorrectArgumentType};
 
@@ -149,4 +277,52 @@ mod test {
             );
         }
     }
+
+    mod list {
+        use super::{ArgumentValue, IncorrectArgumentType};
+
+        #[test]
+        fn returns_the_items_when_value_is_a_list() {
+            assert_eq!(
+                Ok(vec![ArgumentValue::Integer(1)]),
+                ArgumentValue::List(vec![ArgumentValue::Integer(1)]).list()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_value_is_a_string() {
+            assert_eq!(
+                Err(IncorrectArgumentType {
+                    expected: "list".to_string(),
+                    got: "string".to_string(),
+                }),
+                ArgumentValue::String("hello".to_string()).list()
+            );
+        }
+    }
+
+    mod object {
+        use super::{ArgumentValue, IncorrectArgumentType};
+        use maplit::hashmap;
+
+        #[test]
+        fn returns_the_fields_when_value_is_an_object() {
+            assert_eq!(
+                Ok(hashmap! {"key".to_string() => ArgumentValue::Integer(1)}),
+                ArgumentValue::Object(hashmap! {"key".to_string() => ArgumentValue::Integer(1)})
+                    .object()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_value_is_a_list() {
+            assert_eq!(
+                Err(IncorrectArgumentType {
+                    expected: "object".to_string(),
+                    got: "list".to_string(),
+                }),
+                ArgumentValue::List(vec![]).object()
+            );
+        }
+    }
 }
@@ -10,4 +10,10 @@ pub enum Error {
         expected: String,
         got: String,
     },
+    IncorrectListItemType {
+        function: String,
+        argument: String,
+        expected: String,
+        got: String,
+    },
 }
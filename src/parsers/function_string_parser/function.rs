@@ -27,6 +27,18 @@ impl Function {
             .map_err(|err| self.incorrect_argument_type_error(name, err))
     }
 
+    pub fn get_boolean_argument(&self, name: &str) -> Result<bool, Error> {
+        self.get_required_argument(name)?
+            .boolean()
+            .map_err(|err| self.incorrect_argument_type_error(name, err))
+    }
+
+    pub fn get_number_argument(&self, name: &str) -> Result<String, Error> {
+        self.get_required_argument(name)?
+            .number()
+            .map_err(|err| self.incorrect_argument_type_error(name, err))
+    }
+
     pub fn get_string_argument(&self, name: &str) -> Result<String, Error> {
         self.get_required_argument(name)?
             .string()
@@ -39,6 +51,32 @@ impl Function {
             .map_err(|err| self.incorrect_argument_type_error(name, err))
     }
 
+    pub fn get_list_argument(&self, name: &str) -> Result<Vec<ArgumentValue>, Error> {
+        self.get_required_argument(name)?
+            .list()
+            .map_err(|err| self.incorrect_argument_type_error(name, err))
+    }
+
+    pub fn get_string_list_argument(&self, name: &str) -> Result<Vec<String>, Error> {
+        self.get_list_argument(name)?
+            .iter()
+            .map(|item| {
+                item.string_or_token()
+                    .map_err(|err| self.incorrect_list_item_type_error(name, err))
+            })
+            .collect()
+    }
+
+    pub fn get_integer_list_argument(&self, name: &str) -> Result<Vec<i32>, Error> {
+        self.get_list_argument(name)?
+            .iter()
+            .map(|item| {
+                item.integer()
+                    .map_err(|err| self.incorrect_list_item_type_error(name, err))
+            })
+            .collect()
+    }
+
     fn get_required_argument(&self, name: &str) -> Result<&ArgumentValue, Error> {
         self.arguments
             .get(name)
@@ -60,6 +98,19 @@ impl Function {
             got,
         }
     }
+
+    fn incorrect_list_item_type_error(
+        &self,
+        argument: &str,
+        IncorrectArgumentType { expected, got }: IncorrectArgumentType,
+    ) -> Error {
+        Error::IncorrectListItemType {
+            function: self.name.clone(),
+            argument: argument.to_string(),
+            expected,
+            got,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -93,4 +144,85 @@ mod test {
             assert!(!f.has_argument("not-arg"));
         }
     }
+
+    mod get_string_list_argument {
+        use super::{ArgumentValue, Function};
+        use crate::parsers::function_string_parser::Error;
+        use maplit::hashmap;
+
+        #[test]
+        fn returns_strings_and_tokens_as_strings() {
+            let f = Function::new(
+                "abc",
+                hashmap! {
+                    "arg".to_string() => ArgumentValue::List(vec![
+                        ArgumentValue::String("ci".to_string()),
+                        ArgumentValue::Token("linux".to_string()),
+                    ]),
+                },
+            );
+            assert_eq!(
+                f.get_string_list_argument("arg"),
+                Ok(vec!["ci".to_string(), "linux".to_string()])
+            );
+        }
+
+        #[test]
+        fn returns_error_when_an_item_is_not_a_string() {
+            let f = Function::new(
+                "abc",
+                hashmap! {
+                    "arg".to_string() => ArgumentValue::List(vec![ArgumentValue::Integer(1)]),
+                },
+            );
+            assert_eq!(
+                f.get_string_list_argument("arg"),
+                Err(Error::IncorrectListItemType {
+                    function: "abc".to_string(),
+                    argument: "arg".to_string(),
+                    expected: "string".to_string(),
+                    got: "integer".to_string(),
+                })
+            );
+        }
+    }
+
+    mod get_integer_list_argument {
+        use super::{ArgumentValue, Function};
+        use crate::parsers::function_string_parser::Error;
+        use maplit::hashmap;
+
+        #[test]
+        fn returns_the_integers() {
+            let f = Function::new(
+                "abc",
+                hashmap! {
+                    "arg".to_string() => ArgumentValue::List(vec![
+                        ArgumentValue::Integer(1),
+                        ArgumentValue::Integer(2),
+                    ]),
+                },
+            );
+            assert_eq!(f.get_integer_list_argument("arg"), Ok(vec![1, 2]));
+        }
+
+        #[test]
+        fn returns_error_when_the_argument_is_not_a_list() {
+            let f = Function::new(
+                "abc",
+                hashmap! {
+                    "arg".to_string() => ArgumentValue::Integer(1),
+                },
+            );
+            assert_eq!(
+                f.get_integer_list_argument("arg"),
+                Err(Error::IncorrectArgumentType {
+                    function: "abc".to_string(),
+                    argument: "arg".to_string(),
+                    expected: "list".to_string(),
+                    got: "integer".to_string(),
+                })
+            );
+        }
+    }
 }
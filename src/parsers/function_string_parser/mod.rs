@@ -1,3 +1,4 @@
+pub use argument_value::ArgumentValue;
 pub use error::Error;
 pub use function::Function;
 pub use parser::parse;
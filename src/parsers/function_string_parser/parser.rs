@@ -3,9 +3,9 @@ use std::collections::HashMap;
 use nom::error::ParseError;
 use nom::{
     branch::alt,
-    bytes::streaming::{tag, take_until},
+    bytes::streaming::tag,
     character::streaming::{alpha1, alphanumeric1, digit1, space0},
-    combinator::map,
+    combinator::{map, map_opt, recognize},
     multi::{many0, separated_list0},
     sequence::{delimited, tuple},
     IResult,
@@ -17,7 +17,7 @@ use super::function::Function;
 pub type Argument<'a> = (&'a str, ArgumentValue);
 
 pub fn parse<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Function, E> {
-    let p = tuple((space0, alpha1, space0, argument_list));
+    let p = tuple((space0, identifier, space0, argument_list));
     map(p, |(_, name, _, arguments)| Function::new(name, arguments))(input)
 }
 
@@ -40,7 +40,7 @@ fn list_of_args_to_hash_map(arguments: &[Argument<'_>]) -> HashMap<String, Argum
         .collect()
 }
 
-fn argument<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Argument<'_>, E> {
+fn argument<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Argument<'a>, E> {
     let p = tuple((
         argument_name,
         tuple((space0, tag("="), space0)),
@@ -49,7 +49,11 @@ fn argument<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Argu
     map(p, |(name, _, value)| (name, value))(input)
 }
 
-fn argument_name<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &str, E> {
+fn argument_name<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    identifier(input)
+}
+
+fn identifier<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
     let mut p = tuple((alpha1, many0(alt((alphanumeric1, tag("_"))))));
     let (remainder, (start, parts)) = p(input)?;
     let length = start.len() + parts.join("").len();
@@ -59,7 +63,15 @@ fn argument_name<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str,
 fn argument_value<'a, E: ParseError<&'a str>>(
     input: &'a str,
 ) -> IResult<&'a str, ArgumentValue, E> {
-    alt((integer_value, string_value, token_value))(input)
+    alt((
+        decimal_value,
+        integer_value,
+        string_value,
+        boolean_value,
+        token_value,
+        list_value,
+        object_value,
+    ))(input)
 }
 
 fn integer_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
@@ -67,13 +79,67 @@ fn integer_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str,
     map(p, |s: &'a str| ArgumentValue::Integer(s.parse().unwrap()))(input)
 }
 
+fn decimal_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
+    let p = recognize(tuple((digit1, tag("."), digit1)));
+    map(p, |s: &'a str| ArgumentValue::Decimal(s.to_string()))(input)
+}
+
+// Supports the escapes `\"`, `\\`, `\n` and `\t`; any other backslash is kept as written
 fn string_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
-    let p = delimited(tag("\""), take_until("\""), tag("\""));
-    map(p, |s: &'a str| ArgumentValue::String(s.to_string()))(input)
+    let (rest, _) = tag("\"")(input)?;
+    let mut value = String::new();
+    let mut chars = rest.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((&rest[index + 1..], ArgumentValue::String(value))),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, escaped @ ('"' | '\\'))) => value.push(escaped),
+                Some((_, other)) => {
+                    value.push('\\');
+                    value.push(other);
+                }
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+
+    Err(nom::Err::Incomplete(nom::Needed::Unknown))
+}
+
+fn list_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
+    let p = delimited(
+        tuple((tag("["), space0)),
+        separated_list0(tuple((space0, tag(","), space0)), argument_value),
+        tuple((space0, tag("]"))),
+    );
+    map(p, ArgumentValue::List)(input)
+}
+
+fn object_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
+    let p = delimited(
+        tuple((tag("{"), space0)),
+        separated_list0(tuple((space0, tag(","), space0)), argument),
+        tuple((space0, tag("}"))),
+    );
+    map(p, |fields| {
+        ArgumentValue::Object(list_of_args_to_hash_map(&fields))
+    })(input)
+}
+
+fn boolean_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
+    map_opt(identifier, |token: &'a str| match token {
+        "true" => Some(ArgumentValue::Boolean(true)),
+        "false" => Some(ArgumentValue::Boolean(false)),
+        _ => None,
+    })(input)
 }
 
 fn token_value<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, ArgumentValue, E> {
-    map(alpha1, |token: &'a str| {
+    map(identifier, |token: &'a str| {
         ArgumentValue::Token(token.to_string())
     })(input)
 }
@@ -100,6 +166,20 @@ mod tests {
             );
         }
 
+        #[test]
+        fn succeeds_when_function_name_contains_underscores() {
+            assert_eq!(
+                parse::<nom::error::Error<&str>>("assert_exists(), more"),
+                Ok((
+                    ", more",
+                    Function {
+                        name: "assert_exists".to_string(),
+                        arguments: HashMap::new(),
+                    }
+                ))
+            );
+        }
+
         #[test]
         fn succeeds_when_function_has_leading_whitespace() {
             assert_eq!(
@@ -369,6 +449,26 @@ mod tests {
             }
         }
 
+        mod decimal_value {
+            use super::{argument_value, ArgumentValue};
+
+            #[test]
+            fn succeeds_when_there_is_a_remainder() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("0.01 leftovers"),
+                    Ok((" leftovers", ArgumentValue::Decimal("0.01".to_string())))
+                );
+            }
+
+            #[test]
+            fn parses_a_number_without_a_fraction_as_an_integer() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("12, leftovers"),
+                    Ok((", leftovers", ArgumentValue::Integer(12)))
+                );
+            }
+        }
+
         mod string_value {
             use super::{argument_value, ArgumentValue};
             use nom::Needed::Unknown;
@@ -392,6 +492,33 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn succeeds_with_escaped_characters() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>(r#""say \"hi\"\n\t\\", more"#),
+                    Ok((
+                        ", more",
+                        ArgumentValue::String("say \"hi\"\n\t\\".to_string())
+                    ))
+                );
+            }
+
+            #[test]
+            fn keeps_unknown_escapes_as_written() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>(r#""\d+""#),
+                    Ok(("", ArgumentValue::String("\\d+".to_string())))
+                );
+            }
+
+            #[test]
+            fn fails_when_the_closing_quote_is_escaped() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>(r#""value\""#),
+                    Err(nom::Err::Incomplete(Unknown))
+                );
+            }
+
             #[test]
             fn fails_when_there_is_no_closing_quote() {
                 assert_eq!(
@@ -401,6 +528,30 @@ mod tests {
             }
         }
 
+        mod boolean_value {
+            use super::{argument_value, ArgumentValue};
+
+            #[test]
+            fn succeeds_for_true_and_false() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("true)"),
+                    Ok((")", ArgumentValue::Boolean(true)))
+                );
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("false)"),
+                    Ok((")", ArgumentValue::Boolean(false)))
+                );
+            }
+
+            #[test]
+            fn tokens_starting_with_true_are_not_booleans() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("trueish)"),
+                    Ok((")", ArgumentValue::Token("trueish".to_string())))
+                );
+            }
+        }
+
         mod token_value {
             use super::{argument_value, ArgumentValue};
 
@@ -413,11 +564,84 @@ mod tests {
                 );
             }
 
+            #[test]
+            fn succeeds_when_the_token_contains_underscores() {
+                let result = argument_value::<nom::error::Error<&str>>("starts_with)");
+                assert_eq!(
+                    result,
+                    Ok((")", ArgumentValue::Token("starts_with".to_string())))
+                );
+            }
+
             // #[test]
             // fn succeeds_when_there_is_no_remainder() {
             //     let result = argument_value("stderr");
             //     assert_eq!(result, Ok(("", ArgumentValue::Token("stderr"))));
             // }
         }
+
+        mod list_value {
+            use super::{argument_value, ArgumentValue};
+
+            #[test]
+            fn succeeds_when_the_list_is_empty() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("[] leftovers"),
+                    Ok((" leftovers", ArgumentValue::List(vec![])))
+                );
+            }
+
+            #[test]
+            fn succeeds_when_the_list_contains_values() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("[ 1, \"two\" ,three ])"),
+                    Ok((
+                        ")",
+                        ArgumentValue::List(vec![
+                            ArgumentValue::Integer(1),
+                            ArgumentValue::String("two".to_string()),
+                            ArgumentValue::Token("three".to_string()),
+                        ])
+                    ))
+                );
+            }
+        }
+
+        mod object_value {
+            use super::{argument_value, ArgumentValue};
+            use maplit::hashmap;
+
+            #[test]
+            fn succeeds_when_the_object_contains_fields() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("{ a=\"x\", b=2 })"),
+                    Ok((
+                        ")",
+                        ArgumentValue::Object(hashmap! {
+                            "a".to_string() => ArgumentValue::String("x".to_string()),
+                            "b".to_string() => ArgumentValue::Integer(2),
+                        })
+                    ))
+                );
+            }
+
+            #[test]
+            fn succeeds_when_objects_are_nested_in_a_list() {
+                assert_eq!(
+                    argument_value::<nom::error::Error<&str>>("[{a=1}, {a=2}])"),
+                    Ok((
+                        ")",
+                        ArgumentValue::List(vec![
+                            ArgumentValue::Object(hashmap! {
+                                "a".to_string() => ArgumentValue::Integer(1),
+                            }),
+                            ArgumentValue::Object(hashmap! {
+                                "a".to_string() => ArgumentValue::Integer(2),
+                            }),
+                        ])
+                    ))
+                );
+            }
+        }
     }
 }
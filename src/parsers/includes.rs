@@ -0,0 +1,169 @@
+use super::code_block_info;
+use super::code_block_type::CodeBlockType;
+use super::error::{Error, Result};
+use super::markdown::{self, Element};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Include paths are relative to the file containing the include
+pub fn expand(elements: Vec<Element>, directory: &Path) -> Result<Vec<Element>> {
+    expand_within(elements, directory, &mut vec![])
+}
+
+fn expand_within(
+    elements: Vec<Element>,
+    directory: &Path,
+    including: &mut Vec<PathBuf>,
+) -> Result<Vec<Element>> {
+    let mut expanded = Vec::with_capacity(elements.len());
+
+    for element in elements {
+        match include_path(&element)? {
+            Some(path) => expanded.extend(include(&path, directory, including)?),
+            None => expanded.push(element),
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn include_path(element: &Element) -> Result<Option<String>> {
+    match element {
+        Element::FencedCodeBlock { info, .. } => Ok(code_block_info::parse(info)?
+            .extra
+            .into_iter()
+            .find_map(|code_block_type| match code_block_type {
+                CodeBlockType::Include(path) => Some(String::from(path)),
+                _ => None,
+            })),
+        Element::FrontMatter(_) | Element::Heading { .. } => Ok(None),
+    }
+}
+
+fn include(path: &str, directory: &Path, including: &mut Vec<PathBuf>) -> Result<Vec<Element>> {
+    let file = directory.join(path);
+    let failed = |reason: String| Error::IncludeFailed {
+        path: path.to_string(),
+        reason,
+    };
+
+    let canonical = fs::canonicalize(&file).map_err(|err| failed(err.to_string()))?;
+    if including.contains(&canonical) {
+        return Err(failed("the file includes itself".to_string()));
+    }
+    let contents = fs::read_to_string(&file).map_err(|err| failed(err.to_string()))?;
+
+    let elements = markdown::parse(&contents)?
+        .into_iter()
+        .filter(|element| !matches!(element, Element::FrontMatter(_)))
+        .collect();
+
+    including.push(canonical);
+    let expanded = expand_within(
+        elements,
+        file.parent().unwrap_or_else(|| Path::new("")),
+        including,
+    );
+    including.pop();
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use crate::parsers::error::Error;
+    use crate::parsers::markdown::{self, Element};
+    use std::fs;
+
+    fn code_block(info: &str, literal: &str) -> Element {
+        Element::FencedCodeBlock {
+            info: info.to_string(),
+            literal: literal.to_string(),
+        }
+    }
+
+    #[test]
+    fn elements_without_includes_are_unchanged() {
+        let elements = vec![code_block("shell,script()", "ls\n")];
+
+        assert_eq!(
+            expand(elements, std::path::Path::new(".")),
+            Ok(vec![code_block("shell,script()", "ls\n")])
+        );
+    }
+
+    #[test]
+    fn included_elements_replace_the_include() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("common")).unwrap();
+        fs::write(
+            dir.path().join("common/setup.md"),
+            "---\ntags: [ignored]\n---\n\n```shell,script()\nsetup\n```\n",
+        )
+        .unwrap();
+        let elements = markdown::parse(
+            "```shell,script()\nfirst\n```\n\n```,include(path=\"common/setup.md\")\n```\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expand(elements, dir.path()),
+            Ok(vec![
+                code_block("shell,script()", "first\n"),
+                code_block("shell,script()", "setup\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn nested_includes_are_relative_to_the_including_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("common")).unwrap();
+        fs::write(
+            dir.path().join("common/outer.md"),
+            "```,include(path=\"inner.md\")\n```\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("common/inner.md"),
+            "```shell,script()\ninner\n```\n",
+        )
+        .unwrap();
+        let elements = vec![code_block(",include(path=\"common/outer.md\")", "")];
+
+        assert_eq!(
+            expand(elements, dir.path()),
+            Ok(vec![code_block("shell,script()", "inner\n")])
+        );
+    }
+
+    #[test]
+    fn fails_when_the_included_file_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let elements = vec![code_block(",include(path=\"missing.md\")", "")];
+
+        assert!(matches!(
+            expand(elements, dir.path()),
+            Err(Error::IncludeFailed { path, .. }) if path == "missing.md"
+        ));
+    }
+
+    #[test]
+    fn fails_when_a_file_includes_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("loop.md"),
+            "```,include(path=\"loop.md\")\n```\n",
+        )
+        .unwrap();
+        let elements = vec![code_block(",include(path=\"loop.md\")", "")];
+
+        assert_eq!(
+            expand(elements, dir.path()),
+            Err(Error::IncludeFailed {
+                path: "loop.md".to_string(),
+                reason: "the file includes itself".to_string(),
+            })
+        );
+    }
+}
@@ -1,4 +1,4 @@
-pub use parser::{parse, Element, Error};
+pub use parser::{is_specdown_comment, options, parse, Element, Error};
 
 pub mod code_block_info;
 mod parser;
@@ -1,4 +1,4 @@
-use comrak::nodes::{AstNode, NodeCodeBlock, NodeValue};
+use comrak::nodes::{AstNode, NodeCodeBlock, NodeHtmlBlock, NodeValue};
 use comrak::{parse_document, Arena, ComrakOptions};
 
 #[derive(Debug, Eq, PartialEq)]
@@ -9,15 +9,30 @@ pub enum Error {
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum Element {
-    FencedCodeBlock { info: String, literal: String },
+    FencedCodeBlock {
+        info: String,
+        literal: String,
+    },
+    FrontMatter(String),
+    Heading {
+        text: String,
+        level: u8,
+        marker: Option<String>,
+    },
 }
 
 pub fn parse(markdown: &str) -> Result<Vec<Element>, Error> {
     let arena = Arena::new();
-    let root = parse_document(&arena, markdown, &ComrakOptions::default());
+    let root = parse_document(&arena, markdown, &options());
     extract_elements(root)
 }
 
+pub fn options() -> ComrakOptions {
+    let mut options = ComrakOptions::default();
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    options
+}
+
 fn extract_elements<'a>(root: &'a AstNode<'a>) -> Result<Vec<Element>, Error> {
     let node_value = &root.data.borrow_mut().value;
 
@@ -34,10 +49,82 @@ fn to_element<'a>(node: &'a AstNode<'a>) -> Option<Result<Element, Error>> {
         NodeValue::CodeBlock(block) => Some(block)
             .filter(|b| b.fenced)
             .map(|b| to_fenced_code_block_element(&b)),
+        NodeValue::FrontMatter(front_matter) => Some(
+            char_vec_to_string(&front_matter).map(|s| Element::FrontMatter(front_matter_body(&s))),
+        ),
+        NodeValue::Heading(heading) => Some(to_heading_element(node, heading.level)),
+        NodeValue::HtmlBlock(block) => to_hidden_code_block_element(&block).transpose(),
         _ => None,
     }
 }
 
+fn to_heading_element<'a>(node: &'a AstNode<'a>, level: u8) -> Result<Element, Error> {
+    Ok(Element::Heading {
+        text: heading_text(node)?.trim().to_string(),
+        level,
+        marker: heading_marker(node)?,
+    })
+}
+
+fn heading_text<'a>(node: &'a AstNode<'a>) -> Result<String, Error> {
+    node.descendants()
+        .filter_map(|descendant| match &descendant.data.borrow().value {
+            NodeValue::Text(text) => Some(char_vec_to_string(text)),
+            NodeValue::Code(code) => Some(char_vec_to_string(&code.literal)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn heading_marker<'a>(node: &'a AstNode<'a>) -> Result<Option<String>, Error> {
+    for descendant in node.descendants() {
+        if let NodeValue::HtmlInline(html) = &descendant.data.borrow().value {
+            if let Some(marker) = specdown_comment(&char_vec_to_string(html)?) {
+                return Ok(Some(marker.trim().to_string()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+pub fn is_specdown_comment(html: &str) -> bool {
+    specdown_comment(html).is_some()
+}
+
+// The text following `specdown:` in an HTML comment
+fn specdown_comment(html: &str) -> Option<&str> {
+    html.trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim_start()
+        .strip_prefix("specdown:")
+}
+
+// A `<!-- specdown: function(...)` comment is a code block which isn't rendered.
+// Its first line is the function and the rest of the comment is the content.
+fn to_hidden_code_block_element(block: &NodeHtmlBlock) -> Result<Option<Element>, Error> {
+    let html = char_vec_to_string(&block.literal)?;
+
+    Ok(specdown_comment(&html).map(|comment| {
+        let (function, literal) = comment.split_once('\n').unwrap_or((comment, ""));
+        Element::FencedCodeBlock {
+            info: format!(",{}", function.trim()),
+            literal: literal.to_string(),
+        }
+    }))
+}
+
+fn front_matter_body(front_matter: &str) -> String {
+    front_matter
+        .lines()
+        .filter(|line| line.trim() != "---")
+        .fold(String::new(), |mut body, line| {
+            body.push_str(line);
+            body.push('\n');
+            body
+        })
+}
+
 fn to_fenced_code_block_element(block: &NodeCodeBlock) -> Result<Element, Error> {
     let (info, literal) = node_block_to_components(block)?;
     let element = Element::FencedCodeBlock { info, literal };
@@ -65,7 +152,7 @@ mod tests {
 
     #[test]
     fn no_actions_returned_when_not_code_blocks_in_markdown() {
-        let markdown = indoc!("# This is markdown");
+        let markdown = indoc!("This is markdown");
 
         assert_eq!(parse(markdown), Ok(vec![]));
     }
@@ -92,6 +179,11 @@ mod tests {
         assert_eq!(
             parse(markdown),
             Ok(vec![
+                Element::Heading {
+                    text: "This is markdown".to_string(),
+                    level: 1,
+                    marker: None,
+                },
                 Element::FencedCodeBlock {
                     info: "info1".to_string(),
                     literal: "literal1\n".to_string(),
@@ -104,10 +196,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn front_matter_is_returned_when_it_exists_in_the_markdown() {
+        let markdown = indoc!(
+            "---
+            key: value
+            ---
+
+            # This is markdown
+            "
+        );
+
+        assert_eq!(
+            parse(markdown),
+            Ok(vec![
+                Element::FrontMatter("key: value\n".to_string()),
+                Element::Heading {
+                    text: "This is markdown".to_string(),
+                    level: 1,
+                    marker: None,
+                },
+            ])
+        );
+    }
+
     #[test]
     fn it_does_not_return_an_element_when_a_code_bloc_is_not_fenced() {
-        let markdown = "# Non-fenced\n    this code block is not fenced";
+        let markdown = "Non-fenced\n\n    this code block is not fenced";
 
         assert_eq!(parse(markdown), Ok(vec![]));
     }
+
+    #[test]
+    fn headings_are_returned_with_their_text() {
+        let markdown = "# Installing *the* `cli`\n\n## Next\n";
+
+        assert_eq!(
+            parse(markdown),
+            Ok(vec![
+                Element::Heading {
+                    text: "Installing the cli".to_string(),
+                    level: 1,
+                    marker: None,
+                },
+                Element::Heading {
+                    text: "Next".to_string(),
+                    level: 2,
+                    marker: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn specdown_comments_are_returned_as_code_blocks() {
+        let markdown = indoc!(
+            "<!-- specdown: script(name=\"setup\")
+            mkdir build
+            -->
+
+            <!-- specdown: skip() -->
+
+            <!-- an ordinary comment -->
+            "
+        );
+
+        assert_eq!(
+            parse(markdown),
+            Ok(vec![
+                Element::FencedCodeBlock {
+                    info: ",script(name=\"setup\")".to_string(),
+                    literal: "mkdir build\n".to_string(),
+                },
+                Element::FencedCodeBlock {
+                    info: ",skip()".to_string(),
+                    literal: String::new(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn headings_are_returned_with_their_specdown_marker() {
+        let markdown = "## Setup <!-- specdown: setup -->\n\n## Notes <!-- a comment -->\n";
+
+        assert_eq!(
+            parse(markdown),
+            Ok(vec![
+                Element::Heading {
+                    text: "Setup".to_string(),
+                    level: 2,
+                    marker: Some("setup".to_string()),
+                },
+                Element::Heading {
+                    text: "Notes".to_string(),
+                    level: 2,
+                    marker: None,
+                },
+            ])
+        );
+    }
 }
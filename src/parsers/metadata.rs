@@ -0,0 +1,227 @@
+use super::version::parse_version;
+use crate::parsers::error::{Error, Result};
+use crate::types::Metadata;
+use yaml_rust2::{Yaml, YamlLoader};
+
+pub fn parse(front_matter: &str) -> Result<Metadata> {
+    let mut metadata = Metadata::default();
+
+    let documents = YamlLoader::load_from_str(front_matter)
+        .map_err(|err| Error::InvalidFrontMatter(err.to_string()))?;
+    let Some(Yaml::Hash(entries)) = documents.into_iter().next() else {
+        return Ok(metadata);
+    };
+
+    for (key, value) in &entries {
+        match key.as_str() {
+            Some("min_specdown_version") => {
+                let version = to_scalar(value).unwrap_or_default();
+                metadata.min_specdown_version =
+                    Some(parse_version(&version).map_err(|_| Error::InvalidMetadata {
+                        key: "min_specdown_version".to_string(),
+                        expected: "a version in the format MAJOR.MINOR.PATCH".to_string(),
+                        got: version,
+                    })?);
+            }
+            Some("tags") => metadata.tags = parse_tags(value),
+            Some("shell") => {
+                metadata.shell = Some(to_scalar(value).ok_or_else(|| Error::InvalidMetadata {
+                    key: "shell".to_string(),
+                    expected: "a shell command".to_string(),
+                    got: describe(value),
+                })?);
+            }
+            Some("env") => metadata.env = parse_env(value)?,
+            _ => return Err(Error::UnknownMetadata(describe(key))),
+        }
+    }
+
+    Ok(metadata)
+}
+
+fn parse_tags(value: &Yaml) -> Vec<String> {
+    match value {
+        Yaml::Array(tags) => tags.iter().filter_map(to_scalar).collect(),
+        _ => to_scalar(value)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect(),
+    }
+}
+
+fn parse_env(value: &Yaml) -> Result<Vec<(String, String)>> {
+    let invalid = || Error::InvalidMetadata {
+        key: "env".to_string(),
+        expected: "a map of variable names to values".to_string(),
+        got: describe(value),
+    };
+
+    match value {
+        Yaml::Hash(variables) => variables
+            .iter()
+            .map(|(name, value)| match (to_scalar(name), value) {
+                (Some(name), Yaml::Null) => Ok((name, String::new())),
+                (Some(name), value) => Ok((name, to_scalar(value).ok_or_else(invalid)?)),
+                (None, _) => Err(invalid()),
+            })
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+fn to_scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(string) | Yaml::Real(string) => Some(string.clone()),
+        Yaml::Integer(integer) => Some(integer.to_string()),
+        Yaml::Boolean(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+fn describe(value: &Yaml) -> String {
+    match value {
+        Yaml::Array(_) => "a list".to_string(),
+        Yaml::Hash(_) => "a map".to_string(),
+        Yaml::Null => "nothing".to_string(),
+        _ => to_scalar(value).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Error, Metadata};
+    use crate::types::Version;
+
+    #[test]
+    fn returns_default_metadata_when_empty() {
+        assert_eq!(parse(""), Ok(Metadata::default()));
+    }
+
+    #[test]
+    fn parses_min_specdown_version() {
+        assert_eq!(
+            parse("min_specdown_version: 1.2.3\n"),
+            Ok(Metadata {
+                min_specdown_version: Some(Version::new(1, 2, 3)),
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_quoted_min_specdown_version() {
+        assert_eq!(
+            parse("min_specdown_version: \"1.2.3\"\n"),
+            Ok(Metadata {
+                min_specdown_version: Some(Version::new(1, 2, 3)),
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_comma_separated_list_of_tags() {
+        assert_eq!(
+            parse("tags: smoke, linux\n"),
+            Ok(Metadata {
+                tags: vec!["smoke".to_string(), "linux".to_string()],
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_bracketed_list_of_tags() {
+        assert_eq!(
+            parse("tags: [\"smoke\", 'linux']\n"),
+            Ok(Metadata {
+                tags: vec!["smoke".to_string(), "linux".to_string()],
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_comments() {
+        assert_eq!(
+            parse("# comment\ntags: smoke\n"),
+            Ok(Metadata {
+                tags: vec!["smoke".to_string()],
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_min_specdown_version_is_invalid() {
+        assert_eq!(
+            parse("min_specdown_version: latest\n"),
+            Err(Error::InvalidMetadata {
+                key: "min_specdown_version".to_string(),
+                expected: "a version in the format MAJOR.MINOR.PATCH".to_string(),
+                got: "latest".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_the_shell_command() {
+        assert_eq!(
+            parse("shell: sh -c\n"),
+            Ok(Metadata {
+                shell: Some("sh -c".to_string()),
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_a_map_of_environment_variables() {
+        assert_eq!(
+            parse("env:\n  GREETING: hello\n  COUNT: 3\n  EMPTY:\n"),
+            Ok(Metadata {
+                env: vec![
+                    ("GREETING".to_string(), "hello".to_string()),
+                    ("COUNT".to_string(), "3".to_string()),
+                    ("EMPTY".to_string(), String::new()),
+                ],
+                ..Metadata::default()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_env_is_not_a_map() {
+        assert_eq!(
+            parse("env: [A, B]\n"),
+            Err(Error::InvalidMetadata {
+                key: "env".to_string(),
+                expected: "a map of variable names to values".to_string(),
+                got: "a list".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_a_key_is_not_recognised() {
+        assert_eq!(
+            parse("shel: sh -c\n"),
+            Err(Error::UnknownMetadata("shel".to_string()))
+        );
+        assert_eq!(
+            parse("timeout: 30\n"),
+            Err(Error::UnknownMetadata("timeout".to_string()))
+        );
+    }
+
+    #[test]
+    fn fails_when_the_front_matter_is_not_valid_yaml() {
+        assert!(matches!(
+            parse("tags: [smoke\n"),
+            Err(Error::InvalidFrontMatter(_))
+        ));
+    }
+}
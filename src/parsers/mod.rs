@@ -1,30 +1,97 @@
-use crate::types::Action;
+use crate::types::{Action, Metadata, SectionPath, Spec};
+use std::path::Path;
 
 mod actions;
 mod code_block_info;
 mod code_block_type;
+mod dump_ast;
 mod error;
 mod function_string_parser;
+mod includes;
 mod markdown;
+mod metadata;
+mod sections;
 mod strip;
+mod version;
 
 use error::Result;
 
+pub use dump_ast::dump_ast;
 pub use strip::strip;
+pub use version::parse_version;
 
-pub fn parse(markdown: &str) -> Result<Vec<Action>> {
-    markdown::parse(markdown)?
+pub fn parse(markdown: &str, directory: &Path) -> Result<Spec> {
+    let elements = markdown::parse(markdown)?;
+
+    let metadata = elements
         .iter()
-        .map(to_action)
-        .collect::<Result<Vec<Option<Action>>>>()
-        .map(|actions| actions.into_iter().flatten().collect())
+        .find_map(|element| match element {
+            markdown::Element::FrontMatter(front_matter) => Some(metadata::parse(front_matter)),
+            markdown::Element::FencedCodeBlock { .. } | markdown::Element::Heading { .. } => None,
+        })
+        .unwrap_or_else(|| Ok(Metadata::default()))?;
+    let elements = includes::expand(elements, directory)?;
+
+    let mut section_paths = sections::paths(elements.iter().filter_map(|element| match element {
+        markdown::Element::Heading { text, level, .. } => Some((*level, text.as_str())),
+        markdown::Element::FrontMatter(_) | markdown::Element::FencedCodeBlock { .. } => None,
+    }))
+    .into_iter();
+
+    let mut heading = None;
+    let mut section = SectionPath::default();
+    let mut headings = vec![];
+    let mut actions = vec![];
+    let mut action_sections = vec![];
+    for element in &elements {
+        if let markdown::Element::Heading {
+            text,
+            level,
+            marker,
+        } = element
+        {
+            heading = Some(text.as_str());
+            section = section_paths.next().unwrap_or_default();
+            headings.push(sections::Heading {
+                position: actions.len(),
+                level: *level,
+                role: sections::to_role(marker.as_deref())?,
+            });
+        } else {
+            for action in to_actions(element)? {
+                actions.push((heading, action));
+                action_sections.push(section.clone());
+            }
+        }
+    }
+
+    let actions = actions::name_scripts(actions)
+        .into_iter()
+        .zip(action_sections)
+        .map(|(action, section)| sections::Sectioned { section, action })
+        .collect();
+    let actions = sections::expand(actions, &headings);
+    let actions = actions::group_alternatives(actions);
+    let actions = actions::inherit_tags(actions, &metadata.tags);
+    let (actions, sections) = actions::move_cleanup_to_end(actions)
+        .into_iter()
+        .map(|sectioned| (sectioned.action, sectioned.section))
+        .unzip();
+
+    Ok(Spec {
+        metadata,
+        actions,
+        sections,
+    })
 }
 
-fn to_action(element: &markdown::Element) -> Result<Option<Action>> {
+fn to_actions(element: &markdown::Element) -> Result<Vec<Action>> {
     match element {
-        markdown::Element::FencedCodeBlock { info, literal } => {
-            let code_block_type = code_block_info::parse(info)?.extra;
-            Ok(actions::create_action(&code_block_type, literal.clone()))
-        }
+        markdown::Element::FencedCodeBlock { info, literal } => Ok(code_block_info::parse(info)?
+            .extra
+            .iter()
+            .flat_map(|code_block_type| actions::create_actions(code_block_type, literal.clone()))
+            .collect()),
+        markdown::Element::FrontMatter(_) | markdown::Element::Heading { .. } => Ok(vec![]),
     }
 }
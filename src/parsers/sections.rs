@@ -0,0 +1,320 @@
+use super::error::{Error, Result};
+use crate::types::{Action, SectionPath};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Setup,
+    Teardown,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Heading {
+    pub position: usize,
+    pub level: u8,
+    pub role: Option<Role>,
+}
+
+pub fn to_role(marker: Option<&str>) -> Result<Option<Role>> {
+    match marker {
+        None => Ok(None),
+        Some("setup") => Ok(Some(Role::Setup)),
+        Some("teardown") => Ok(Some(Role::Teardown)),
+        Some(other) => Err(Error::UnknownSectionMarker(other.to_string())),
+    }
+}
+
+// An action with the section it was written in, which stays with the action as
+// it is copied and reordered
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sectioned {
+    pub section: SectionPath,
+    pub action: Action,
+}
+
+impl AsRef<Action> for Sectioned {
+    fn as_ref(&self) -> &Action {
+        &self.action
+    }
+}
+
+impl AsMut<Action> for Sectioned {
+    fn as_mut(&mut self) -> &mut Action {
+        &mut self.action
+    }
+}
+
+// Each heading closes any open heading at the same or a deeper level
+pub fn paths<'a>(headings: impl IntoIterator<Item = (u8, &'a str)>) -> Vec<SectionPath> {
+    let mut open: Vec<(u8, &str)> = vec![];
+
+    headings
+        .into_iter()
+        .map(|(level, text)| {
+            while open
+                .last()
+                .is_some_and(|(open_level, _)| *open_level >= level)
+            {
+                open.pop();
+            }
+            open.push((level, text));
+            SectionPath(open.iter().map(|(_, text)| (*text).to_string()).collect())
+        })
+        .collect()
+}
+
+struct Hooks<T> {
+    level: u8,
+    setup: Vec<T>,
+    teardown: Vec<T>,
+}
+
+// Actions under a setup or teardown heading are moved into every following
+// section at the same heading level, until a higher level heading is reached
+pub fn expand<T: Clone>(actions: Vec<T>, headings: &[Heading]) -> Vec<T> {
+    if headings.iter().all(|heading| heading.role.is_none()) {
+        return actions;
+    }
+
+    let mut expanded = Vec::with_capacity(actions.len());
+    let mut hooks: Option<Hooks<T>> = None;
+    let mut collecting = None;
+    let mut in_section = false;
+    let mut headings = headings.iter().peekable();
+
+    for (position, action) in actions.into_iter().enumerate() {
+        while let Some(heading) = headings.next_if(|heading| heading.position <= position) {
+            enter_heading(
+                heading,
+                &mut hooks,
+                &mut collecting,
+                &mut in_section,
+                &mut expanded,
+            );
+        }
+
+        match (collecting, hooks.as_mut()) {
+            (Some(Role::Setup), Some(hooks)) => hooks.setup.push(action),
+            (Some(Role::Teardown), Some(hooks)) => hooks.teardown.push(action),
+            _ => expanded.push(action),
+        }
+    }
+
+    for heading in headings {
+        enter_heading(
+            heading,
+            &mut hooks,
+            &mut collecting,
+            &mut in_section,
+            &mut expanded,
+        );
+    }
+
+    if let (true, Some(hooks)) = (in_section, &hooks) {
+        expanded.extend(hooks.teardown.iter().cloned());
+    }
+
+    expanded
+}
+
+fn enter_heading<T: Clone>(
+    heading: &Heading,
+    hooks: &mut Option<Hooks<T>>,
+    collecting: &mut Option<Role>,
+    in_section: &mut bool,
+    expanded: &mut Vec<T>,
+) {
+    let ends_section = hooks.as_ref().is_some_and(|h| heading.level <= h.level);
+
+    if ends_section {
+        if let (true, Some(current)) = (*in_section, hooks.as_ref()) {
+            expanded.extend(current.teardown.iter().cloned());
+        }
+        *in_section = false;
+        *collecting = None;
+    }
+
+    if let Some(role) = heading.role {
+        if hooks.as_ref().is_none_or(|h| h.level != heading.level) {
+            *hooks = Some(Hooks {
+                level: heading.level,
+                setup: vec![],
+                teardown: vec![],
+            });
+        }
+        *collecting = Some(role);
+    } else if ends_section {
+        match hooks.as_ref() {
+            Some(current) if current.level == heading.level => {
+                expanded.extend(current.setup.iter().cloned());
+                *in_section = true;
+            }
+            _ => *hooks = None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand, paths, to_role, Heading, Role};
+    use crate::parsers::error::Error;
+    use crate::types::{Action, NoteAction, SectionPath};
+
+    fn note(text: &str) -> Action {
+        Action::Note(NoteAction {
+            text: text.to_string(),
+        })
+    }
+
+    fn heading(position: usize, level: u8, role: Option<Role>) -> Heading {
+        Heading {
+            position,
+            level,
+            role,
+        }
+    }
+
+    #[test]
+    fn to_role_parses_known_markers() {
+        assert_eq!(to_role(None), Ok(None));
+        assert_eq!(to_role(Some("setup")), Ok(Some(Role::Setup)));
+        assert_eq!(to_role(Some("teardown")), Ok(Some(Role::Teardown)));
+    }
+
+    #[test]
+    fn to_role_fails_for_unknown_markers() {
+        assert_eq!(
+            to_role(Some("before")),
+            Err(Error::UnknownSectionMarker("before".to_string()))
+        );
+    }
+
+    fn path(headings: &[&str]) -> SectionPath {
+        SectionPath(headings.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn paths_follow_the_heading_hierarchy() {
+        let headings = [
+            (1, "Guide"),
+            (2, "Installation"),
+            (3, "From source"),
+            (3, "From a package"),
+            (2, "Usage"),
+            (1, "Reference"),
+        ];
+
+        assert_eq!(
+            paths(headings.iter().copied()),
+            vec![
+                path(&["Guide"]),
+                path(&["Guide", "Installation"]),
+                path(&["Guide", "Installation", "From source"]),
+                path(&["Guide", "Installation", "From a package"]),
+                path(&["Guide", "Usage"]),
+                path(&["Reference"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_skip_missing_heading_levels() {
+        assert_eq!(
+            paths(vec![(1, "Guide"), (3, "Detail"), (2, "Usage")]),
+            vec![
+                path(&["Guide"]),
+                path(&["Guide", "Detail"]),
+                path(&["Guide", "Usage"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn actions_are_unchanged_without_marked_headings() {
+        let actions = vec![note("a"), note("b")];
+
+        assert_eq!(
+            expand(actions.clone(), &[heading(0, 2, None), heading(1, 2, None)]),
+            actions
+        );
+    }
+
+    #[test]
+    fn setup_and_teardown_wrap_each_following_section() {
+        let actions = vec![
+            note("first"),
+            note("setup"),
+            note("teardown"),
+            note("one"),
+            note("two"),
+        ];
+        let headings = [
+            heading(1, 2, Some(Role::Setup)),
+            heading(2, 2, Some(Role::Teardown)),
+            heading(3, 2, None),
+            heading(4, 2, None),
+        ];
+
+        assert_eq!(
+            expand(actions, &headings),
+            vec![
+                note("first"),
+                note("setup"),
+                note("one"),
+                note("teardown"),
+                note("setup"),
+                note("two"),
+                note("teardown"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sub_headings_stay_within_their_section() {
+        let actions = vec![
+            note("setup"),
+            note("setup detail"),
+            note("one"),
+            note("one detail"),
+        ];
+        let headings = [
+            heading(0, 2, Some(Role::Setup)),
+            heading(1, 3, None),
+            heading(2, 2, None),
+            heading(3, 3, None),
+        ];
+
+        assert_eq!(
+            expand(actions, &headings),
+            vec![
+                note("setup"),
+                note("setup detail"),
+                note("one"),
+                note("one detail"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_higher_level_heading_ends_the_setup_scope() {
+        let actions = vec![note("setup"), note("one"), note("outside"), note("two")];
+        let headings = [
+            heading(0, 2, Some(Role::Setup)),
+            heading(1, 2, None),
+            heading(2, 1, None),
+            heading(3, 2, None),
+        ];
+
+        assert_eq!(
+            expand(actions, &headings),
+            vec![note("setup"), note("one"), note("outside"), note("two")]
+        );
+    }
+
+    #[test]
+    fn teardown_runs_after_a_section_with_no_actions() {
+        let actions = vec![note("teardown")];
+        let headings = [heading(0, 2, Some(Role::Teardown)), heading(1, 2, None)];
+
+        assert_eq!(expand(actions, &headings), vec![note("teardown")]);
+    }
+}
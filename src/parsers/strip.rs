@@ -1,14 +1,30 @@
 use self::comrak::nodes::NodeCodeBlock;
 use comrak;
 use comrak::nodes::{AstNode, NodeValue};
-use comrak::{format_commonmark, parse_document, Arena, ComrakOptions};
+use comrak::{format_commonmark, parse_document, Arena};
+use std::collections::HashMap;
 
 use super::code_block_info;
+use super::markdown;
 
-pub fn strip(markdown: &str) -> String {
+pub fn strip(markdown: &str, language_map: &HashMap<String, String>) -> String {
     let arena = Arena::new();
 
-    let root = parse_document(&arena, markdown, &ComrakOptions::default());
+    let options = markdown::options();
+    let root = parse_document(&arena, markdown, &options);
+
+    let hidden_blocks: Vec<_> = root
+        .descendants()
+        .filter(|node| match &node.data.borrow().value {
+            NodeValue::HtmlBlock(block) => {
+                markdown::is_specdown_comment(&String::from_utf8_lossy(&block.literal))
+            }
+            _ => false,
+        })
+        .collect();
+    for node in hidden_blocks {
+        node.detach();
+    }
 
     iter_nodes(root, &|node| {
         if let NodeValue::CodeBlock(NodeCodeBlock { ref mut info, .. }) =
@@ -18,12 +34,13 @@ pub fn strip(markdown: &str) -> String {
             let language = code_block_info::parse(&info_string)
                 .expect("To parse codeblock info")
                 .language;
-            *info = Vec::from(language);
+            let language = language_map.get(&language).unwrap_or(&language);
+            *info = Vec::from(language.as_str());
         }
     });
 
     let mut result = vec![];
-    format_commonmark(root, &ComrakOptions::default(), &mut result).unwrap();
+    format_commonmark(root, &options, &mut result).unwrap();
     String::from_utf8(result).unwrap()
 }
 
@@ -44,6 +61,8 @@ mod tests {
     mod strip {
         use super::strip;
         use indoc::indoc;
+        use maplit::hashmap;
+        use std::collections::HashMap;
 
         #[test]
         fn test_strip() {
@@ -67,7 +86,67 @@ mod tests {
                 "
             );
 
-            assert_eq!(strip(markdown), expected.to_string());
+            assert_eq!(strip(markdown, &HashMap::new()), expected.to_string());
+        }
+
+        #[test]
+        fn test_strip_removes_specdown_comments() {
+            let markdown = indoc!(
+                "
+                # Header
+
+                <!-- specdown: script(name=\"setup\")
+                mkdir build
+                -->
+
+                <!-- an ordinary comment -->
+                "
+            );
+
+            let expected = indoc!(
+                "
+                # Header
+
+                <!-- an ordinary comment -->
+                "
+            );
+
+            assert_eq!(strip(markdown, &HashMap::new()), expected.to_string());
+        }
+
+        #[test]
+        fn test_strip_maps_languages() {
+            let markdown = indoc!(
+                "
+                ```shell, script(name=\"something\")
+                run
+                ```
+
+                ```text, verify(script_name=\"something\")
+                output
+                ```
+                "
+            );
+
+            let expected = indoc!(
+                "
+                ``` bash
+                run
+                ```
+
+                ``` text
+                output
+                ```
+                "
+            );
+
+            assert_eq!(
+                strip(
+                    markdown,
+                    &hashmap! {"shell".to_string() => "bash".to_string()}
+                ),
+                expected.to_string()
+            );
         }
     }
 }
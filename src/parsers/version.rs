@@ -0,0 +1,209 @@
+use crate::types::{PreRelease, Version};
+use std::fmt;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum InvalidVersion {
+    WrongNumberOfParts,
+    InvalidNumber { part: &'static str, got: String },
+    InvalidIdentifier { got: String },
+}
+
+impl fmt::Display for InvalidVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongNumberOfParts => write!(f, "it does not have the format MAJOR.MINOR.PATCH"),
+            Self::InvalidNumber { part, got } => {
+                write!(
+                    f,
+                    "the {part} version {got:?} is not a number without leading zeros"
+                )
+            }
+            Self::InvalidIdentifier { got } => write!(
+                f,
+                "{got:?} is not an identifier made of letters, digits and hyphens"
+            ),
+        }
+    }
+}
+
+// Parses a semantic version such as `1.2.3-rc.1+build.5`, allowing a leading `v`
+pub fn parse_version(version: &str) -> Result<Version, InvalidVersion> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let (version, build) = version
+        .split_once('+')
+        .map_or((version, None), |(version, build)| (version, Some(build)));
+    let (core, pre_release) = version
+        .split_once('-')
+        .map_or((version, None), |(core, pre_release)| {
+            (core, Some(pre_release))
+        });
+
+    let [major, minor, patch] = core.split('.').collect::<Vec<_>>()[..] else {
+        return Err(InvalidVersion::WrongNumberOfParts);
+    };
+
+    let version = Version::new(
+        parse_number("major", major)?,
+        parse_number("minor", minor)?,
+        parse_number("patch", patch)?,
+    );
+
+    Ok(Version {
+        pre_release: pre_release.map_or(Ok(vec![]), |pre_release| {
+            identifiers(pre_release)?
+                .into_iter()
+                .map(parse_pre_release)
+                .collect()
+        })?,
+        build: build.map_or(Ok(vec![]), |build| {
+            identifiers(build).map(|ids| ids.into_iter().map(String::from).collect())
+        })?,
+        ..version
+    })
+}
+
+fn parse_number(part: &'static str, text: &str) -> Result<u32, InvalidVersion> {
+    if has_leading_zero(text) || !text.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid_number(part, text));
+    }
+    text.parse().map_err(|_| invalid_number(part, text))
+}
+
+fn invalid_number(part: &'static str, text: &str) -> InvalidVersion {
+    InvalidVersion::InvalidNumber {
+        part,
+        got: text.to_string(),
+    }
+}
+
+fn identifiers(text: &str) -> Result<Vec<&str>, InvalidVersion> {
+    text.split('.')
+        .map(|identifier| {
+            if identifier.is_empty()
+                || !identifier
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            {
+                Err(InvalidVersion::InvalidIdentifier {
+                    got: identifier.to_string(),
+                })
+            } else {
+                Ok(identifier)
+            }
+        })
+        .collect()
+}
+
+fn parse_pre_release(identifier: &str) -> Result<PreRelease, InvalidVersion> {
+    if !identifier.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(PreRelease::Alphanumeric(identifier.to_string()));
+    }
+
+    match identifier.parse() {
+        Ok(number) if !has_leading_zero(identifier) => Ok(PreRelease::Numeric(number)),
+        _ => Err(InvalidVersion::InvalidIdentifier {
+            got: identifier.to_string(),
+        }),
+    }
+}
+
+fn has_leading_zero(text: &str) -> bool {
+    text.len() > 1 && text.starts_with('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_version, InvalidVersion, PreRelease, Version};
+
+    #[test]
+    fn parses_major_minor_and_patch() {
+        assert_eq!(parse_version("1.2.3"), Ok(Version::new(1, 2, 3)));
+    }
+
+    #[test]
+    fn strips_a_leading_v() {
+        assert_eq!(parse_version("v1.2.3"), Ok(Version::new(1, 2, 3)));
+    }
+
+    #[test]
+    fn parses_pre_release_identifiers() {
+        assert_eq!(
+            parse_version("1.2.3-rc.1").map(|version| version.pre_release),
+            Ok(vec![
+                PreRelease::Alphanumeric("rc".to_string()),
+                PreRelease::Numeric(1)
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_build_metadata() {
+        assert_eq!(
+            parse_version("1.2.3-rc.1+build.5").map(|version| version.to_string()),
+            Ok("1.2.3-rc.1+build.5".to_string())
+        );
+        assert_eq!(
+            parse_version("1.2.3+build.5").map(|version| version.build),
+            Ok(vec!["build".to_string(), "5".to_string()])
+        );
+    }
+
+    #[test]
+    fn orders_versions_by_semver_precedence() {
+        let versions: Vec<Version> = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ]
+        .iter()
+        .map(|version| parse_version(version).unwrap())
+        .collect();
+
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn ignores_build_metadata_when_comparing() {
+        assert_eq!(parse_version("1.2.3+build.5"), parse_version("1.2.3"));
+    }
+
+    #[test]
+    fn fails_when_there_are_not_three_parts() {
+        assert_eq!(
+            parse_version("1.2"),
+            Err(InvalidVersion::WrongNumberOfParts)
+        );
+    }
+
+    #[test]
+    fn fails_when_a_number_has_a_leading_zero() {
+        assert_eq!(
+            parse_version("01.2.3"),
+            Err(InvalidVersion::InvalidNumber {
+                part: "major",
+                got: "01".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_a_pre_release_identifier_is_empty() {
+        assert_eq!(
+            parse_version("1.2.3-rc..1"),
+            Err(InvalidVersion::InvalidIdentifier { got: String::new() })
+        );
+    }
+
+    #[test]
+    fn explains_why_the_version_is_invalid() {
+        assert_eq!(
+            parse_version("1.x.3").unwrap_err().to_string(),
+            "the minor version \"x\" is not a number without leading zeros"
+        );
+    }
+}
@@ -1,34 +1,93 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use crate::types::{
-    CreateFileAction, ExitCode, OutputExpectation, ScriptAction, VerifyAction, VerifyValue,
+    Action, CreateFileAction, ExitCode, FileMode, NoteAction, OutputExpectation, ScriptAction,
+    SetEnvAction, StdinAction, Stream, VerifyAction, VerifyDirectoryAction, VerifyFileAction,
 };
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum ActionError {
     ExitCodeIsIncorrect(ScriptResult),
     UnexpectedOutputIsPresent(ScriptResult),
+    NotIdempotent(ScriptResult),
+    NotStable(ScriptResult),
     OutputDoesNotMatch(VerifyResult),
+    DirectoryDoesNotMatch(VerifyDirectoryResult),
+    FileDoesNotMatch(VerifyFileResult),
 }
 
 trait ActionErrorProvider {
     fn error(&self) -> Option<ActionError>;
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RawOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ScriptResult {
     pub action: ScriptAction,
     pub exit_code: Option<ExitCode>,
     pub stdout: String,
     pub stderr: String,
+    pub output: String,
+    pub raw_output: RawOutput,
+    pub durations: Vec<Duration>,
+    pub idempotency_failure: Option<IdempotencyFailure>,
+    pub stability_failure: Option<StabilityFailure>,
+    pub workdir: Option<PathBuf>,
+    pub pid: Option<u32>,
+    pub signal: Option<i32>,
+    pub environment: Option<Box<EnvironmentSnapshot>>,
+    pub attempts: u32,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EnvironmentSnapshot {
+    pub before: BTreeMap<String, String>,
+    pub after: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IdempotencyFailure {
+    OutputChanged { first_stdout: String },
+    ChangedMarkerPrinted { marker: String },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StabilityFailure {
+    pub run: u32,
+    pub first_stdout: String,
+    pub stdout: String,
 }
 
 impl ActionErrorProvider for ScriptResult {
     fn error(&self) -> Option<ActionError> {
-        if self.action.expected_exit_code.is_some()
-            && self.action.expected_exit_code != self.exit_code
-        {
+        let exited_incorrectly = match self.action.options.expected_signal {
+            Some(expected_signal) => self.signal != Some(expected_signal),
+            None => self
+                .action
+                .expected_exit_code
+                .as_ref()
+                .is_some_and(|expected| !self.exit_code.is_some_and(|code| expected.matches(code))),
+        };
+        if exited_incorrectly {
             return Some(ActionError::ExitCodeIsIncorrect(self.clone()));
         }
 
+        if self.idempotency_failure.is_some() {
+            return Some(ActionError::NotIdempotent(self.clone()));
+        }
+
+        if self.stability_failure.is_some() {
+            return Some(ActionError::NotStable(self.clone()));
+        }
+
         if self.action.expected_output == OutputExpectation::StdOut && !self.stderr.is_empty() {
             return Some(ActionError::UnexpectedOutputIsPresent(self.clone()));
         }
@@ -49,22 +108,90 @@ impl ActionErrorProvider for ScriptResult {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerifyFailure {
+    OutputDoesNotMatch,
+    MatcherFailed {
+        reason: String,
+    },
+    InvalidEncoding {
+        reason: String,
+    },
+    BytesDoNotMatch {
+        offset: usize,
+    },
+    NoAlternativeMatched,
+    FileMissing,
+    FileExists,
+    ModeDoesNotMatch {
+        expected: FileMode,
+        got: FileMode,
+    },
+    UnexpectedStream {
+        stream: Stream,
+    },
+    EnvironmentDoesNotMatch {
+        name: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Displayed {
+    Transformed { expected: String, got: String },
+    TransformFailed { command: String, message: String },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VerifyResult {
     pub action: VerifyAction,
     pub got: String,
+    pub failure: Option<VerifyFailure>,
+    pub displayed: Option<Displayed>,
 }
 
 impl ActionErrorProvider for VerifyResult {
     fn error(&self) -> Option<ActionError> {
-        if self.action.expected_value == VerifyValue(self.got.clone()) {
+        self.failure
+            .as_ref()
+            .map(|_| ActionError::OutputDoesNotMatch(self.clone()))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyDirectoryResult {
+    pub action: VerifyDirectoryAction,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+    pub differing: Vec<String>,
+}
+
+impl ActionErrorProvider for VerifyDirectoryResult {
+    fn error(&self) -> Option<ActionError> {
+        if self.missing.is_empty() && self.extra.is_empty() && self.differing.is_empty() {
             None
         } else {
-            Some(ActionError::OutputDoesNotMatch(self.clone()))
+            Some(ActionError::DirectoryDoesNotMatch(self.clone()))
         }
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyFileResult {
+    pub action: VerifyFileAction,
+    pub got: String,
+    pub failure: Option<VerifyFailure>,
+}
+
+impl ActionErrorProvider for VerifyFileResult {
+    fn error(&self) -> Option<ActionError> {
+        self.failure
+            .as_ref()
+            .map(|_| ActionError::FileDoesNotMatch(self.clone()))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CreateFileResult {
     pub action: CreateFileAction,
@@ -76,11 +203,62 @@ impl ActionErrorProvider for CreateFileResult {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NoteResult {
+    pub action: NoteAction,
+}
+
+impl ActionErrorProvider for NoteResult {
+    fn error(&self) -> Option<ActionError> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetEnvResult {
+    pub action: SetEnvAction,
+}
+
+impl ActionErrorProvider for SetEnvResult {
+    fn error(&self) -> Option<ActionError> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdinResult {
+    pub action: StdinAction,
+}
+
+impl ActionErrorProvider for StdinResult {
+    fn error(&self) -> Option<ActionError> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SkippedResult {
+    pub action: Action,
+    pub reason: Option<String>,
+}
+
+impl ActionErrorProvider for SkippedResult {
+    fn error(&self) -> Option<ActionError> {
+        None
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ActionResult {
     Script(ScriptResult),
     Verify(VerifyResult),
+    VerifyDirectory(VerifyDirectoryResult),
+    VerifyFile(VerifyFileResult),
     CreateFile(CreateFileResult),
+    Note(NoteResult),
+    SetEnv(SetEnvResult),
+    Stdin(StdinResult),
+    Skipped(SkippedResult),
 }
 
 impl ActionResult {
@@ -92,25 +270,52 @@ impl ActionResult {
         self.as_error_provider().error()
     }
 
+    pub const fn failure_allowed(&self) -> bool {
+        match self {
+            Self::Script(result) => result.action.options.allow_failure,
+            Self::Verify(result) => result.action.options.allow_failure,
+            Self::VerifyFile(result) => result.action.options.allow_failure,
+            Self::VerifyDirectory(_)
+            | Self::CreateFile(_)
+            | Self::Note(_)
+            | Self::SetEnv(_)
+            | Self::Stdin(_)
+            | Self::Skipped(_) => false,
+        }
+    }
+
     fn as_error_provider(&self) -> &dyn ActionErrorProvider {
         match self {
             Self::Script(result) => result,
             Self::Verify(result) => result,
+            Self::VerifyDirectory(result) => result,
+            Self::VerifyFile(result) => result,
             Self::CreateFile(result) => result,
+            Self::Note(result) => result,
+            Self::SetEnv(result) => result,
+            Self::Stdin(result) => result,
+            Self::Skipped(result) => result,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ActionError, ActionResult, CreateFileResult, ScriptResult, VerifyResult};
+    use super::{
+        ActionError, ActionResult, CreateFileResult, RawOutput, ScriptResult, VerifyResult,
+    };
 
     mod success {
-        use super::{ActionError, ActionResult, CreateFileResult, ScriptResult, VerifyResult};
+        use super::{
+            ActionError, ActionResult, CreateFileResult, RawOutput, ScriptResult, VerifyResult,
+        };
 
         mod error {
-            use super::{ActionError, ActionResult, ScriptResult};
-            use crate::types::{ExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptName};
+            use super::{ActionError, ActionResult, RawOutput, ScriptResult};
+            use crate::types::{
+                ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode,
+                ScriptName, ScriptOptions,
+            };
 
             #[test]
             fn returns_none_when_successful_script() {
@@ -120,10 +325,21 @@ mod tests {
                         script_code: ScriptCode("example code".to_string()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::Any,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: String::new(),
                     stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 });
                 assert_eq!(result.error(), None);
                 assert!(result.success());
@@ -135,12 +351,23 @@ mod tests {
                     action: ScriptAction {
                         script_name: Some(ScriptName("example_script".to_string())),
                         script_code: ScriptCode("example code".to_string()),
-                        expected_exit_code: Some(ExitCode(1)),
+                        expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(1))),
                         expected_output: OutputExpectation::Any,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: Some(ExitCode(1)),
                     stdout: String::new(),
                     stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 });
                 assert_eq!(result.error(), None);
                 assert!(result.success());
@@ -152,12 +379,23 @@ mod tests {
                     action: ScriptAction {
                         script_name: Some(ScriptName("example_script".to_string())),
                         script_code: ScriptCode("example code".to_string()),
-                        expected_exit_code: Some(ExitCode(1)),
+                        expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(1))),
                         expected_output: OutputExpectation::Any,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: Some(ExitCode(2)),
                     stdout: String::new(),
                     stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 };
                 let result = ActionResult::Script(script_result.clone());
                 assert_eq!(
@@ -167,6 +405,47 @@ mod tests {
                 assert!(!result.success());
             }
 
+            #[test]
+            fn returns_exit_code_is_incorrect_unless_killed_by_the_expected_signal() {
+                let script_result = ScriptResult {
+                    action: ScriptAction {
+                        script_name: Some(ScriptName("example_script".to_string())),
+                        script_code: ScriptCode("example code".to_string()),
+                        expected_exit_code: None,
+                        expected_output: OutputExpectation::Any,
+                        options: ScriptOptions {
+                            expected_signal: Some(15),
+                            ..ScriptOptions::default()
+                        },
+                    },
+                    exit_code: Some(ExitCode(0)),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
+                };
+                let result = ActionResult::Script(script_result.clone());
+                assert_eq!(
+                    result.error(),
+                    Some(ActionError::ExitCodeIsIncorrect(script_result.clone()))
+                );
+
+                let killed = ActionResult::Script(ScriptResult {
+                    exit_code: None,
+                    signal: Some(15),
+                    ..script_result
+                });
+                assert_eq!(killed.error(), None);
+            }
+
             #[test]
             fn returns_unexpected_output_is_present_when_stderr_is_present_but_only_stdout_is_expected(
             ) {
@@ -176,10 +455,21 @@ mod tests {
                         script_code: ScriptCode("example code".to_string()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::StdOut,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: String::new(),
                     stderr: "unexpected output".to_string(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 };
                 let result = ActionResult::Script(script_result.clone());
                 assert_eq!(
@@ -198,10 +488,21 @@ mod tests {
                         script_code: ScriptCode("example code".to_string()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::StdErr,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: "unexpected output".to_string(),
                     stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 };
                 let result = ActionResult::Script(script_result.clone());
                 assert_eq!(
@@ -220,10 +521,21 @@ mod tests {
                         script_code: ScriptCode("example code".to_string()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::None,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: "unexpected output".to_string(),
                     stderr: String::new(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 };
                 let result = ActionResult::Script(script_result.clone());
                 assert_eq!(
@@ -242,10 +554,21 @@ mod tests {
                         script_code: ScriptCode("example code".to_string()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::None,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: String::new(),
                     stderr: "unexpected output".to_string(),
+                    output: String::new(),
+                    raw_output: RawOutput::default(),
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 };
                 let result = ActionResult::Script(script_result.clone());
                 assert_eq!(
@@ -258,10 +581,13 @@ mod tests {
 
         mod verify {
             use super::{ActionError, ActionResult, VerifyResult};
-            use crate::types::{ScriptName, Source, Stream, VerifyAction, VerifyValue};
+            use crate::results::VerifyFailure;
+            use crate::types::{
+                ScriptName, Source, Stream, VerifyAction, VerifyOptions, VerifyValue,
+            };
 
             #[test]
-            fn returns_true_when_expected_output_is_the_same_as_got_output() {
+            fn returns_true_when_there_is_no_failure() {
                 let result = ActionResult::Verify(VerifyResult {
                     action: VerifyAction {
                         source: Source {
@@ -269,15 +595,18 @@ mod tests {
                             stream: Stream::StdOut,
                         },
                         expected_value: VerifyValue("the output".to_string()),
+                        options: VerifyOptions::default(),
                     },
                     got: "the output".to_string(),
+                    failure: None,
+                    displayed: None,
                 });
                 assert_eq!(result.error(), None);
                 assert!(result.success());
             }
 
             #[test]
-            fn returns_false_when_expected_output_is_not_the_same_as_got_output() {
+            fn returns_false_when_there_is_a_failure() {
                 let verify_result = VerifyResult {
                     action: VerifyAction {
                         source: Source {
@@ -285,8 +614,11 @@ mod tests {
                             stream: Stream::StdOut,
                         },
                         expected_value: VerifyValue("expected output".to_string()),
+                        options: VerifyOptions::default(),
                     },
                     got: "different output".to_string(),
+                    failure: Some(VerifyFailure::OutputDoesNotMatch),
+                    displayed: None,
                 };
                 let result = ActionResult::Verify(verify_result.clone());
                 assert_eq!(
@@ -299,7 +631,7 @@ mod tests {
 
         mod create_file {
             use super::{ActionResult, CreateFileResult};
-            use crate::types::{CreateFileAction, FileContent, FilePath};
+            use crate::types::{CreateFileAction, CreateFileOptions, FileContent, FilePath};
 
             #[test]
             fn returns_true() {
@@ -307,6 +639,7 @@ mod tests {
                     action: CreateFileAction {
                         file_path: FilePath("path".to_string()),
                         file_content: FileContent("content".to_string()),
+                        options: CreateFileOptions::default(),
                     },
                 });
                 assert!(result.success());
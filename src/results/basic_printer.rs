@@ -3,24 +3,48 @@ use std::path::Path;
 use crossterm::style::Stylize;
 
 use super::diff_theme::DIFF_THEME;
+use super::explanation;
 use crate::ansi::strip_ansi_escape_chars;
 use crate::runner::Error;
 use crate::runner::RunEvent;
-use crate::types::{ExitCode, OutputExpectation, Stream, VerifyAction};
+use crate::runner::{describe_action, json, wildcard, yaml};
+use crate::types::{
+    DirectoryExpectation, ExitCode, Matcher, OutputExpectation, ScriptPhase, SectionPath,
+    SetEnvAction, Stream, VerifyAction, VerifyValue,
+};
 
 use super::action_result::ActionResult;
-use super::action_result::{ActionError, CreateFileResult, ScriptResult, VerifyResult};
+use super::action_result::{
+    ActionError, CreateFileResult, Displayed, IdempotencyFailure, NoteResult, ScriptResult,
+    SetEnvResult, SkippedResult, StabilityFailure, StdinResult, VerifyDirectoryResult,
+    VerifyFailure, VerifyFileResult, VerifyResult,
+};
+use super::benchmark_table::{self, Benchmark};
+use super::coverage::{BlockStatus, Coverage};
+use super::line_diff;
 use super::printer::Printer;
 
 struct Summary {
     pub number_succeeded: u32,
     pub number_failed: u32,
+    pub allowed_failures: u32,
+    pub skipped: u32,
+}
+
+// How much detail is printed for each result
+#[derive(Default)]
+struct Detail {
+    show_output_on_pass: bool,
+    full_diff: bool,
+    explain: bool,
 }
 
 pub struct BasicPrinter {
     display_function: Box<dyn Fn(&str)>,
     summary: Summary,
     colour: bool,
+    detail: Detail,
+    benchmarks: Vec<Benchmark>,
 }
 
 impl BasicPrinter {
@@ -30,19 +54,48 @@ impl BasicPrinter {
             summary: Summary {
                 number_succeeded: 0,
                 number_failed: 0,
+                allowed_failures: 0,
+                skipped: 0,
             },
             colour,
+            detail: Detail::default(),
+            benchmarks: Vec::new(),
         }
     }
+
+    pub const fn show_output_on_pass(mut self, show_output_on_pass: bool) -> Self {
+        self.detail.show_output_on_pass = show_output_on_pass;
+        self
+    }
+
+    pub const fn full_diff(mut self, full_diff: bool) -> Self {
+        self.detail.full_diff = full_diff;
+        self
+    }
+
+    pub const fn explain(mut self, explain: bool) -> Self {
+        self.detail.explain = explain;
+        self
+    }
+
+    pub(super) fn display_with(mut self, display_function: impl Fn(&str) + 'static) -> Self {
+        self.display_function = Box::new(display_function);
+        self
+    }
 }
 
 impl Printer for BasicPrinter {
     fn print(&mut self, event: &RunEvent) {
         match event {
             RunEvent::SpecFileStarted(path) => self.print_spec_file(path),
+            RunEvent::SectionStarted(section) => self.print_section(section),
             RunEvent::TestCompleted(result) => self.print_result(result),
+            RunEvent::FailureAllowed(result) => self.print_allowed_failure(result),
             RunEvent::SpecFileCompleted { .. } => self.print_summary(),
             RunEvent::ErrorOccurred(error) => self.print_error(error),
+            RunEvent::Warning(message) => self.print_warning(message),
+            RunEvent::Info(message) => self.display(&format!("  \u{2139} {message}")),
+            RunEvent::CoverageCollected(coverage) => self.print_coverage(coverage),
         }
     }
 }
@@ -52,6 +105,8 @@ impl BasicPrinter {
         self.summary = Summary {
             number_succeeded: 0,
             number_failed: 0,
+            allowed_failures: 0,
+            skipped: 0,
         };
         self.display(&format!(
             "Running tests for {}:\n",
@@ -59,50 +114,149 @@ impl BasicPrinter {
         ));
     }
 
+    fn print_section(&self, section: &SectionPath) {
+        if !section.0.is_empty() {
+            self.display(&format!("  {}", section.to_string().bold()));
+        }
+    }
+
     fn print_result(&mut self, result: &ActionResult) {
+        if let ActionResult::Note(NoteResult { action }) = result {
+            self.display(&format!("  - note: {}", action.text));
+            return;
+        }
+
+        if let ActionResult::Skipped(_) = result {
+            self.summary.skipped += 1;
+            let message = format!("  - {}", Self::action_title(result));
+            self.display(&format!("{}", message.yellow()));
+            return;
+        }
+
+        if let ActionResult::Script(ScriptResult {
+            action, durations, ..
+        }) = result
+        {
+            if durations.len() > 1 {
+                self.benchmarks.push(Benchmark {
+                    script_name: action
+                        .script_name
+                        .clone()
+                        .map_or("<unnamed>".to_string(), Into::into),
+                    durations: durations.clone(),
+                });
+            }
+        }
+
         self.count_action(result);
         self.display_action(result);
+        if self.detail.explain {
+            if let Some(explanation) = explanation::explain(result) {
+                self.display(&format!("    why: {explanation}"));
+            }
+        }
         if let Some(error) = result.error() {
             self.display_action_error(&error);
+        } else if let ActionResult::Verify(VerifyResult {
+            action,
+            got,
+            displayed,
+            ..
+        }) = result
+        {
+            if self.detail.show_output_on_pass {
+                let (_, got) = self.displayed_values(action, got, displayed.as_ref());
+                self.display(&format!("===\n{got}\n==="));
+            }
         }
     }
 
+    fn print_allowed_failure(&mut self, result: &ActionResult) {
+        self.summary.allowed_failures += 1;
+        self.print_warning(&format!(
+            "{} (failure allowed)",
+            Self::action_message(result)
+        ));
+    }
+
     fn print_error(&self, error: &Error) {
-        self.display_error_item(&match error {
-            Error::ScriptOutputMissing {
-                missing_script_name,
-            } => {
-                format!(
-                    "Failed to verify the output of '{missing_script_name}': No script with that name has been executed yet."
-                )
-            },
-            Error::CommandFailed { command, message } => format!(
-                "Failed to run command: {command} (Error: {message})"
-            ),
-            Error::BadShellCommand { command, message } => format!(
-                "Invalid shell command provided: {command} (Error: {message})"
-            ),
-            Error::RunFailed { message } => message.to_string(),
-        });
+        self.display_error_item(&error_message(error));
+    }
+
+    fn print_warning(&self, message: &str) {
+        self.display(&format!("{}", format!("  \u{26a0} {message}").yellow()));
     }
 
-    fn print_summary(&self) {
+    fn print_coverage(&self, coverage: &Coverage) {
+        self.display("Coverage:\n");
+        for block in &coverage.blocks {
+            let line = format!("  {block}");
+            match block.status {
+                BlockStatus::Passed => self.display_success(&line),
+                BlockStatus::Failed => self.display_error(&line),
+                BlockStatus::Skipped => self.display(&format!("{}", line.yellow())),
+            }
+        }
+        self.display(&format!("\n  {}\n", coverage.summary()));
+    }
+
+    fn print_summary(&mut self) {
+        if !self.benchmarks.is_empty() {
+            self.display(&format!("\n{}", benchmark_table::format(&self.benchmarks)));
+            self.benchmarks.clear();
+        }
+
+        let Summary {
+            number_succeeded,
+            number_failed,
+            allowed_failures,
+            skipped,
+        } = self.summary;
+        let others = [(allowed_failures, "allowed to fail"), (skipped, "skipped")]
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!(" / {count} {label}"))
+            .collect::<Vec<_>>()
+            .concat();
         self.display(&format!(
-            "\n  {} functions run ({} succeeded / {} failed)\n",
-            self.summary.number_failed + self.summary.number_succeeded,
-            self.summary.number_succeeded,
-            self.summary.number_failed
+            "\n  {} functions run ({number_succeeded} succeeded / {number_failed} failed{others})\n",
+            number_succeeded + number_failed + allowed_failures + skipped,
         ));
     }
 
     fn display_action(&mut self, result: &ActionResult) {
-        let title = Self::action_title(result);
-        let result_message = Self::action_result_message(result);
-        let full_message = &format!("{title} {result_message}");
+        let message = &Self::action_message(result);
         if result.success() {
-            self.display_success_item(full_message);
+            self.display_success_item(message);
         } else {
-            self.display_error_item(full_message);
+            self.display_error_item(message);
+        }
+    }
+
+    pub(super) fn action_message(result: &ActionResult) -> String {
+        if let ActionResult::Skipped(_) = result {
+            return Self::action_title(result);
+        }
+
+        let message = if let Some(ActionError::ExitCodeIsIncorrect(script_result)) = result.error()
+        {
+            exit_code_message(&script_result)
+        } else {
+            format!(
+                "{} {}",
+                Self::action_title(result),
+                Self::action_result_message(result)
+            )
+        };
+
+        match result {
+            ActionResult::Script(script_result) => {
+                format!(
+                    "{message}{}",
+                    attempts_message(result.success(), script_result)
+                )
+            }
+            _ => message,
         }
     }
 
@@ -110,7 +264,12 @@ impl BasicPrinter {
         match result {
             ActionResult::Script(ScriptResult { action, .. }) => {
                 format!(
-                    "running script '{}'",
+                    "running {}script '{}'",
+                    if action.options.phase == ScriptPhase::Cleanup {
+                        "cleanup "
+                    } else {
+                        ""
+                    },
                     action
                         .script_name
                         .clone()
@@ -119,16 +278,54 @@ impl BasicPrinter {
             }
             ActionResult::Verify(VerifyResult { action, .. }) => format!(
                 "verifying {} from '{}'",
-                stream_to_string(&action.source.stream),
+                match (&action.options.stream_usage, &action.options.env_changed) {
+                    (Some(_), _) => "stream usage".to_string(),
+                    (None, Some(name)) => format!("environment variable {name}"),
+                    (None, None) => stream_to_string(&action.source.stream).to_string(),
+                },
                 action
                     .source
                     .name
                     .clone()
                     .map_or("<unnamed>".to_string(), Into::into),
             ),
-            ActionResult::CreateFile(CreateFileResult { action, .. }) => {
-                format!("creating file {}", String::from(action.file_path.clone()))
+            ActionResult::VerifyDirectory(VerifyDirectoryResult { action, .. }) => {
+                let directory = String::from(action.directory.clone());
+                match &action.expected {
+                    DirectoryExpectation::Golden(golden) => format!(
+                        "verifying directory {directory} against {}",
+                        String::from(golden.clone())
+                    ),
+                    DirectoryExpectation::Tree(_) => format!("verifying tree of {directory}"),
+                }
             }
+            ActionResult::VerifyFile(VerifyFileResult { action, .. }) => {
+                let path = String::from(action.file_path.clone());
+                match action.exists {
+                    Some(true) => format!("checking {path} exists"),
+                    Some(false) => format!("checking {path} does not exist"),
+                    None => format!("verifying file {path}"),
+                }
+            }
+            ActionResult::CreateFile(CreateFileResult { action, .. }) => format!(
+                "{} file {}",
+                if action.options.append {
+                    "appending to"
+                } else {
+                    "creating"
+                },
+                String::from(action.file_path.clone())
+            ),
+            ActionResult::Note(NoteResult { action }) => format!("note: {}", action.text),
+            ActionResult::SetEnv(SetEnvResult { action }) => env_change_title(action),
+            ActionResult::Stdin(StdinResult { action }) => format!(
+                "providing stdin for '{}'",
+                String::from(action.script_name.clone())
+            ),
+            ActionResult::Skipped(SkippedResult { action, reason }) => match reason {
+                Some(reason) => format!("skipped {}: {reason}", describe_action(action)),
+                None => format!("skipped {}", describe_action(action)),
+            },
         }
     }
 
@@ -142,13 +339,6 @@ impl BasicPrinter {
 
     fn action_result_message(result: &ActionResult) -> String {
         match result.error() {
-            Some(ActionError::ExitCodeIsIncorrect(result)) => {
-                format!(
-                    "failed (expected exitcode {}, got {})",
-                    Self::exit_code_to_string(result.action.expected_exit_code),
-                    Self::exit_code_to_string(result.exit_code),
-                )
-            }
             Some(ActionError::UnexpectedOutputIsPresent(result)) => {
                 format!(
                     "failed (unexpected {})",
@@ -160,30 +350,235 @@ impl BasicPrinter {
                     }
                 )
             }
-            Some(ActionError::OutputDoesNotMatch(_)) => "failed".to_string(),
+            Some(
+                ActionError::OutputDoesNotMatch(VerifyResult {
+                    failure:
+                        Some(
+                            VerifyFailure::MatcherFailed { reason }
+                            | VerifyFailure::InvalidEncoding { reason },
+                        ),
+                    ..
+                })
+                | ActionError::FileDoesNotMatch(VerifyFileResult {
+                    failure:
+                        Some(
+                            VerifyFailure::MatcherFailed { reason }
+                            | VerifyFailure::InvalidEncoding { reason },
+                        ),
+                    ..
+                }),
+            ) => format!("failed ({reason})"),
+            Some(ActionError::OutputDoesNotMatch(VerifyResult {
+                failure: Some(VerifyFailure::BytesDoNotMatch { offset }),
+                ..
+            })) => {
+                format!("failed (output differs from the expected bytes at byte offset {offset})")
+            }
+            Some(ActionError::OutputDoesNotMatch(VerifyResult {
+                action,
+                failure: Some(VerifyFailure::NoAlternativeMatched),
+                ..
+            })) => format!(
+                "failed (matched none of the {} alternatives)",
+                action.options.alternatives.len() + 1
+            ),
+            Some(ActionError::FileDoesNotMatch(VerifyFileResult {
+                failure: Some(VerifyFailure::FileMissing),
+                ..
+            })) => "failed (file does not exist)".to_string(),
+            Some(ActionError::FileDoesNotMatch(VerifyFileResult {
+                failure: Some(VerifyFailure::FileExists),
+                ..
+            })) => "failed (file exists)".to_string(),
+            Some(ActionError::FileDoesNotMatch(VerifyFileResult {
+                failure: Some(VerifyFailure::ModeDoesNotMatch { expected, got }),
+                ..
+            })) => format!("failed (expected mode {expected}, got {got})"),
+            Some(ActionError::OutputDoesNotMatch(VerifyResult {
+                failure: Some(VerifyFailure::UnexpectedStream { stream }),
+                ..
+            })) => format!(
+                "failed (unexpected output on {})",
+                stream_to_string(&stream)
+            ),
+            Some(ActionError::OutputDoesNotMatch(VerifyResult {
+                failure:
+                    Some(VerifyFailure::EnvironmentDoesNotMatch {
+                        name,
+                        before,
+                        after,
+                    }),
+                ..
+            })) => format!(
+                "failed ({name} was {} before and {} after)",
+                env_value(before.as_deref()),
+                env_value(after.as_deref())
+            ),
+            Some(
+                ActionError::ExitCodeIsIncorrect(_)
+                | ActionError::OutputDoesNotMatch(_)
+                | ActionError::DirectoryDoesNotMatch(_)
+                | ActionError::FileDoesNotMatch(_),
+            ) => "failed".to_string(),
+            Some(ActionError::NotIdempotent(ScriptResult {
+                idempotency_failure: Some(IdempotencyFailure::ChangedMarkerPrinted { marker }),
+                ..
+            })) => format!("failed (the second run printed '{marker}')"),
+            Some(ActionError::NotIdempotent(_)) => {
+                "failed (the second run changed the output)".to_string()
+            }
+            Some(ActionError::NotStable(ScriptResult {
+                action,
+                stability_failure: Some(StabilityFailure { run, .. }),
+                ..
+            })) => format!(
+                "failed (run {run} of {} changed the output)",
+                action.options.stable_runs.unwrap_or(run)
+            ),
+            Some(ActionError::NotStable(_)) => "failed (the output changed)".to_string(),
             None => "succeeded".to_string(),
         }
     }
 
-    fn exit_code_to_string(exit_code: Option<ExitCode>) -> String {
-        exit_code
-            .map(String::from)
-            .or_else(|| Some("None".to_string()))
-            .unwrap()
+    fn display_changed_output(&mut self, result: &ScriptResult) {
+        let stdout = &result.stdout;
+        match (&result.idempotency_failure, &result.stability_failure) {
+            (Some(IdempotencyFailure::OutputChanged { first_stdout }), _) => {
+                self.display_diff(first_stdout, stdout);
+            }
+            (Some(IdempotencyFailure::ChangedMarkerPrinted { .. }), _) => self.display(&format!(
+                "=== second run:
+{stdout}
+==="
+            )),
+            (
+                None,
+                Some(StabilityFailure {
+                    first_stdout,
+                    stdout,
+                    ..
+                }),
+            ) => self.display_diff(first_stdout, stdout),
+            (None, None) => {}
+        }
     }
 
     fn display_action_error(&mut self, error: &ActionError) {
         match error {
-            ActionError::ExitCodeIsIncorrect(ScriptResult { stdout, stderr, .. })
-            | ActionError::UnexpectedOutputIsPresent(ScriptResult { stdout, stderr, .. }) => {
+            ActionError::ExitCodeIsIncorrect(ScriptResult { stderr, .. }) => {
+                if let Some(tail) = stderr_tail(stderr) {
+                    self.display(&tail);
+                }
+            }
+            ActionError::UnexpectedOutputIsPresent(ScriptResult { stdout, stderr, .. }) => {
                 self.disply_all_output(stdout, stderr);
             }
+            ActionError::NotIdempotent(result) | ActionError::NotStable(result) => {
+                self.display_changed_output(result);
+            }
             ActionError::OutputDoesNotMatch(VerifyResult {
-                action: VerifyAction { expected_value, .. },
+                action,
+                failure,
+                got,
+                displayed,
+            }) => {
+                if let Some(message) = &action.options.message {
+                    self.display_error(&format!("    {message}"));
+                }
+                if let Some(VerifyFailure::UnexpectedStream { stream }) = failure {
+                    self.display(&content_head(stream_to_string(stream), got));
+                    return;
+                }
+                if action.options.env_changed.is_some() {
+                    return;
+                }
+                let (expected, got) = self.displayed_values(action, got, displayed.as_ref());
+                let got = &got;
+                if !matches!(
+                    failure,
+                    Some(
+                        VerifyFailure::InvalidEncoding { .. }
+                            | VerifyFailure::BytesDoNotMatch { .. }
+                    )
+                ) && compares_content(&action.options.matcher)
+                {
+                    if matches!(failure, Some(VerifyFailure::NoAlternativeMatched)) {
+                        self.display_alternative_differences(action, got);
+                    } else {
+                        self.display_difference(&action.options.matcher, &expected, got);
+                    }
+                } else if matches!(action.options.matcher, Matcher::Regex | Matcher::Glob) {
+                    self.display(&content_head(stream_to_string(&action.source.stream), got));
+                } else if matches!(action.options.matcher, Matcher::Json | Matcher::Yaml) {
+                    self.display_structural_differences(&action.options.matcher, &expected, got);
+                }
+            }
+            ActionError::FileDoesNotMatch(VerifyFileResult {
+                action,
                 got,
+                failure,
             }) => {
-                self.display_diff(&String::from(expected_value.clone()), got);
+                if let Some(message) = &action.options.message {
+                    self.display_error(&format!("    {message}"));
+                }
+                if matches!(
+                    failure,
+                    Some(
+                        VerifyFailure::FileMissing
+                            | VerifyFailure::FileExists
+                            | VerifyFailure::ModeDoesNotMatch { .. }
+                    )
+                ) {
+                    return;
+                }
+                if matches!(action.options.matcher, Matcher::Regex | Matcher::Glob) {
+                    self.display(&content_head(&String::from(action.file_path.clone()), got));
+                } else if matches!(action.options.matcher, Matcher::Json | Matcher::Yaml) {
+                    self.display_structural_differences(
+                        &action.options.matcher,
+                        &action.expected_value,
+                        got,
+                    );
+                } else if compares_content(&action.options.matcher) {
+                    self.display_difference(&action.options.matcher, &action.expected_value, got);
+                }
             }
+            ActionError::DirectoryDoesNotMatch(VerifyDirectoryResult {
+                missing,
+                extra,
+                differing,
+                ..
+            }) => {
+                for (label, files) in [
+                    ("missing", missing),
+                    ("extra", extra),
+                    ("differs", differing),
+                ] {
+                    for file in files {
+                        self.display_error(&format!("    {label}: {file}"));
+                    }
+                }
+            }
+        }
+    }
+
+    fn displayed_values(
+        &self,
+        action: &VerifyAction,
+        got: &str,
+        displayed: Option<&Displayed>,
+    ) -> (VerifyValue, String) {
+        match displayed {
+            Some(Displayed::Transformed { expected, got }) => {
+                (VerifyValue(expected.clone()), got.clone())
+            }
+            Some(Displayed::TransformFailed { command, message }) => {
+                self.print_warning(&format!(
+                    "Failed to transform the output for display with: {command} (Error: {message})"
+                ));
+                (action.expected_value.clone(), got.to_string())
+            }
+            None => (action.expected_value.clone(), got.to_string()),
         }
     }
 
@@ -194,6 +589,93 @@ impl BasicPrinter {
         ));
     }
 
+    fn display_alternative_differences(&mut self, action: &VerifyAction, got: &str) {
+        let alternatives: Vec<&VerifyValue> = std::iter::once(&action.expected_value)
+            .chain(&action.options.alternatives)
+            .collect();
+
+        for (index, expected) in alternatives.iter().enumerate() {
+            self.display_error(&format!(
+                "    alternative {} of {}:",
+                index + 1,
+                alternatives.len()
+            ));
+            self.display_difference(&action.options.matcher, expected, got);
+        }
+    }
+
+    fn display_difference(&mut self, matcher: &Matcher, expected: &VerifyValue, got: &str) {
+        let expected = String::from(expected.clone());
+        if self.detail.full_diff && matcher == &Matcher::Exact {
+            self.display_line_differences(&expected, got);
+        } else if matcher == &Matcher::Wildcard {
+            self.display_wildcard_difference(&expected, got);
+        } else {
+            self.display_diff(&expected, got);
+        }
+    }
+
+    fn display_wildcard_difference(&mut self, expected: &str, got: &str) {
+        let expansion = wildcard::expand(expected, got);
+        self.display_diff(&expansion.expected, got);
+        for consumed in expansion.consumed {
+            let lines = match consumed.lines.len() {
+                0 => "no lines".to_string(),
+                1 => format!("output line {}", consumed.lines.start),
+                _ => format!(
+                    "output lines {} to {}",
+                    consumed.lines.start,
+                    consumed.lines.end - 1
+                ),
+            };
+            self.display_error(&format!(
+                "    `...` on line {} matched {lines}",
+                consumed.line
+            ));
+        }
+    }
+
+    fn display_structural_differences(
+        &mut self,
+        matcher: &Matcher,
+        expected: &VerifyValue,
+        got: &str,
+    ) {
+        let expected = String::from(expected.clone());
+        let differences = if matcher == &Matcher::Yaml {
+            yaml::differences(&expected, got)
+        } else {
+            json::differences(&expected, got)
+        };
+        let Ok(differences) = differences else {
+            return;
+        };
+        // A single difference is already given as the failure reason
+        if differences.len() < 2 {
+            return;
+        }
+        self.display(&format!(
+            "=== {} differing value{}:\n{}\n===",
+            differences.len(),
+            if differences.len() == 1 { "" } else { "s" },
+            differences
+                .iter()
+                .map(|difference| format!("  {difference}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    fn display_line_differences(&mut self, expected: &str, actual: &str) {
+        let differences = line_diff::differences(expected, actual);
+        self.display(&format!(
+            "=== {} differing line{}:\n{}\n===",
+            differences.len(),
+            if differences.len() == 1 { "" } else { "s" },
+            line_diff::format(&differences)
+        ));
+    }
+
     fn disply_all_output(&mut self, stdout: &str, stderr: &str) {
         self.display(&format!(
             "\n=== stdout:\n{stdout}\n\n=== stderr:\n{stderr}\n\n"
@@ -227,12 +709,380 @@ impl BasicPrinter {
     }
 }
 
+pub(super) fn error_message(error: &Error) -> String {
+    match error {
+        Error::ScriptOutputMissing {
+            missing_script_name,
+        } => {
+            format!(
+                    "Failed to verify the output of '{missing_script_name}': No script with that name has been executed yet."
+                )
+        }
+        Error::SourceScriptFailed { script_name } => {
+            format!("verifying the output of '{script_name}' skipped: source script failed")
+        }
+        Error::CommandFailed { command, message } => {
+            format!("Failed to run command: {command} (Error: {message})")
+        }
+        Error::TeeFailed { path, message } => {
+            format!("Failed to write script output to {path} (Error: {message})")
+        }
+        Error::NormalizerFailed { command, message } => {
+            format!("Failed to normalize the output with: {command} (Error: {message})")
+        }
+        Error::WorkdirFailed { message } => {
+            format!("Failed to create a temporary working directory (Error: {message})")
+        }
+        Error::EnvironmentCaptureFailed { message } => {
+            format!("Failed to capture the environment of the script (Error: {message})")
+        }
+        Error::EnvironmentNotCaptured { script_name } => {
+            format!(
+                "Failed to verify the environment of '{script_name}': It was not run with capture_env=true."
+            )
+        }
+        Error::RunningDirNotEmpty { entries } => format!(
+            "The running directory is not empty, it contains: {}",
+            entries.join(", ")
+        ),
+        Error::ChangedFilesUnavailable { git_ref, message } => {
+            format!("Failed to list the files changed since '{git_ref}' (Error: {message})")
+        }
+        Error::BadShellCommand { command, message } => {
+            format!("Invalid shell command provided: {command} (Error: {message})")
+        }
+        Error::UnknownVariable { variable_name } => {
+            format!(
+                "Failed to render the expected output: Unknown variable '{variable_name}' \
+                 (add raw=true to verify() to compare `${{…}}` literally)"
+            )
+        }
+        Error::RunFailed { message } => message.to_string(),
+    }
+}
+
 const fn stream_to_string(stream: &Stream) -> &str {
     match stream {
         Stream::StdOut => "stdout",
         Stream::StdErr => "stderr",
+        Stream::Output => "output",
+    }
+}
+
+const fn compares_content(matcher: &Matcher) -> bool {
+    !matches!(
+        matcher,
+        Matcher::Number { .. }
+            | Matcher::Semver { .. }
+            | Matcher::LineCount
+            | Matcher::ValidJson
+            | Matcher::Json
+            | Matcher::Regex
+            | Matcher::Glob
+            | Matcher::Yaml
+    )
+}
+
+fn env_value(value: Option<&str>) -> String {
+    value.map_or_else(|| "unset".to_string(), |value| format!("'{value}'"))
+}
+
+fn attempts_message(success: bool, result: &ScriptResult) -> String {
+    let max_attempts = result.action.options.retries + 1;
+    if max_attempts == 1 {
+        String::new()
+    } else if success {
+        format!(" on attempt {} of {max_attempts}", result.attempts)
+    } else {
+        format!(" after {} attempts", result.attempts)
     }
 }
 
+fn env_change_title(action: &SetEnvAction) -> String {
+    let variables = |names: Vec<&str>| {
+        format!(
+            "environment variable{} {}",
+            if names.len() == 1 { "" } else { "s" },
+            names.join(", ")
+        )
+    };
+
+    let mut changes = vec![];
+    if action.reset {
+        changes.push("resetting the environment".to_string());
+    }
+    if !action.unset.is_empty() {
+        changes.push(format!(
+            "unsetting {}",
+            variables(action.unset.iter().map(String::as_str).collect())
+        ));
+    }
+    if !action.variables.is_empty() {
+        changes.push(format!(
+            "setting {}",
+            variables(
+                action
+                    .variables
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect()
+            )
+        ));
+    }
+
+    if changes.is_empty() {
+        "setting no environment variables".to_string()
+    } else {
+        changes.join(" and ")
+    }
+}
+
+const STDERR_TAIL_LINES: usize = 10;
+
+fn exit_code_message(result: &ScriptResult) -> String {
+    let outcome = match (result.signal, result.exit_code) {
+        (Some(signal), _) => format!("was killed by signal {signal}"),
+        (None, exit_code) => format!("exited {}", exit_code_to_string(exit_code)),
+    };
+
+    let expected = match result.action.options.expected_signal {
+        Some(signal) => format!("to be killed by signal {signal}"),
+        None => result
+            .action
+            .expected_exit_code
+            .as_ref()
+            .map_or_else(|| "None".to_string(), ToString::to_string),
+    };
+
+    format!(
+        "script '{}' {outcome}, expected {expected}",
+        result
+            .action
+            .script_name
+            .clone()
+            .map_or("<unnamed>".to_string(), Into::into),
+    )
+}
+
+fn exit_code_to_string(exit_code: Option<ExitCode>) -> String {
+    exit_code.map_or_else(|| "None".to_string(), String::from)
+}
+
+fn stderr_tail(stderr: &str) -> Option<String> {
+    let lines: Vec<&str> = stderr.lines().collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let header = if lines.len() > STDERR_TAIL_LINES {
+        format!("=== stderr (last {STDERR_TAIL_LINES} lines):")
+    } else {
+        "=== stderr:".to_string()
+    };
+    let tail = &lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..];
+
+    Some(format!("{header}\n{}\n===", tail.join("\n")))
+}
+
+const CONTENT_HEAD_LINES: usize = 10;
+
+fn content_head(label: &str, content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+
+    let header = if lines.len() > CONTENT_HEAD_LINES {
+        format!("=== {label} (first {CONTENT_HEAD_LINES} lines):")
+    } else {
+        format!("=== {label}:")
+    };
+    let head = &lines[..lines.len().min(CONTENT_HEAD_LINES)];
+
+    format!("{header}\n{}\n===", head.join("\n"))
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use super::{
+        attempts_message, content_head, env_change_title, exit_code_message, stderr_tail,
+        BasicPrinter, Printer, RunEvent,
+    };
+    use crate::results::{RawOutput, ScriptResult};
+    use crate::types::{
+        ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptName,
+        ScriptOptions, SectionPath, SetEnvAction,
+    };
+
+    fn script_result(exit_code: Option<ExitCode>) -> ScriptResult {
+        ScriptResult {
+            action: ScriptAction {
+                script_name: Some(ScriptName("deploy".to_string())),
+                script_code: ScriptCode("exit 2".to_string()),
+                expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+                expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
+            },
+            exit_code,
+            stdout: String::new(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        }
+    }
+
+    #[test]
+    fn exit_code_message_includes_the_actual_and_expected_exit_codes() {
+        assert_eq!(
+            exit_code_message(&script_result(Some(ExitCode(2)))),
+            "script 'deploy' exited 2, expected 0"
+        );
+    }
+
+    #[test]
+    fn exit_code_message_shows_none_when_there_is_no_exit_code() {
+        assert_eq!(
+            exit_code_message(&script_result(None)),
+            "script 'deploy' exited None, expected 0"
+        );
+    }
+
+    #[test]
+    fn exit_code_message_shows_the_signal_when_the_script_was_killed() {
+        let result = ScriptResult {
+            signal: Some(9),
+            ..script_result(None)
+        };
+
+        assert_eq!(
+            exit_code_message(&result),
+            "script 'deploy' was killed by signal 9, expected 0"
+        );
+    }
+
+    #[test]
+    fn exit_code_message_shows_the_expected_signal() {
+        let mut result = script_result(Some(ExitCode(0)));
+        result.action.options.expected_signal = Some(15);
+
+        assert_eq!(
+            exit_code_message(&result),
+            "script 'deploy' exited 0, expected to be killed by signal 15"
+        );
+    }
+
+    #[test]
+    fn stderr_tail_is_none_when_stderr_is_empty() {
+        assert_eq!(stderr_tail(""), None);
+    }
+
+    #[test]
+    fn stderr_tail_shows_short_stderr_in_full() {
+        assert_eq!(
+            stderr_tail("first\nsecond\n"),
+            Some("=== stderr:\nfirst\nsecond\n===".to_string())
+        );
+    }
+
+    #[test]
+    fn stderr_tail_only_shows_the_last_lines_of_long_stderr() {
+        let lines: Vec<String> = (1..=12).map(|n| format!("line {n}")).collect();
+
+        assert_eq!(
+            stderr_tail(&lines.join("\n")),
+            Some(format!(
+                "=== stderr (last 10 lines):\n{}\n===",
+                lines[2..].join("\n")
+            ))
+        );
+    }
+
+    #[test]
+    fn content_head_shows_short_content_in_full() {
+        assert_eq!(
+            content_head("config.ini", "first\nsecond\n"),
+            "=== config.ini:\nfirst\nsecond\n===".to_string()
+        );
+    }
+
+    #[test]
+    fn content_head_only_shows_the_first_lines_of_long_content() {
+        let lines: Vec<String> = (1..=12).map(|n| format!("line {n}")).collect();
+
+        assert_eq!(
+            content_head("config.ini", &lines.join("\n")),
+            format!(
+                "=== config.ini (first 10 lines):\n{}\n===",
+                lines[..10].join("\n")
+            )
+        );
+    }
+
+    #[test]
+    fn attempts_message_is_empty_without_retries() {
+        assert_eq!(
+            attempts_message(true, &script_result(Some(ExitCode(0)))),
+            ""
+        );
+    }
+
+    #[test]
+    fn attempts_message_shows_which_attempt_succeeded_or_how_many_failed() {
+        let mut result = script_result(Some(ExitCode(0)));
+        result.action.options.retries = 2;
+        result.attempts = 2;
+
+        assert_eq!(attempts_message(true, &result), " on attempt 2 of 3");
+        assert_eq!(attempts_message(false, &result), " after 2 attempts");
+    }
+
+    #[test]
+    fn env_change_title_lists_each_kind_of_change() {
+        assert_eq!(
+            env_change_title(&SetEnvAction {
+                variables: vec![("A".to_string(), "1".to_string())],
+                unset: vec!["B".to_string(), "C".to_string()],
+                reset: true,
+            }),
+            "resetting the environment and unsetting environment variables B, C \
+             and setting environment variable A"
+        );
+    }
+
+    #[test]
+    fn prints_a_header_when_the_section_changes() {
+        let captured = Rc::new(RefCell::new(vec![]));
+        let sink = Rc::clone(&captured);
+        let mut printer = BasicPrinter::new(false)
+            .display_with(move |text: &str| sink.borrow_mut().push(text.to_string()));
+
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("deploy.md")),
+            RunEvent::SectionStarted(SectionPath(vec!["Deploy".to_string()])),
+            RunEvent::SectionStarted(SectionPath(vec![
+                "Deploy".to_string(),
+                "Rollback".to_string(),
+            ])),
+        ] {
+            printer.print(&event);
+        }
+
+        assert_eq!(
+            captured.take(),
+            vec![
+                "Running tests for deploy.md:\n".to_string(),
+                "  Deploy".to_string(),
+                "  Deploy > Rollback".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub struct Benchmark {
+    pub script_name: String,
+    pub durations: Vec<Duration>,
+}
+
+const HEADINGS: [&str; 5] = ["script", "iterations", "min", "mean", "max"];
+
+pub fn format(benchmarks: &[Benchmark]) -> String {
+    let rows: Vec<[String; 5]> = benchmarks.iter().map(to_row).collect();
+
+    let widths: Vec<usize> = (0..HEADINGS.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(HEADINGS[column].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let headings = HEADINGS.map(ToString::to_string);
+    std::iter::once(&headings)
+        .chain(rows.iter())
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            format!("  {}", cells.join("  ").trim_end())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn to_row(benchmark: &Benchmark) -> [String; 5] {
+    let durations = &benchmark.durations;
+    let total: Duration = durations.iter().sum();
+    let count = u32::try_from(durations.len()).unwrap_or(u32::MAX).max(1);
+
+    [
+        format!("'{}'", benchmark.script_name),
+        durations.len().to_string(),
+        format_duration(durations.iter().min().copied().unwrap_or_default()),
+        format_duration(total / count),
+        format_duration(durations.iter().max().copied().unwrap_or_default()),
+    ]
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, Benchmark};
+    use std::time::Duration;
+
+    #[test]
+    fn formats_min_mean_and_max_for_each_script() {
+        let benchmarks = vec![
+            Benchmark {
+                script_name: "fast".to_string(),
+                durations: vec![
+                    Duration::from_millis(1),
+                    Duration::from_millis(2),
+                    Duration::from_millis(3),
+                ],
+            },
+            Benchmark {
+                script_name: "much_slower".to_string(),
+                durations: vec![
+                    Duration::from_millis(100),
+                    Duration::from_millis(150),
+                    Duration::from_millis(110),
+                ],
+            },
+        ];
+
+        assert_eq!(
+            format(&benchmarks),
+            [
+                "  script         iterations  min       mean      max",
+                "  'fast'         3           1.00ms    2.00ms    3.00ms",
+                "  'much_slower'  3           100.00ms  120.00ms  150.00ms",
+            ]
+            .join("\n")
+        );
+    }
+}
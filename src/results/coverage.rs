@@ -0,0 +1,124 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::types::SectionPath;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlockStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl fmt::Display for BlockStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        };
+        write!(f, "{status}")
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockCoverage {
+    pub spec_file: PathBuf,
+    pub block: usize,
+    pub section: SectionPath,
+    pub description: String,
+    pub status: BlockStatus,
+}
+
+impl fmt::Display for BlockCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} block {}", self.spec_file.display(), self.block)?;
+        if !self.section.0.is_empty() {
+            write!(f, " ({})", self.section)?;
+        }
+        write!(f, ", {}: {}", self.description, self.status)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Coverage {
+    pub blocks: Vec<BlockCoverage>,
+}
+
+impl Coverage {
+    pub fn executed(&self) -> usize {
+        self.blocks
+            .iter()
+            .filter(|block| block.status != BlockStatus::Skipped)
+            .count()
+    }
+
+    pub fn percentage(&self) -> usize {
+        if self.blocks.is_empty() {
+            100
+        } else {
+            self.executed() * 100 / self.blocks.len()
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} blocks executed ({}%)",
+            self.executed(),
+            self.blocks.len(),
+            self.percentage()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{BlockCoverage, BlockStatus, Coverage};
+    use crate::types::SectionPath;
+
+    fn block(status: BlockStatus) -> BlockCoverage {
+        BlockCoverage {
+            spec_file: PathBuf::from("one.md"),
+            block: 1,
+            section: SectionPath::default(),
+            description: "script 'build'".to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn counts_passed_and_failed_blocks_as_executed() {
+        let coverage = Coverage {
+            blocks: vec![
+                block(BlockStatus::Passed),
+                block(BlockStatus::Failed),
+                block(BlockStatus::Skipped),
+            ],
+        };
+
+        assert_eq!(coverage.summary(), "2 of 3 blocks executed (66%)");
+    }
+
+    #[test]
+    fn describes_a_block_by_its_file_position_and_action() {
+        assert_eq!(
+            block(BlockStatus::Skipped).to_string(),
+            "one.md block 1, script 'build': skipped"
+        );
+    }
+
+    #[test]
+    fn includes_the_section_in_the_description_when_there_is_one() {
+        let block = BlockCoverage {
+            section: SectionPath(vec!["Installation".to_string()]),
+            ..block(BlockStatus::Passed)
+        };
+
+        assert_eq!(
+            block.to_string(),
+            "one.md block 1 (Installation), script 'build': passed"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use crate::runner::wildcard;
+use crate::types::{
+    Case, DirectoryExpectation, Encoding, Matcher, OutputRange, Rendering, Stream, StreamUsage,
+    VerifyOptions, VerifyValue, Whitespace,
+};
+
+use super::action_result::{
+    ActionResult, IdempotencyFailure, ScriptResult, VerifyDirectoryResult, VerifyFailure,
+    VerifyFileResult, VerifyResult,
+};
+
+pub fn explain(result: &ActionResult) -> Option<String> {
+    match result {
+        ActionResult::Script(result) => Some(explain_script(result)),
+        ActionResult::Verify(result) => Some(explain_verify(result)),
+        ActionResult::VerifyFile(result) => Some(explain_verify_file(result)),
+        ActionResult::VerifyDirectory(result) => Some(explain_verify_directory(result)),
+        ActionResult::CreateFile(_)
+        | ActionResult::Note(_)
+        | ActionResult::SetEnv(_)
+        | ActionResult::Stdin(_)
+        | ActionResult::Skipped(_) => None,
+    }
+}
+
+fn explain_script(result: &ScriptResult) -> String {
+    let outcome = match (result.signal, result.exit_code) {
+        (Some(signal), _) => format!("was killed by signal {signal}"),
+        (None, Some(exit_code)) => format!("exited with code {}", String::from(exit_code)),
+        (None, None) => "exited without an exit code".to_string(),
+    };
+    let expectation = match (
+        result.action.options.expected_signal,
+        &result.action.expected_exit_code,
+    ) {
+        (Some(expected), _) if Some(expected) == result.signal => {
+            ", which was the expected signal".to_string()
+        }
+        (Some(expected), _) => format!(", but signal {expected} was expected"),
+        (None, Some(expected)) if result.exit_code.is_some_and(|code| expected.matches(code)) => {
+            ", which was the expected exit code".to_string()
+        }
+        (None, Some(expected)) => format!(", but {expected} was expected"),
+        (None, None) => ", and its exit code was not checked".to_string(),
+    };
+    let idempotency = match &result.idempotency_failure {
+        Some(IdempotencyFailure::OutputChanged { .. }) => {
+            "; its output changed when it was run a second time"
+        }
+        Some(IdempotencyFailure::ChangedMarkerPrinted { .. }) => {
+            "; it printed its changed marker when it was run a second time"
+        }
+        None if result.action.options.idempotent => "; it was idempotent",
+        None => "",
+    };
+    let stability = match (&result.stability_failure, result.action.options.stable_runs) {
+        (Some(failure), runs) => format!(
+            "; run {} of {} printed different output to the first",
+            failure.run,
+            runs.unwrap_or(failure.run)
+        ),
+        (None, Some(runs)) => format!("; it printed the same output on all {runs} runs"),
+        (None, None) => String::new(),
+    };
+
+    format!("the script {outcome}{expectation}{idempotency}{stability}")
+}
+
+fn explain_verify(result: &VerifyResult) -> String {
+    let VerifyResult {
+        action,
+        got,
+        failure,
+        ..
+    } = result;
+    let stream = match action.source.stream {
+        Stream::StdOut => "stdout",
+        Stream::StdErr => "stderr",
+        Stream::Output => "output",
+    };
+
+    if let Some(name) = &action.options.env_changed {
+        let change = if action.expected_value.0.trim_end().is_empty() {
+            format!("{name} was checked to have changed while the script ran")
+        } else {
+            format!("{name} was checked to have changed to the expected value while the script ran")
+        };
+        return match failure {
+            None => format!("{change}, and it had"),
+            Some(failure) => format!("{change}, but {}", failure_reason(failure)),
+        };
+    }
+
+    if let Some(stream_usage) = &action.options.stream_usage {
+        let used = match stream_usage {
+            StreamUsage::StdOutOnly => "stdout",
+            StreamUsage::StdErrOnly => "stderr",
+        };
+        return match failure {
+            None => format!("the script was checked to write only to {used}, and it did"),
+            Some(failure) => format!(
+                "the script was checked to write only to {used}, and {}",
+                failure_reason(failure)
+            ),
+        };
+    }
+
+    format!(
+        "{stream} was {}{}, and {}",
+        comparison(&action.options),
+        normalizations(&action.options),
+        outcome(
+            &action.options,
+            &action.expected_value,
+            got,
+            failure.as_ref()
+        )
+    )
+}
+
+fn explain_verify_file(result: &VerifyFileResult) -> String {
+    let VerifyFileResult {
+        action,
+        got,
+        failure,
+    } = result;
+
+    if let Some(mode) = action.mode {
+        return match failure {
+            None => format!("the file's mode was compared with {mode}, and it matched"),
+            Some(failure) => format!(
+                "the file's mode was compared with {mode}, and {}",
+                failure_reason(failure)
+            ),
+        };
+    }
+
+    format!(
+        "the file was {}{}, and {}",
+        comparison(&action.options),
+        normalizations(&action.options),
+        outcome(
+            &action.options,
+            &action.expected_value,
+            got,
+            failure.as_ref()
+        )
+    )
+}
+
+fn explain_verify_directory(result: &VerifyDirectoryResult) -> String {
+    let differences = result.missing.len() + result.extra.len() + result.differing.len();
+    let outcome = if differences == 0 {
+        "every file matched".to_string()
+    } else {
+        format!(
+            "{} missing, {} extra and {} differing",
+            result.missing.len(),
+            result.extra.len(),
+            result.differing.len()
+        )
+    };
+
+    match &result.action.expected {
+        DirectoryExpectation::Golden(golden) => format!(
+            "the directory was compared file by file with '{}', and {outcome}",
+            String::from(golden.clone())
+        ),
+        DirectoryExpectation::Tree(_) => {
+            format!(
+                "the files in the directory were matched against the listed paths, and {outcome}"
+            )
+        }
+    }
+}
+
+fn comparison(options: &VerifyOptions) -> String {
+    if let Some(encoding) = &options.encoding {
+        return match encoding {
+            Encoding::Utf8 => "checked to be valid UTF-8".to_string(),
+            Encoding::Ascii => "checked to be valid ASCII".to_string(),
+            Encoding::Base64 => "compared byte for byte with the base64 expected value".to_string(),
+        };
+    }
+
+    let matcher = match &options.matcher {
+        Matcher::Exact => "compared exactly with the expected value".to_string(),
+        Matcher::Fuzzy { max_distance } => format!(
+            "compared with the expected value allowing up to {max_distance} edit{}",
+            if *max_distance == 1 { "" } else { "s" }
+        ),
+        Matcher::StartsWith => "checked to start with the expected value".to_string(),
+        Matcher::EndsWith => "checked to end with the expected value".to_string(),
+        Matcher::Contains => "checked to contain the expected value".to_string(),
+        Matcher::Number { comparison, value } => {
+            format!("read as a number and checked to be {comparison} {value}")
+        }
+        Matcher::Semver { comparison, value } => {
+            format!("read as a semantic version and checked to be {comparison} {value}")
+        }
+        Matcher::LineCount => "checked to have the expected number of lines".to_string(),
+        Matcher::ValidJson => "checked to be valid JSON".to_string(),
+        Matcher::Json => "compared structurally with the expected JSON".to_string(),
+        Matcher::Regex => "matched against the expected regular expression".to_string(),
+        Matcher::Yaml => "compared structurally with the expected YAML".to_string(),
+        Matcher::Numeric { tolerance } => format!(
+            "compared with the expected value allowing numbers to differ by up to {tolerance}"
+        ),
+        Matcher::Glob => "matched line by line against the expected glob patterns".to_string(),
+        Matcher::Wildcard => {
+            "compared with the expected value, letting each `...` line match any lines".to_string()
+        }
+    };
+
+    if options.any_of {
+        format!(
+            "{matcher} and its {} alternative{}",
+            options.alternatives.len(),
+            if options.alternatives.len() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        )
+    } else {
+        matcher
+    }
+}
+
+fn normalizations(options: &VerifyOptions) -> String {
+    let mut applied = vec!["stripping ANSI escape codes".to_string()];
+    if options.output_range == OutputRange::SinceLast {
+        applied.push("dropping the output seen by the previous verify".to_string());
+    }
+    if options.mask_paths {
+        applied.push("masking paths".to_string());
+    }
+    if !options.substitutions.is_empty() {
+        applied.push(format!(
+            "applying {} substitution{}",
+            options.substitutions.len(),
+            if options.substitutions.len() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ));
+    }
+    if let Some(command) = &options.normalize_with {
+        applied.push(format!("normalizing both values with `{command}`"));
+    }
+    if options.case == Case::Insensitive {
+        applied.push("ignoring case".to_string());
+    }
+    match options.whitespace {
+        Whitespace::Trim => applied.push("trimming surrounding whitespace".to_string()),
+        Whitespace::TrimTrailing => applied.push("trimming trailing whitespace".to_string()),
+        Whitespace::Keep => {}
+    }
+
+    let last = applied
+        .pop()
+        .expect("ANSI escape codes are always stripped");
+    let mut description = if applied.is_empty() {
+        format!(" after {last}")
+    } else {
+        format!(" after {} and {last}", applied.join(", "))
+    };
+    if options.whitespace == Whitespace::Keep {
+        description.push_str(" but without trimming whitespace");
+    }
+    if options.rendering == Rendering::Raw {
+        description.push_str(", leaving variables in the expected value unrendered");
+    }
+    description
+}
+
+fn outcome(
+    options: &VerifyOptions,
+    expected: &VerifyValue,
+    got: &str,
+    failure: Option<&VerifyFailure>,
+) -> String {
+    match failure {
+        None => "it matched".to_string(),
+        Some(VerifyFailure::OutputDoesNotMatch) => {
+            let VerifyValue(expected) = expected;
+            let mut expected = options.trim_whitespace(expected);
+            let got = options.trim_whitespace(got);
+            if options.matcher == Matcher::Wildcard {
+                expected = wildcard::expand(&expected, &got).expected;
+            }
+            if options.case == Case::Insensitive {
+                first_difference(&expected.to_lowercase(), &got.to_lowercase())
+            } else {
+                first_difference(&expected, &got)
+            }
+        }
+        Some(failure) => failure_reason(failure),
+    }
+}
+
+fn failure_reason(failure: &VerifyFailure) -> String {
+    match failure {
+        VerifyFailure::OutputDoesNotMatch => "it did not match".to_string(),
+        VerifyFailure::MatcherFailed { reason } | VerifyFailure::InvalidEncoding { reason } => {
+            format!("it did not match because {reason}")
+        }
+        VerifyFailure::BytesDoNotMatch { offset } => {
+            format!("the first difference is at byte {offset}")
+        }
+        VerifyFailure::NoAlternativeMatched => "none of the alternatives matched".to_string(),
+        VerifyFailure::FileMissing => "the file does not exist".to_string(),
+        VerifyFailure::FileExists => "the file exists".to_string(),
+        VerifyFailure::ModeDoesNotMatch { got, .. } => format!("its mode was {got}"),
+        VerifyFailure::EnvironmentDoesNotMatch {
+            name,
+            before,
+            after,
+        } => match (before, after) {
+            (before, after) if before == after => format!("{name} was not changed"),
+            (_, Some(after)) => format!("{name} was changed to '{after}'"),
+            (_, None) => format!("{name} was unset"),
+        },
+        VerifyFailure::UnexpectedStream { stream } => format!(
+            "the script also wrote to {}",
+            match stream {
+                Stream::StdOut => "stdout",
+                Stream::StdErr => "stderr",
+                Stream::Output => "output",
+            }
+        ),
+    }
+}
+
+fn first_difference(expected: &str, got: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let got_lines: Vec<&str> = got.lines().collect();
+
+    let differing_line = expected_lines
+        .iter()
+        .zip(&got_lines)
+        .position(|(expected, got)| expected != got);
+
+    match differing_line {
+        Some(index) => format!("the first difference is on line {}", index + 1),
+        None if expected_lines.len() != got_lines.len() => format!(
+            "it has {} line{} where {} {} expected",
+            got_lines.len(),
+            if got_lines.len() == 1 { "" } else { "s" },
+            expected_lines.len(),
+            if expected_lines.len() == 1 {
+                "was"
+            } else {
+                "were"
+            }
+        ),
+        None => "it differs only in its trailing newline".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::explain;
+    use crate::results::{ActionResult, VerifyFailure, VerifyResult};
+    use crate::types::{
+        Matcher, Source, Stream, VerifyAction, VerifyOptions, VerifyValue, Whitespace,
+    };
+
+    fn verify_result(
+        options: VerifyOptions,
+        got: &str,
+        failure: Option<VerifyFailure>,
+    ) -> ActionResult {
+        ActionResult::Verify(VerifyResult {
+            action: VerifyAction {
+                source: Source {
+                    name: None,
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue("first\nsecond\n".to_string()),
+                options,
+            },
+            got: got.to_string(),
+            failure,
+            displayed: None,
+        })
+    }
+
+    #[test]
+    fn explains_the_matcher_and_trimming_of_a_trimmed_verify() {
+        let result = verify_result(
+            VerifyOptions {
+                whitespace: Whitespace::Trim,
+                ..VerifyOptions::default()
+            },
+            "  first\nsecond  \n",
+            None,
+        );
+
+        assert_eq!(
+            explain(&result).expect("an explanation"),
+            "stdout was compared exactly with the expected value after stripping ANSI escape \
+             codes and trimming surrounding whitespace, and it matched"
+        );
+    }
+
+    #[test]
+    fn explains_where_the_output_first_differs() {
+        let result = verify_result(
+            VerifyOptions::default(),
+            "first\nthird\n",
+            Some(VerifyFailure::OutputDoesNotMatch),
+        );
+
+        assert_eq!(
+            explain(&result).expect("an explanation"),
+            "stdout was compared exactly with the expected value after stripping ANSI escape \
+             codes but without trimming whitespace, and the first difference is on line 2"
+        );
+    }
+
+    #[test]
+    fn explains_the_reason_a_matcher_failed() {
+        let result = verify_result(
+            VerifyOptions {
+                matcher: Matcher::ValidJson,
+                whitespace: Whitespace::Trim,
+                ..VerifyOptions::default()
+            },
+            "{",
+            Some(VerifyFailure::MatcherFailed {
+                reason: "the output is not valid JSON".to_string(),
+            }),
+        );
+
+        assert_eq!(
+            explain(&result).expect("an explanation"),
+            "stdout was checked to be valid JSON after stripping ANSI escape codes and trimming \
+             surrounding whitespace, and it did not match because the output is not valid JSON"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+#[derive(Debug, Eq, PartialEq)]
+pub struct LineDifference<'a> {
+    pub number: usize,
+    pub expected: Option<&'a str>,
+    pub got: Option<&'a str>,
+}
+
+pub fn differences<'a>(expected: &'a str, got: &'a str) -> Vec<LineDifference<'a>> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let got: Vec<&str> = got.lines().collect();
+
+    (0..expected.len().max(got.len()))
+        .map(|index| LineDifference {
+            number: index + 1,
+            expected: expected.get(index).copied(),
+            got: got.get(index).copied(),
+        })
+        .filter(|difference| difference.expected != difference.got)
+        .collect()
+}
+
+pub fn format(differences: &[LineDifference<'_>]) -> String {
+    differences
+        .iter()
+        .map(|difference| {
+            format!(
+                "  line {}: expected {}, got {}",
+                difference.number,
+                describe_line(difference.expected),
+                describe_line(difference.got)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn describe_line(line: Option<&str>) -> String {
+    line.map_or_else(|| "no line".to_string(), |line| format!("{line:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{differences, format, LineDifference};
+
+    #[test]
+    fn returns_no_differences_when_the_lines_match() {
+        assert_eq!(differences("a\nb\n", "a\nb\n"), vec![]);
+    }
+
+    #[test]
+    fn returns_each_line_which_differs() {
+        assert_eq!(
+            differences("a\nb\nc\nd\n", "a\nx\nc\ny\n"),
+            vec![
+                LineDifference {
+                    number: 2,
+                    expected: Some("b"),
+                    got: Some("x"),
+                },
+                LineDifference {
+                    number: 4,
+                    expected: Some("d"),
+                    got: Some("y"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_missing_and_extra_lines() {
+        assert_eq!(
+            differences("a\nb\n", "a\n"),
+            vec![LineDifference {
+                number: 2,
+                expected: Some("b"),
+                got: None,
+            }]
+        );
+        assert_eq!(
+            differences("a\n", "a\nb\n"),
+            vec![LineDifference {
+                number: 2,
+                expected: None,
+                got: Some("b"),
+            }]
+        );
+    }
+
+    #[test]
+    fn formats_each_difference_with_its_line_number_and_both_sides() {
+        assert_eq!(
+            format(&differences("a\nb\nc\n", "a\nx\n")),
+            [
+                "  line 2: expected \"b\", got \"x\"",
+                "  line 3: expected \"c\", got no line",
+            ]
+            .join("\n")
+        );
+    }
+}
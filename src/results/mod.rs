@@ -1,7 +1,22 @@
-pub use action_result::{ActionResult, CreateFileResult, ScriptResult, VerifyResult};
+pub use action_result::{
+    ActionResult, CreateFileResult, Displayed, EnvironmentSnapshot, IdempotencyFailure, NoteResult,
+    RawOutput, ScriptResult, SetEnvResult, SkippedResult, StabilityFailure, StdinResult,
+    VerifyDirectoryResult, VerifyFailure, VerifyFileResult, VerifyResult,
+};
+pub use coverage::{BlockCoverage, BlockStatus, Coverage};
+pub use ndjson_printer::NdjsonPrinter;
 pub use printer::Printer;
+pub use summary_printer::SummaryPrinter;
+pub use tui_printer::TuiPrinter;
 
 mod action_result;
 pub mod basic_printer;
+mod benchmark_table;
+mod coverage;
 mod diff_theme;
+mod explanation;
+mod line_diff;
+mod ndjson_printer;
 mod printer;
+mod summary_printer;
+mod tui_printer;
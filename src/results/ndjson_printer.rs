@@ -0,0 +1,254 @@
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use crate::ansi::strip_ansi_escape_chars;
+use crate::runner::RunEvent;
+use crate::types::SectionPath;
+
+use super::basic_printer::{error_message, BasicPrinter};
+use super::printer::Printer;
+
+pub struct NdjsonPrinter {
+    current_file: Option<PathBuf>,
+    current_section: Option<SectionPath>,
+}
+
+impl NdjsonPrinter {
+    pub const fn new() -> Self {
+        Self {
+            current_file: None,
+            current_section: None,
+        }
+    }
+
+    fn record(&mut self, event: &RunEvent) -> Value {
+        match event {
+            RunEvent::SpecFileStarted(path) => {
+                self.current_file = Some(path.clone());
+                self.current_section = None;
+            }
+            RunEvent::SectionStarted(section) => self.current_section = Some(section.clone()),
+            _ => {}
+        }
+
+        let spec_file = self
+            .current_file
+            .as_ref()
+            .map(|path| path.display().to_string());
+        let section = self.current_section.as_ref().map(ToString::to_string);
+        let record = match event {
+            RunEvent::SpecFileStarted(_) => json!({
+                "event": "spec_file_started",
+                "spec_file": spec_file,
+            }),
+            RunEvent::SectionStarted(_) => json!({
+                "event": "section_started",
+                "spec_file": spec_file,
+                "section": section,
+            }),
+            RunEvent::TestCompleted(result) => json!({
+                "event": "test_completed",
+                "spec_file": spec_file,
+                "section": section,
+                "success": result.success(),
+                "message": strip_ansi_escape_chars(&BasicPrinter::action_message(result)),
+            }),
+            RunEvent::FailureAllowed(result) => json!({
+                "event": "failure_allowed",
+                "spec_file": spec_file,
+                "section": section,
+                "message": strip_ansi_escape_chars(&BasicPrinter::action_message(result)),
+            }),
+            RunEvent::SpecFileCompleted { success } => json!({
+                "event": "spec_file_completed",
+                "spec_file": spec_file,
+                "success": success,
+            }),
+            RunEvent::ErrorOccurred(error) => json!({
+                "event": "error",
+                "spec_file": spec_file,
+                "message": error_message(error),
+            }),
+            RunEvent::Warning(message) => json!({
+                "event": "warning",
+                "spec_file": spec_file,
+                "message": message,
+            }),
+            RunEvent::Info(message) => json!({
+                "event": "info",
+                "spec_file": spec_file,
+                "message": message,
+            }),
+            RunEvent::CoverageCollected(coverage) => json!({
+                "event": "coverage",
+                "blocks": coverage
+                    .blocks
+                    .iter()
+                    .map(|block| json!({
+                        "spec_file": block.spec_file.display().to_string(),
+                        "block": block.block,
+                        "section": (!block.section.0.is_empty()).then(|| block.section.to_string()),
+                        "description": block.description,
+                        "status": block.status.to_string(),
+                    }))
+                    .collect::<Vec<Value>>(),
+                "executed": coverage.executed(),
+                "total": coverage.blocks.len(),
+                "percentage": coverage.percentage(),
+            }),
+        };
+
+        if let RunEvent::SpecFileCompleted { .. } = event {
+            self.current_file = None;
+            self.current_section = None;
+        }
+
+        record
+    }
+}
+
+impl Printer for NdjsonPrinter {
+    fn print(&mut self, event: &RunEvent) {
+        println!("{}", self.record(event));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serde_json::Value;
+
+    use super::{NdjsonPrinter, RunEvent};
+    use crate::results::{
+        ActionResult, BlockCoverage, BlockStatus, Coverage, RawOutput, ScriptResult,
+    };
+    use crate::runner::Error;
+    use crate::types::{
+        ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptName,
+        ScriptOptions, SectionPath,
+    };
+
+    fn script_result(exit_code: i32) -> ActionResult {
+        ActionResult::Script(ScriptResult {
+            action: ScriptAction {
+                script_name: Some(ScriptName("deploy".to_string())),
+                script_code: ScriptCode(format!("exit {exit_code}")),
+                expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+                expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
+            },
+            exit_code: Some(ExitCode(exit_code)),
+            stdout: String::new(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        })
+    }
+
+    #[test]
+    fn each_event_is_a_single_line_json_object_in_the_order_it_occurred() {
+        let mut printer = NdjsonPrinter::new();
+        let lines: Vec<String> = [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::Warning("slow".to_string()),
+            RunEvent::TestCompleted(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: false },
+            RunEvent::ErrorOccurred(Error::RunFailed {
+                message: "broken".to_string(),
+            }),
+        ]
+        .iter()
+        .map(|event| printer.record(event).to_string())
+        .collect();
+
+        assert!(lines.iter().all(|line| !line.contains('\n')));
+        let records: Vec<Value> = lines
+            .iter()
+            .map(|line| serde_json::from_str(line).expect("each line to be valid JSON"))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                serde_json::json!({"event": "spec_file_started", "spec_file": "one.md"}),
+                serde_json::json!({"event": "warning", "spec_file": "one.md", "message": "slow"}),
+                serde_json::json!({
+                    "event": "test_completed",
+                    "spec_file": "one.md",
+                    "section": null,
+                    "success": false,
+                    "message": "script 'deploy' exited 2, expected 0",
+                }),
+                serde_json::json!({
+                    "event": "spec_file_completed",
+                    "spec_file": "one.md",
+                    "success": false,
+                }),
+                serde_json::json!({"event": "error", "spec_file": null, "message": "broken"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn results_are_labelled_with_the_section_they_came_from() {
+        let mut printer = NdjsonPrinter::new();
+        let section = SectionPath(vec!["Installation".to_string(), "From source".to_string()]);
+        let records: Vec<Value> = [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::SectionStarted(section),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+        ]
+        .iter()
+        .map(|event| printer.record(event))
+        .collect();
+
+        assert_eq!(
+            records[1],
+            serde_json::json!({
+                "event": "section_started",
+                "spec_file": "one.md",
+                "section": "Installation > From source",
+            })
+        );
+        assert_eq!(records[2]["section"], "Installation > From source");
+    }
+
+    #[test]
+    fn coverage_is_a_single_record_listing_every_block() {
+        let coverage = Coverage {
+            blocks: vec![BlockCoverage {
+                spec_file: PathBuf::from("one.md"),
+                block: 1,
+                section: SectionPath::default(),
+                description: "script 'deploy'".to_string(),
+                status: BlockStatus::Skipped,
+            }],
+        };
+
+        assert_eq!(
+            NdjsonPrinter::new().record(&RunEvent::CoverageCollected(coverage)),
+            serde_json::json!({
+                "event": "coverage",
+                "blocks": [{
+                    "spec_file": "one.md",
+                    "block": 1,
+                    "section": null,
+                    "description": "script 'deploy'",
+                    "status": "skipped",
+                }],
+                "executed": 0,
+                "total": 1,
+                "percentage": 0,
+            })
+        );
+    }
+}
@@ -2,4 +2,6 @@ use crate::runner::RunEvent;
 
 pub trait Printer {
     fn print(&mut self, event: &RunEvent);
+
+    fn finish(&mut self) {}
 }
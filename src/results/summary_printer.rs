@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::style::Stylize;
+
+use crate::ansi::strip_ansi_escape_chars;
+use crate::runner::RunEvent;
+use crate::types::SectionPath;
+
+use super::action_result::{ActionResult, NoteResult};
+use super::basic_printer::{error_message, BasicPrinter};
+use super::printer::Printer;
+
+pub struct SummaryPrinter {
+    colour: bool,
+    current_file: Option<PathBuf>,
+    current_section: Option<SectionPath>,
+    number_of_files: u32,
+    number_succeeded: u32,
+    number_failed: u32,
+    number_allowed_failures: u32,
+    number_skipped: u32,
+    problems: Vec<String>,
+    coverage: Option<String>,
+}
+
+impl SummaryPrinter {
+    pub const fn new(colour: bool) -> Self {
+        Self {
+            colour,
+            current_file: None,
+            current_section: None,
+            number_of_files: 0,
+            number_succeeded: 0,
+            number_failed: 0,
+            number_allowed_failures: 0,
+            number_skipped: 0,
+            problems: Vec::new(),
+            coverage: None,
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = self.problems.clone();
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        let others = [
+            (self.number_allowed_failures, "allowed to fail"),
+            (self.number_skipped, "skipped"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!(" / {count} {label}"))
+        .collect::<Vec<_>>()
+        .concat();
+        lines.push(format!(
+            "  {} files run, {} functions run ({} succeeded / {} failed{others})",
+            self.number_of_files,
+            self.number_failed
+                + self.number_succeeded
+                + self.number_allowed_failures
+                + self.number_skipped,
+            self.number_succeeded,
+            self.number_failed
+        ));
+        if let Some(coverage) = &self.coverage {
+            lines.push(format!("  {coverage}"));
+        }
+        lines
+    }
+
+    fn record_result(&mut self, result: &ActionResult) {
+        if let ActionResult::Note(NoteResult { .. }) = result {
+            return;
+        }
+
+        if let ActionResult::Skipped(_) = result {
+            self.number_skipped += 1;
+            return;
+        }
+
+        if result.success() {
+            self.number_succeeded += 1;
+        } else {
+            self.number_failed += 1;
+            self.record_problem(&format!(
+                "{}",
+                format!(
+                    "  \u{2717} {}",
+                    self.in_current_file(&BasicPrinter::action_message(result))
+                )
+                .red()
+            ));
+        }
+    }
+
+    fn record_problem(&mut self, text: &str) {
+        self.problems.push(if self.colour {
+            text.to_string()
+        } else {
+            strip_ansi_escape_chars(text)
+        });
+    }
+
+    // Prefixes the message with the file and section it came from, e.g.
+    // `deploy.md > Rollback: message`
+    fn in_current_file(&self, message: &str) -> String {
+        let section = self
+            .current_section
+            .as_ref()
+            .map_or_else(String::new, |section| format!(" > {section}"));
+        self.current_file.as_deref().map_or_else(
+            || message.to_string(),
+            |path: &Path| format!("{}{section}: {message}", path.display()),
+        )
+    }
+}
+
+impl Printer for SummaryPrinter {
+    fn print(&mut self, event: &RunEvent) {
+        match event {
+            RunEvent::SpecFileStarted(path) => {
+                self.number_of_files += 1;
+                self.current_file = Some(path.clone());
+                self.current_section = None;
+            }
+            RunEvent::SectionStarted(section) => {
+                self.current_section =
+                    Some(section.clone()).filter(|section| !section.0.is_empty());
+            }
+            RunEvent::Info(_) => {}
+            RunEvent::TestCompleted(result) => self.record_result(result),
+            RunEvent::FailureAllowed(result) => {
+                self.number_allowed_failures += 1;
+                let message = format!(
+                    "  \u{26a0} {} (failure allowed)",
+                    self.in_current_file(&BasicPrinter::action_message(result))
+                );
+                self.record_problem(&format!("{}", message.yellow()));
+            }
+            RunEvent::SpecFileCompleted { .. } => {
+                self.current_file = None;
+                self.current_section = None;
+            }
+            RunEvent::ErrorOccurred(error) => {
+                let message = format!("  \u{2717} {}", self.in_current_file(&error_message(error)));
+                self.record_problem(&format!("{}", message.red()));
+            }
+            RunEvent::Warning(message) => {
+                let message = format!("  \u{26a0} {}", self.in_current_file(message));
+                self.record_problem(&format!("{}", message.yellow()));
+            }
+            RunEvent::CoverageCollected(coverage) => self.coverage = Some(coverage.summary()),
+        }
+    }
+
+    fn finish(&mut self) {
+        for line in self.lines() {
+            println!("{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Printer, RunEvent, SummaryPrinter};
+    use crate::results::{ActionResult, RawOutput, ScriptResult};
+    use crate::runner::Error;
+    use crate::types::{
+        ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptName,
+        ScriptOptions, SectionPath,
+    };
+
+    fn script_result(exit_code: i32) -> ActionResult {
+        ActionResult::Script(ScriptResult {
+            action: ScriptAction {
+                script_name: Some(ScriptName("deploy".to_string())),
+                script_code: ScriptCode(format!("exit {exit_code}")),
+                expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+                expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
+            },
+            exit_code: Some(ExitCode(exit_code)),
+            stdout: String::new(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        })
+    }
+
+    #[test]
+    fn only_the_totals_are_shown_when_everything_succeeds() {
+        let mut printer = SummaryPrinter::new(false);
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+            RunEvent::SpecFileCompleted { success: true },
+            RunEvent::SpecFileStarted(PathBuf::from("two.md")),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+            RunEvent::SpecFileCompleted { success: true },
+        ] {
+            printer.print(&event);
+        }
+
+        assert_eq!(
+            printer.lines(),
+            vec!["  2 files run, 2 functions run (2 succeeded / 0 failed)".to_string()]
+        );
+    }
+
+    #[test]
+    fn failures_and_errors_are_listed_one_per_line_before_the_totals() {
+        let mut printer = SummaryPrinter::new(false);
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+            RunEvent::TestCompleted(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: false },
+            RunEvent::SpecFileStarted(PathBuf::from("two.md")),
+            RunEvent::ErrorOccurred(Error::RunFailed {
+                message: "broken".to_string(),
+            }),
+            RunEvent::SpecFileCompleted { success: false },
+        ] {
+            printer.print(&event);
+        }
+
+        assert_eq!(
+            printer.lines(),
+            vec![
+                "  \u{2717} one.md: script 'deploy' exited 2, expected 0".to_string(),
+                "  \u{2717} two.md: broken".to_string(),
+                String::new(),
+                "  2 files run, 2 functions run (1 succeeded / 1 failed)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn allowed_failures_are_listed_and_counted_separately() {
+        let mut printer = SummaryPrinter::new(false);
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+            RunEvent::FailureAllowed(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: true },
+        ] {
+            printer.print(&event);
+        }
+
+        assert_eq!(
+            printer.lines(),
+            vec![
+                "  \u{26a0} one.md: script 'deploy' exited 2, expected 0 (failure allowed)"
+                    .to_string(),
+                String::new(),
+                "  1 files run, 2 functions run (1 succeeded / 0 failed / 1 allowed to fail)"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn failures_include_the_section_they_happened_in() {
+        let mut printer = SummaryPrinter::new(false);
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::SectionStarted(SectionPath(vec![
+                "Deploy".to_string(),
+                "Rollback".to_string(),
+            ])),
+            RunEvent::TestCompleted(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: false },
+            RunEvent::SpecFileStarted(PathBuf::from("two.md")),
+            RunEvent::TestCompleted(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: false },
+        ] {
+            printer.print(&event);
+        }
+
+        assert_eq!(
+            printer.lines(),
+            vec![
+                "  \u{2717} one.md > Deploy > Rollback: script 'deploy' exited 2, expected 0"
+                    .to_string(),
+                "  \u{2717} two.md: script 'deploy' exited 2, expected 0".to_string(),
+                String::new(),
+                "  2 files run, 2 functions run (0 succeeded / 2 failed)".to_string(),
+            ]
+        );
+    }
+}
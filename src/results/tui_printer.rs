@@ -0,0 +1,448 @@
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Print, SetAttribute, Stylize};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::runner::RunEvent;
+
+use super::action_result::{ActionResult, ScriptResult};
+use super::basic_printer::{error_message, BasicPrinter};
+use super::coverage::BlockStatus;
+use super::printer::Printer;
+
+const HELP: &str = "\u{2191}/\u{2193} move  Enter expand  q quit";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum LineKind {
+    Heading,
+    Success,
+    Failure,
+    Warning,
+    Note,
+    Detail,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Line {
+    text: String,
+    kind: LineKind,
+    selected: bool,
+}
+
+enum Entry {
+    Heading(String),
+    Item {
+        summary: String,
+        kind: LineKind,
+        detail: Vec<String>,
+        expanded: bool,
+    },
+}
+
+pub struct TuiPrinter {
+    entries: Vec<Entry>,
+    selected: Option<usize>,
+}
+
+impl TuiPrinter {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            selected: None,
+        }
+    }
+
+    fn add_item(&mut self, summary: String, kind: LineKind, detail: Vec<String>) {
+        self.entries.push(Entry::Item {
+            summary,
+            kind,
+            detail,
+            expanded: false,
+        });
+        if self.selected.is_none() {
+            self.selected = Some(self.entries.len() - 1);
+        }
+    }
+
+    fn add_result(&mut self, result: &ActionResult) {
+        let kind = match result {
+            ActionResult::Note(_) => LineKind::Note,
+            ActionResult::Skipped(_) => LineKind::Warning,
+            _ if result.success() => LineKind::Success,
+            _ => LineKind::Failure,
+        };
+        let mut detail = result_detail(result);
+        if let ActionResult::Script(ScriptResult { stdout, stderr, .. }) = result {
+            for (label, output) in [("stdout", stdout), ("stderr", stderr)] {
+                if !output.is_empty() {
+                    detail.push(format!("=== {label}:"));
+                    detail.extend(output.lines().map(ToString::to_string));
+                }
+            }
+        }
+        self.add_item(BasicPrinter::action_message(result), kind, detail);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Enter => {
+                if let Some(Entry::Item { expanded, .. }) =
+                    self.selected.and_then(|index| self.entries.get_mut(index))
+                {
+                    *expanded = !*expanded;
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn move_selection(&mut self, step: isize) {
+        let Some(mut index) = self.selected else {
+            return;
+        };
+        loop {
+            index = match index.checked_add_signed(step) {
+                Some(index) if index < self.entries.len() => index,
+                _ => return,
+            };
+            if let Entry::Item { .. } = self.entries[index] {
+                self.selected = Some(index);
+                return;
+            }
+        }
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = vec![];
+        for (index, entry) in self.entries.iter().enumerate() {
+            match entry {
+                Entry::Heading(path) => lines.push(Line {
+                    text: path.clone(),
+                    kind: LineKind::Heading,
+                    selected: false,
+                }),
+                Entry::Item {
+                    summary,
+                    kind,
+                    detail,
+                    expanded,
+                } => {
+                    let marker = if detail.is_empty() {
+                        ' '
+                    } else if *expanded {
+                        '-'
+                    } else {
+                        '+'
+                    };
+                    lines.push(Line {
+                        text: format!("  {marker} {} {summary}", symbol(*kind)),
+                        kind: *kind,
+                        selected: self.selected == Some(index),
+                    });
+                    if *expanded {
+                        lines.extend(detail.iter().map(|text| Line {
+                            text: format!("      {text}"),
+                            kind: LineKind::Detail,
+                            selected: false,
+                        }));
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    fn visible_lines(&self, height: usize) -> Vec<Line> {
+        let lines = self.lines();
+        let selected = lines.iter().position(|line| line.selected).unwrap_or(0);
+        let offset = (selected + 1).saturating_sub(height);
+        lines.into_iter().skip(offset).take(height).collect()
+    }
+
+    fn browse(
+        &mut self,
+        next_key: &mut dyn FnMut() -> io::Result<KeyCode>,
+        draw: &mut dyn FnMut(&TuiPrinter) -> io::Result<()>,
+    ) -> io::Result<()> {
+        loop {
+            draw(self)?;
+            if !self.handle_key(next_key()?) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Printer for TuiPrinter {
+    fn print(&mut self, event: &RunEvent) {
+        match event {
+            RunEvent::SpecFileStarted(path) => {
+                self.entries
+                    .push(Entry::Heading(path.display().to_string()));
+            }
+            RunEvent::SectionStarted(section) => {
+                self.entries.push(Entry::Heading(format!("  {section}")));
+            }
+            RunEvent::TestCompleted(result) => self.add_result(result),
+            RunEvent::FailureAllowed(result) => {
+                let detail = result_detail(result);
+                self.add_item(
+                    format!("{} (failure allowed)", BasicPrinter::action_message(result)),
+                    LineKind::Warning,
+                    detail,
+                );
+            }
+            RunEvent::SpecFileCompleted { .. } => {}
+            RunEvent::ErrorOccurred(error) => {
+                self.add_item(error_message(error), LineKind::Failure, vec![]);
+            }
+            RunEvent::Warning(message) => {
+                self.add_item(message.clone(), LineKind::Warning, vec![]);
+            }
+            RunEvent::Info(message) => {
+                self.add_item(message.clone(), LineKind::Note, vec![]);
+            }
+            RunEvent::CoverageCollected(coverage) => {
+                self.entries
+                    .push(Entry::Heading(format!("Coverage: {}", coverage.summary())));
+                for block in &coverage.blocks {
+                    let kind = match block.status {
+                        BlockStatus::Passed => LineKind::Success,
+                        BlockStatus::Failed => LineKind::Failure,
+                        BlockStatus::Skipped => LineKind::Warning,
+                    };
+                    self.add_item(block.to_string(), kind, vec![]);
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Err(err) = run_in_terminal(self) {
+            eprintln!("  \u{2717} The results browser failed (Error: {err})");
+        }
+    }
+}
+
+const fn symbol(kind: LineKind) -> char {
+    match kind {
+        LineKind::Success => '\u{2713}',
+        LineKind::Failure => '\u{2717}',
+        LineKind::Warning => '\u{26a0}',
+        LineKind::Heading | LineKind::Note | LineKind::Detail => '-',
+    }
+}
+
+fn result_detail(result: &ActionResult) -> Vec<String> {
+    let captured = Rc::new(RefCell::new(vec![]));
+    let sink = Rc::clone(&captured);
+    let mut printer = BasicPrinter::new(false).display_with(move |text: &str| {
+        sink.borrow_mut()
+            .extend(text.lines().map(ToString::to_string));
+    });
+    printer.print(&RunEvent::TestCompleted(Box::new(result.clone())));
+
+    let mut lines = captured.take();
+    if !lines.is_empty() {
+        lines.remove(0);
+    }
+    lines
+}
+
+fn run_in_terminal(printer: &mut TuiPrinter) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = printer.browse(&mut next_key, &mut |printer| draw(&mut stdout, printer));
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn next_key() -> io::Result<KeyCode> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(key.code);
+            }
+        }
+    }
+}
+
+fn draw(stdout: &mut io::Stdout, printer: &TuiPrinter) -> io::Result<()> {
+    let (width, height) = match terminal::size()? {
+        (0, _) | (_, 0) => (80, 24),
+        size => size,
+    };
+    let width = usize::from(width);
+    let height = usize::from(height).saturating_sub(1);
+
+    queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+    for (row, line) in (0..).zip(printer.visible_lines(height)) {
+        let text: String = line.text.chars().take(width).collect();
+        let styled = match line.kind {
+            LineKind::Heading => text.bold().blue(),
+            LineKind::Success => text.green(),
+            LineKind::Failure => text.red(),
+            LineKind::Warning => text.yellow(),
+            LineKind::Note | LineKind::Detail => text.stylize(),
+        };
+        queue!(stdout, cursor::MoveTo(0, row))?;
+        if line.selected {
+            queue!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        queue!(stdout, Print(styled), SetAttribute(Attribute::Reset))?;
+    }
+    let help_row = u16::try_from(height).unwrap_or(u16::MAX);
+    queue!(stdout, cursor::MoveTo(0, help_row), Print(HELP.dim()))?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crossterm::event::KeyCode;
+
+    use super::{Line, LineKind, Printer, RunEvent, TuiPrinter};
+    use crate::results::{ActionResult, RawOutput, ScriptResult};
+    use crate::types::{
+        ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptName,
+        ScriptOptions,
+    };
+
+    fn script_result(exit_code: i32) -> ActionResult {
+        ActionResult::Script(ScriptResult {
+            action: ScriptAction {
+                script_name: Some(ScriptName("deploy".to_string())),
+                script_code: ScriptCode(format!("exit {exit_code}")),
+                expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+                expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
+            },
+            exit_code: Some(ExitCode(exit_code)),
+            stdout: "deploying\n".to_string(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        })
+    }
+
+    fn printer() -> TuiPrinter {
+        let mut printer = TuiPrinter::new();
+        for event in [
+            RunEvent::SpecFileStarted(PathBuf::from("one.md")),
+            RunEvent::TestCompleted(Box::new(script_result(0))),
+            RunEvent::TestCompleted(Box::new(script_result(2))),
+            RunEvent::SpecFileCompleted { success: false },
+        ] {
+            printer.print(&event);
+        }
+        printer
+    }
+
+    fn line(text: &str, kind: LineKind, selected: bool) -> Line {
+        Line {
+            text: text.to_string(),
+            kind,
+            selected,
+        }
+    }
+
+    #[test]
+    fn starts_with_the_first_result_selected_and_exits_on_q() {
+        let mut printer = printer();
+        let mut keys = vec![KeyCode::Char('q')].into_iter();
+        let mut draws = 0;
+
+        printer
+            .browse(&mut || Ok(keys.next().expect("another key")), &mut |_| {
+                draws += 1;
+                Ok(())
+            })
+            .expect("the browser to exit cleanly");
+
+        assert_eq!(draws, 1);
+        assert_eq!(
+            printer.lines(),
+            vec![
+                line("one.md", LineKind::Heading, false),
+                line(
+                    "  + \u{2713} running script 'deploy' succeeded",
+                    LineKind::Success,
+                    true
+                ),
+                line(
+                    "  + \u{2717} script 'deploy' exited 2, expected 0",
+                    LineKind::Failure,
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn enter_expands_the_selected_result_to_show_its_output() {
+        let mut printer = printer();
+        let mut keys = vec![KeyCode::Down, KeyCode::Enter, KeyCode::Char('q')].into_iter();
+
+        printer
+            .browse(&mut || Ok(keys.next().expect("another key")), &mut |_| {
+                Ok(())
+            })
+            .expect("the browser to exit cleanly");
+
+        assert_eq!(
+            printer.lines()[2..],
+            [
+                line(
+                    "  - \u{2717} script 'deploy' exited 2, expected 0",
+                    LineKind::Failure,
+                    true
+                ),
+                line("      === stdout:", LineKind::Detail, false),
+                line("      deploying", LineKind::Detail, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_visible_lines_scroll_to_keep_the_selection_on_screen() {
+        let mut printer = printer();
+        printer.handle_key(KeyCode::Down);
+
+        assert_eq!(
+            printer.visible_lines(2),
+            vec![
+                line(
+                    "  + \u{2713} running script 'deploy' succeeded",
+                    LineKind::Success,
+                    false
+                ),
+                line(
+                    "  + \u{2717} script 'deploy' exited 2, expected 0",
+                    LineKind::Failure,
+                    true
+                ),
+            ]
+        );
+    }
+}
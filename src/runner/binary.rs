@@ -0,0 +1,77 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use crate::results::VerifyFailure;
+use crate::types::VerifyValue;
+
+use super::Error;
+
+pub fn compare(expected: &VerifyValue, got: &[u8]) -> Result<Option<VerifyFailure>, Error> {
+    let VerifyValue(encoded) = expected;
+    let encoded: String = encoded.split_whitespace().collect();
+    let expected = STANDARD.decode(encoded).map_err(|err| Error::RunFailed {
+        message: format!("Failed to decode the expected output as base64 (Error: {err})"),
+    })?;
+
+    Ok(first_difference(&expected, got).map(|offset| VerifyFailure::BytesDoNotMatch { offset }))
+}
+
+fn first_difference(expected: &[u8], got: &[u8]) -> Option<usize> {
+    expected
+        .iter()
+        .zip(got)
+        .position(|(expected, got)| expected != got)
+        .or_else(|| (expected.len() != got.len()).then_some(expected.len().min(got.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Error, VerifyFailure, VerifyValue};
+
+    #[test]
+    fn succeeds_when_the_decoded_bytes_match() {
+        assert_eq!(
+            compare(&VerifyValue("AAH/fg==\n".to_string()), b"\x00\x01\xff\x7e"),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn ignores_whitespace_in_the_expected_value() {
+        assert_eq!(
+            compare(
+                &VerifyValue("AA\nH/\n fg==\n".to_string()),
+                b"\x00\x01\xff\x7e"
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn fails_with_the_offset_of_the_first_differing_byte() {
+        assert_eq!(
+            compare(&VerifyValue("AAH/fg==".to_string()), b"\x00\x01\xfe\x7e"),
+            Ok(Some(VerifyFailure::BytesDoNotMatch { offset: 2 }))
+        );
+    }
+
+    #[test]
+    fn fails_at_the_end_of_the_shorter_value_when_one_is_a_prefix_of_the_other() {
+        assert_eq!(
+            compare(&VerifyValue("AAH/fg==".to_string()), b"\x00\x01"),
+            Ok(Some(VerifyFailure::BytesDoNotMatch { offset: 2 }))
+        );
+        assert_eq!(
+            compare(&VerifyValue("AAE=".to_string()), b"\x00\x01\xff"),
+            Ok(Some(VerifyFailure::BytesDoNotMatch { offset: 2 }))
+        );
+    }
+
+    #[test]
+    fn returns_an_error_when_the_expected_value_is_not_base64() {
+        assert!(matches!(
+            compare(&VerifyValue("not base64!".to_string()), b""),
+            Err(Error::RunFailed { .. })
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn list_files(directory: &Path) -> BTreeSet<PathBuf> {
+    let mut files = BTreeSet::new();
+    collect_files(directory, directory, &mut files);
+    files
+}
+
+fn collect_files(root: &Path, directory: &Path, files: &mut BTreeSet<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_files(root, &path, files);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.insert(relative.to_path_buf());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::list_files;
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn lists_files_relative_to_the_directory() {
+        let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+        fs::create_dir_all(directory.path().join("sub/dir")).expect("Failed to create dirs");
+        fs::write(directory.path().join("top.txt"), "").expect("Failed to write file");
+        fs::write(directory.path().join("sub/dir/nested.txt"), "").expect("Failed to write file");
+
+        assert_eq!(
+            list_files(directory.path()),
+            vec![
+                PathBuf::from("sub/dir/nested.txt"),
+                PathBuf::from("top.txt")
+            ]
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+        );
+    }
+
+    #[test]
+    fn returns_nothing_when_the_directory_does_not_exist() {
+        assert!(list_files(&PathBuf::from("/does/not/exist")).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::results::VerifyFailure;
+use crate::types::Encoding;
+
+pub fn check(encoding: &Encoding, bytes: &[u8]) -> Option<VerifyFailure> {
+    first_invalid_offset(encoding, bytes).map(|offset| VerifyFailure::InvalidEncoding {
+        reason: format!("invalid {} at byte offset {offset}", name(encoding)),
+    })
+}
+
+fn first_invalid_offset(encoding: &Encoding, bytes: &[u8]) -> Option<usize> {
+    match encoding {
+        Encoding::Utf8 => std::str::from_utf8(bytes)
+            .err()
+            .map(|err| err.valid_up_to()),
+        Encoding::Ascii => bytes.iter().position(|byte| !byte.is_ascii()),
+        Encoding::Base64 => None,
+    }
+}
+
+const fn name(encoding: &Encoding) -> &str {
+    match encoding {
+        Encoding::Utf8 => "UTF-8",
+        Encoding::Ascii => "ASCII",
+        Encoding::Base64 => "base64",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Encoding, VerifyFailure};
+
+    #[test]
+    fn succeeds_for_valid_utf8() {
+        assert_eq!(
+            check(&Encoding::Utf8, "h\u{e9}llo \u{2550}".as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_with_the_offset_of_the_first_invalid_utf8_sequence() {
+        assert_eq!(
+            check(&Encoding::Utf8, b"ok\xff\xfe"),
+            Some(VerifyFailure::InvalidEncoding {
+                reason: "invalid UTF-8 at byte offset 2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn succeeds_for_valid_ascii() {
+        assert_eq!(check(&Encoding::Ascii, b"hello"), None);
+    }
+
+    #[test]
+    fn fails_with_the_offset_of_the_first_non_ascii_byte() {
+        assert_eq!(
+            check(&Encoding::Ascii, "abc\u{e9}".as_bytes()),
+            Some(VerifyFailure::InvalidEncoding {
+                reason: "invalid ASCII at byte offset 3".to_string()
+            })
+        );
+    }
+}
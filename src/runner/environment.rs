@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+
+use crate::results::EnvironmentSnapshot;
+use crate::types::ScriptCode;
+
+use super::Error;
+
+pub struct Capture {
+    directory: TempDir,
+}
+
+impl Capture {
+    pub fn new() -> Result<Self, Error> {
+        tempfile::Builder::new()
+            .prefix("specdown-env-")
+            .tempdir()
+            .map(|directory| Self { directory })
+            .map_err(|err| Error::EnvironmentCaptureFailed {
+                message: err.to_string(),
+            })
+    }
+
+    fn before_path(&self) -> PathBuf {
+        self.directory.path().join("before")
+    }
+
+    fn after_path(&self) -> PathBuf {
+        self.directory.path().join("after")
+    }
+
+    // The environment is written by the script's own shell, so this only
+    // works with POSIX shells
+    pub fn wrap(&self, script_code: &ScriptCode) -> ScriptCode {
+        let ScriptCode(code) = script_code;
+        let before = shell_words::quote(&self.before_path().display().to_string()).into_owned();
+        let after = shell_words::quote(&self.after_path().display().to_string()).into_owned();
+
+        ScriptCode(format!(
+            "env > {before}\ntrap 'env > {}' EXIT\n{code}",
+            after.replace('\'', "'\\''")
+        ))
+    }
+
+    pub fn read(&self) -> Result<EnvironmentSnapshot, Error> {
+        let read = |path: PathBuf| {
+            fs::read_to_string(path)
+                .map(|contents| parse(&contents))
+                .map_err(|err| Error::EnvironmentCaptureFailed {
+                    message: err.to_string(),
+                })
+        };
+
+        Ok(EnvironmentSnapshot {
+            before: read(self.before_path())?,
+            after: read(self.after_path())?,
+        })
+    }
+}
+
+fn parse(env_output: &str) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    let mut last_name: Option<String> = None;
+
+    for line in env_output.lines() {
+        match line.split_once('=') {
+            Some((name, value)) if is_variable_name(name) => {
+                variables.insert(name.to_string(), value.to_string());
+                last_name = Some(name.to_string());
+            }
+            // A line without a name continues a value which contains a newline
+            _ => {
+                if let Some(value) = last_name.as_ref().and_then(|name| variables.get_mut(name)) {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+
+    variables
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    #[cfg(not(windows))]
+    use std::path::PathBuf;
+
+    use super::{parse, Capture};
+    #[cfg(not(windows))]
+    use crate::runner::executor::Executor;
+    #[cfg(not(windows))]
+    use crate::runner::shell_executor::ShellExecutor;
+    #[cfg(not(windows))]
+    use crate::runner::ExecutionOptions;
+    #[cfg(not(windows))]
+    use crate::types::ScriptCode;
+
+    #[test]
+    fn parses_the_output_of_env_including_multiline_values() {
+        assert_eq!(
+            parse("HOME=/home/me\nMESSAGE=first\nsecond\nEMPTY=\n"),
+            BTreeMap::from([
+                ("HOME".to_string(), "/home/me".to_string()),
+                ("MESSAGE".to_string(), "first\nsecond".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ])
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn captures_the_environment_before_and_after_the_script() {
+        let capture = Capture::new().expect("the capture to be created");
+        let shell =
+            ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[]).expect("shell to be created");
+
+        let output = shell
+            .execute(
+                &capture.wrap(&ScriptCode("export GREETING=hello; exit 3".to_string())),
+                &ExecutionOptions::default(),
+            )
+            .expect("success");
+        let snapshot = capture.read().expect("the environment to be captured");
+
+        assert_eq!(output.exit_code, Some(3));
+        assert_eq!(snapshot.before.get("GREETING"), None);
+        assert_eq!(snapshot.after.get("GREETING"), Some(&"hello".to_string()));
+    }
+}
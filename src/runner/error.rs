@@ -2,6 +2,15 @@
 pub enum Error {
     RunFailed { message: String },
     CommandFailed { command: String, message: String },
+    TeeFailed { path: String, message: String },
+    ChangedFilesUnavailable { git_ref: String, message: String },
     ScriptOutputMissing { missing_script_name: String },
+    SourceScriptFailed { script_name: String },
     BadShellCommand { command: String, message: String },
+    UnknownVariable { variable_name: String },
+    NormalizerFailed { command: String, message: String },
+    WorkdirFailed { message: String },
+    EnvironmentCaptureFailed { message: String },
+    EnvironmentNotCaptured { script_name: String },
+    RunningDirNotEmpty { entries: Vec<String> },
 }
@@ -1,3 +1,11 @@
+use std::env;
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
 use crate::types::ScriptCode;
 
 use super::Error;
@@ -6,22 +14,263 @@ pub struct Output {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: Option<i32>,
+    pub raw_stdout: Vec<u8>,
+    pub raw_stderr: Vec<u8>,
+    pub combined: String,
+    pub raw_combined: Vec<u8>,
+    pub pid: Option<u32>,
+    pub signal: Option<i32>,
 }
 
-impl From<std::process::Output> for Output {
-    fn from(output: std::process::Output) -> Self {
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let exit_code = output.status.code();
+impl Output {
+    #[cfg(test)]
+    pub fn from_bytes(stdout: Vec<u8>, stderr: Vec<u8>, exit_code: Option<i32>) -> Self {
+        let combined = [&stdout[..], &stderr[..]].concat();
+        Self::from_captured(
+            Captured {
+                stdout,
+                stderr,
+                combined,
+            },
+            exit_code,
+        )
+    }
 
+    pub fn from_process(pid: u32, status: ExitStatus, captured: Captured) -> Self {
         Self {
-            stdout,
-            stderr,
+            pid: Some(pid),
+            signal: termination_signal(status),
+            ..Self::from_captured(captured, status.code())
+        }
+    }
+
+    fn from_captured(captured: Captured, exit_code: Option<i32>) -> Self {
+        Self {
+            stdout: String::from_utf8_lossy(&captured.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&captured.stderr).to_string(),
+            combined: String::from_utf8_lossy(&captured.combined).to_string(),
             exit_code,
+            raw_stdout: captured.stdout,
+            raw_stderr: captured.stderr,
+            raw_combined: captured.combined,
+            pid: None,
+            signal: None,
         }
     }
 }
 
+pub struct Captured {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub combined: Vec<u8>,
+}
+
+#[cfg(unix)]
+fn termination_signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal()
+}
+
+#[cfg(not(unix))]
+const fn termination_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionOptions {
+    pub tee: Option<PathBuf>,
+    pub stdin: Option<String>,
+    pub current_dir: Option<PathBuf>,
+    pub env: Vec<(String, String)>,
+    pub unset_env: Vec<String>,
+    pub shell_command: Option<String>,
+}
+
+impl ExecutionOptions {
+    pub fn set_env_var(&self, name: &str) -> Option<&String> {
+        self.env
+            .iter()
+            .rev()
+            .find(|(env_name, _)| env_name == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn unsets_env_var(&self, name: &str) -> bool {
+        self.unset_env.iter().any(|env_name| env_name == name)
+    }
+}
+
 pub trait Executor {
-    fn execute(&self, script: &ScriptCode) -> Result<Output, Error>;
+    fn execute(&self, script: &ScriptCode, options: &ExecutionOptions) -> Result<Output, Error>;
+
+    // Whether `command` would be found on the PATH that scripts are run with
+    fn has_command(&self, command: &str, _options: &ExecutionOptions) -> bool {
+        env::var_os("PATH").is_some_and(|path| is_on_path(command, &path))
+    }
+
+    // The value of an environment variable as scripts would see it
+    fn env_var(&self, name: &str, options: &ExecutionOptions) -> Option<String> {
+        if let Some(value) = options.set_env_var(name) {
+            return Some(value.clone());
+        }
+
+        if options.unsets_env_var(name) {
+            return None;
+        }
+
+        env::var(name).ok()
+    }
+}
+
+pub fn is_on_path(command: &str, path: &OsStr) -> bool {
+    env::split_paths(path).any(|dir| {
+        is_executable(&dir.join(command))
+            || (cfg!(windows) && is_executable(&dir.join(format!("{command}.exe"))))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// Reads stdout and stderr at the same time so the combined output keeps the order
+// in which the script wrote them. Stdout is also written to `stdout_writer`.
+pub fn capture<W: Write>(child: &mut Child, stdout_writer: W) -> std::io::Result<Captured> {
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let stderr_reader = {
+        let output = Arc::clone(&output);
+        thread::spawn(move || tee(stderr, std::io::sink(), &output))
+    };
+    let mut stdout_pipe = child.stdout.take().expect("stdout is piped");
+    let stdout = tee(&mut stdout_pipe, stdout_writer, &output);
+    if stdout.is_err() {
+        // Keep reading so the script is not left blocked on a full pipe
+        let _ = std::io::copy(&mut stdout_pipe, &mut std::io::sink());
+    }
+    let stderr = stderr_reader.join().expect("Failed to read stderr");
+    let (stdout, stderr) = (stdout?, stderr?);
+
+    let combined = output.lock().expect("output not to be poisoned").clone();
+    Ok(Captured {
+        stdout,
+        stderr,
+        combined,
+    })
+}
+
+pub fn tee<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    output: &Mutex<Vec<u8>>,
+) -> std::io::Result<Vec<u8>> {
+    let mut captured = Vec::new();
+    let mut buffer = [0; 4096];
+
+    loop {
+        let count = reader.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        captured.extend_from_slice(&buffer[..count]);
+        output
+            .lock()
+            .expect("output not to be poisoned")
+            .extend_from_slice(&buffer[..count]);
+        writer.write_all(&buffer[..count])?;
+        writer.flush()?;
+    }
+
+    Ok(captured)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capture, is_on_path, tee, Output};
+    use std::io::{self, Write};
+    use std::sync::Mutex;
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tee_returns_the_input_and_writes_it_to_the_writer() {
+        let mut written = Vec::new();
+        let output = Mutex::new(b"earlier ".to_vec());
+        let captured = tee(&b"hello world"[..], &mut written, &output).expect("tee to succeed");
+        assert_eq!(captured, b"hello world");
+        assert_eq!(written, b"hello world");
+        assert_eq!(
+            output.into_inner().expect("output not to be poisoned"),
+            b"earlier hello world"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn capture_reads_all_output_before_returning_a_writer_error() {
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("yes | head -c 200000; echo done >&2")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("the script to start");
+
+        let result = capture(&mut child, FailingWriter);
+
+        assert_eq!(
+            result.map(|_| ()).map_err(|err| err.to_string()),
+            Err("disk full".to_string())
+        );
+        assert!(child.wait().expect("the script to be reaped").success());
+    }
+
+    #[test]
+    fn output_from_bytes_combines_stdout_and_stderr() {
+        let output = Output::from_bytes(b"out\n".to_vec(), b"err\n".to_vec(), Some(0));
+        assert_eq!(output.combined, "out\nerr\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_on_path_finds_executable_files_in_any_path_directory() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("a temporary directory");
+        let command = dir.path().join("deploy");
+        fs::write(&command, "#!/bin/sh\n").expect("the command to be written");
+        fs::write(dir.path().join("notes"), "").expect("the file to be written");
+        fs::set_permissions(&command, fs::Permissions::from_mode(0o755))
+            .expect("the command to be executable");
+        let path = std::env::join_paths(["/does/not/exist", dir.path().to_str().unwrap()])
+            .expect("a PATH");
+
+        assert!(is_on_path("deploy", &path));
+        assert!(!is_on_path("notes", &path));
+        assert!(!is_on_path("missing", &path));
+    }
 }
@@ -1,26 +1,71 @@
-use std::fs::File;
+use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::Path;
 
 use crate::results::{ActionResult, CreateFileResult};
-use crate::types::{CreateFileAction, FileContent, FilePath};
+use crate::types::{CreateFileAction, CreateFileOptions, FileContent, FileMode, FilePath};
 
 pub fn run(action: &CreateFileAction) -> ActionResult {
     let CreateFileAction {
         file_path: FilePath(path_string),
         file_content: FileContent(content_string),
+        options:
+            CreateFileOptions {
+                final_newline,
+                append,
+                mode,
+            },
     } = action;
 
     // TODO: Nice error handling
-    let mut file = File::create(path_string).expect("Failed to create file");
-    write!(file, "{content_string}").expect("Failed to write to file");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(*append)
+        .truncate(!*append)
+        .open(path_string)
+        .expect("Failed to create file");
+    write!(
+        file,
+        "{}",
+        with_final_newline(content_string, *final_newline)
+    )
+    .expect("Failed to write to file");
+    if let Some(mode) = mode {
+        set_file_mode(Path::new(path_string), *mode).expect("Failed to set the file mode");
+    }
     ActionResult::CreateFile(CreateFileResult {
         action: action.clone(),
     })
 }
 
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: FileMode) -> std::io::Result<()> {
+    use std::fs::{self, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, Permissions::from_mode(mode.0))
+}
+
+// File modes are only supported on Unix so the mode is ignored elsewhere
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: FileMode) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn with_final_newline(content: &str, final_newline: Option<bool>) -> String {
+    match final_newline {
+        Some(true) if !content.ends_with('\n') => format!("{content}\n"),
+        Some(false) => content.strip_suffix('\n').unwrap_or(content).to_string(),
+        _ => content.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{run, ActionResult, FileContent, FilePath};
+    use super::{
+        run, with_final_newline, ActionResult, CreateFileOptions, FileContent, FileMode, FilePath,
+    };
     use crate::results::CreateFileResult;
     use crate::types::CreateFileAction;
     use std::fs;
@@ -35,6 +80,7 @@ mod tests {
         let action = CreateFileAction {
             file_path: FilePath(file_path.to_string()),
             file_content: FileContent("example content".to_string()),
+            options: CreateFileOptions::default(),
         };
 
         run(&action);
@@ -61,6 +107,7 @@ mod tests {
         let action = CreateFileAction {
             file_path: FilePath(file_path.to_string()),
             file_content: FileContent("example content".to_string()),
+            options: CreateFileOptions::default(),
         };
         let result = run(&action);
 
@@ -69,4 +116,76 @@ mod tests {
             ActionResult::CreateFile(CreateFileResult { action })
         );
     }
+
+    #[test]
+    fn with_final_newline_preserves_the_content_by_default() {
+        assert_eq!(with_final_newline("a\n", None), "a\n");
+        assert_eq!(with_final_newline("a", None), "a");
+    }
+
+    #[test]
+    fn with_final_newline_false_strips_a_single_trailing_newline() {
+        assert_eq!(with_final_newline("a\n\n", Some(false)), "a\n");
+        assert_eq!(with_final_newline("a", Some(false)), "a");
+    }
+
+    #[test]
+    fn with_final_newline_true_ensures_there_is_a_trailing_newline() {
+        assert_eq!(with_final_newline("a", Some(true)), "a\n");
+        assert_eq!(with_final_newline("a\n", Some(true)), "a\n");
+    }
+
+    #[test]
+    fn test_run_with_append_adds_to_the_end_of_the_file() {
+        fs::create_dir_all(".tests").expect("Failed to create test directory");
+
+        let file_path = ".tests/test_file3.txt";
+        fs::remove_file(file_path).ok();
+
+        for content in ["first\n", "second\n"] {
+            run(&CreateFileAction {
+                file_path: FilePath(file_path.to_string()),
+                file_content: FileContent(content.to_string()),
+                options: CreateFileOptions {
+                    append: true,
+                    ..CreateFileOptions::default()
+                },
+            });
+        }
+
+        assert_eq!(
+            fs::read_to_string(file_path).expect("File could not be read"),
+            "first\nsecond\n"
+        );
+
+        fs::remove_file(file_path).expect("Failed to delete file");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_a_mode_sets_the_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::create_dir_all(".tests").expect("Failed to create test directory");
+
+        let file_path = ".tests/test_file4.sh";
+        fs::remove_file(file_path).ok();
+
+        run(&CreateFileAction {
+            file_path: FilePath(file_path.to_string()),
+            file_content: FileContent("echo hello\n".to_string()),
+            options: CreateFileOptions {
+                mode: Some(FileMode(0o750)),
+                ..CreateFileOptions::default()
+            },
+        });
+
+        let mode = fs::metadata(file_path)
+            .expect("File could not be read")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o750);
+
+        fs::remove_file(file_path).expect("Failed to delete file");
+    }
 }
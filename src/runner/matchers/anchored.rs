@@ -0,0 +1,108 @@
+use crate::results::VerifyFailure;
+
+pub fn starts_with(expected: &str, got: &str) -> Option<VerifyFailure> {
+    if got.starts_with(expected) {
+        return None;
+    }
+
+    let boundary: String = got.chars().take(expected.chars().count()).collect();
+    Some(VerifyFailure::MatcherFailed {
+        reason: format!(
+            "output does not start with the expected text, it starts with {boundary:?}"
+        ),
+    })
+}
+
+pub fn ends_with(expected: &str, got: &str) -> Option<VerifyFailure> {
+    if got.ends_with(expected) {
+        return None;
+    }
+
+    let got_length = got.chars().count();
+    let boundary: String = got
+        .chars()
+        .skip(got_length.saturating_sub(expected.chars().count()))
+        .collect();
+    Some(VerifyFailure::MatcherFailed {
+        reason: format!("output does not end with the expected text, it ends with {boundary:?}"),
+    })
+}
+
+pub fn contains(expected: &str, got: &str) -> Option<VerifyFailure> {
+    if got.contains(expected) {
+        None
+    } else {
+        Some(VerifyFailure::MatcherFailed {
+            reason: "output does not contain the expected text".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, ends_with, starts_with, VerifyFailure};
+
+    #[test]
+    fn contains_succeeds_when_the_expected_text_is_anywhere_in_the_output() {
+        assert_eq!(
+            contains(
+                "Compiling 42 dependencies\n",
+                "[12:00:01] Starting\nCompiling 42 dependencies\n[12:00:05] Finished\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn contains_fails_when_the_expected_text_is_not_in_the_output() {
+        assert_eq!(
+            contains("Compiling 43 dependencies", "Compiling 42 dependencies\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output does not contain the expected text".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn starts_with_succeeds_when_the_output_begins_with_the_expected_text() {
+        assert_eq!(starts_with("Hello", "Hello world"), None);
+    }
+
+    #[test]
+    fn starts_with_fails_with_the_start_of_the_output_on_a_near_miss() {
+        assert_eq!(
+            starts_with("Hello", "Help world"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output does not start with the expected text, it starts with \"Help \""
+                    .to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn starts_with_fails_when_the_output_is_shorter_than_the_expected_text() {
+        assert_eq!(
+            starts_with("Hello world", "Hello"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output does not start with the expected text, it starts with \"Hello\""
+                    .to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn ends_with_succeeds_when_the_output_finishes_with_the_expected_text() {
+        assert_eq!(ends_with("done\n", "Building...\ndone\n"), None);
+    }
+
+    #[test]
+    fn ends_with_fails_with_the_end_of_the_output_on_a_near_miss() {
+        assert_eq!(
+            ends_with("done\n", "Building...\ndone!\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output does not end with the expected text, it ends with \"one!\\n\""
+                    .to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::results::VerifyFailure;
+
+const MAX_INPUT_LENGTH: usize = 10_000;
+
+pub fn compare(expected: &str, got: &str, max_distance: u32) -> Option<VerifyFailure> {
+    let expected: Vec<char> = expected.chars().collect();
+    let got: Vec<char> = got.chars().collect();
+
+    if expected.len() > MAX_INPUT_LENGTH || got.len() > MAX_INPUT_LENGTH {
+        return Some(VerifyFailure::MatcherFailed {
+            reason: format!(
+                "output is too large for fuzzy matching, the limit is {MAX_INPUT_LENGTH} characters"
+            ),
+        });
+    }
+
+    let distance = edit_distance(&expected, &got);
+
+    if distance <= max_distance as usize {
+        None
+    } else {
+        Some(VerifyFailure::MatcherFailed {
+            reason: format!("edit distance {distance} exceeds the maximum of {max_distance}"),
+        })
+    }
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != b_char);
+            current[j + 1] = (previous[j] + substitution_cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, edit_distance, VerifyFailure, MAX_INPUT_LENGTH};
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn edit_distance_counts_insertions_deletions_and_substitutions() {
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+        assert_eq!(edit_distance(&chars("abc"), &chars("")), 3);
+        assert_eq!(edit_distance(&chars("same"), &chars("same")), 0);
+    }
+
+    #[test]
+    fn returns_none_when_distance_is_at_the_threshold() {
+        assert_eq!(compare("kitten", "sitting", 3), None);
+    }
+
+    #[test]
+    fn returns_failure_with_the_distance_when_beyond_the_threshold() {
+        assert_eq!(
+            compare("kitten", "sitting", 2),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "edit distance 3 exceeds the maximum of 2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn returns_failure_when_the_input_is_too_large() {
+        let large = "a".repeat(MAX_INPUT_LENGTH + 1);
+        assert_eq!(
+            compare(&large, "a", 3),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output is too large for fuzzy matching, the limit is 10000 characters"
+                    .to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::results::VerifyFailure;
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let got_lines: Vec<&str> = got.lines().collect();
+
+    if expected_lines.len() != got_lines.len() {
+        return Some(VerifyFailure::MatcherFailed {
+            reason: format!(
+                "expected {} lines, got {}",
+                expected_lines.len(),
+                got_lines.len()
+            ),
+        });
+    }
+
+    expected_lines
+        .iter()
+        .zip(&got_lines)
+        .position(|(pattern, line)| !matches(pattern, line))
+        .map(|index| VerifyFailure::MatcherFailed {
+            reason: format!(
+                "line {} {:?} does not match the pattern `{}`",
+                index + 1,
+                got_lines[index],
+                expected_lines[index]
+            ),
+        })
+}
+
+// `*` matches any run of characters and `?` matches exactly one
+pub fn matches(pattern: &str, line: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let line: Vec<char> = line.chars().collect();
+
+    let (mut p, mut l) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while l < line.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, l));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == line[l] => {
+                p += 1;
+                l += 1;
+            }
+            _ => match backtrack {
+                Some((star, start)) => {
+                    p = star + 1;
+                    l = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, matches, VerifyFailure};
+
+    #[test]
+    fn a_star_matches_any_run_of_characters() {
+        assert!(matches("built in *s", "built in 1.25s"));
+        assert!(matches("built in *s", "built in s"));
+        assert!(matches("*/bin/*", "/usr/local/bin/specdown"));
+        assert!(!matches("built in *s", "built in 1.25ms!"));
+    }
+
+    #[test]
+    fn a_question_mark_matches_exactly_one_character() {
+        assert!(matches("v1.?.0", "v1.2.0"));
+        assert!(!matches("v1.?.0", "v1.12.0"));
+        assert!(!matches("v1.?.0", "v1..0"));
+    }
+
+    #[test]
+    fn succeeds_when_every_line_matches_its_pattern() {
+        assert_eq!(
+            compare(
+                "specdown v*\nbuilt in *s\n",
+                "specdown v1.2.0\nbuilt in 0.3s\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_with_the_first_line_which_does_not_match() {
+        assert_eq!(
+            compare("specdown v*\nbuilt in *s\n", "specdown v1.2.0\nfailed\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "line 2 \"failed\" does not match the pattern `built in *s`".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_the_line_counts_differ() {
+        assert_eq!(
+            compare("*\n", "one\ntwo\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "expected 1 lines, got 2".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use serde_json::Value;
+
+use crate::results::VerifyFailure;
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    match differences(expected, got) {
+        Ok(differences) => super::describe_differences(differences),
+        Err(reason) => Some(VerifyFailure::MatcherFailed { reason }),
+    }
+}
+
+pub fn differences(expected: &str, got: &str) -> Result<Vec<String>, String> {
+    let expected: Value = serde_json::from_str(expected)
+        .map_err(|err| format!("expected value is not valid JSON: {err}"))?;
+    let got: Value =
+        serde_json::from_str(got).map_err(|err| format!("output is not valid JSON: {err}"))?;
+
+    let mut differences = vec![];
+    collect_differences("", &expected, &got, &mut differences);
+    Ok(differences)
+}
+
+fn collect_differences(path: &str, expected: &Value, got: &Value, differences: &mut Vec<String>) {
+    match (expected, got) {
+        (Value::Object(expected), Value::Object(got)) => {
+            for (key, expected_value) in expected {
+                let key_path = format!("{path}.{key}");
+                match got.get(key) {
+                    Some(got_value) => {
+                        collect_differences(&key_path, expected_value, got_value, differences);
+                    }
+                    None => differences.push(format!("`{}` is missing", display_path(&key_path))),
+                }
+            }
+            for key in got.keys().filter(|key| !expected.contains_key(*key)) {
+                differences.push(format!(
+                    "`{}` is unexpected",
+                    display_path(&format!("{path}.{key}"))
+                ));
+            }
+        }
+        (Value::Array(expected), Value::Array(got)) => {
+            for (index, (expected_value, got_value)) in expected.iter().zip(got).enumerate() {
+                collect_differences(
+                    &format!("{path}[{index}]"),
+                    expected_value,
+                    got_value,
+                    differences,
+                );
+            }
+            for index in got.len()..expected.len() {
+                differences.push(format!(
+                    "`{}` is missing",
+                    display_path(&format!("{path}[{index}]"))
+                ));
+            }
+            for index in expected.len()..got.len() {
+                differences.push(format!(
+                    "`{}` is unexpected",
+                    display_path(&format!("{path}[{index}]"))
+                ));
+            }
+        }
+        _ if scalars_equal(expected, got) => {}
+        _ => differences.push(format!(
+            "values differ at `{}` (expected {}, got {})",
+            display_path(path),
+            describe_value(expected),
+            describe_value(got)
+        )),
+    }
+}
+
+fn scalars_equal(expected: &Value, got: &Value) -> bool {
+    match (expected, got) {
+        (Value::Number(expected), Value::Number(got)) => expected.as_f64() == got.as_f64(),
+        _ => expected == got,
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    let path = path.strip_prefix('.').unwrap_or(path);
+    if path.is_empty() {
+        "."
+    } else {
+        path
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "an object".to_string(),
+        Value::Array(_) => "an array".to_string(),
+        scalar => scalar.to_string(),
+    }
+}
+
+pub fn check_valid(got: &str) -> Option<VerifyFailure> {
+    serde_json::from_str::<serde_json::Value>(got)
+        .err()
+        .map(|err| VerifyFailure::MatcherFailed {
+            reason: format!("output is not valid JSON: {err}"),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_valid, compare, differences, VerifyFailure};
+
+    #[test]
+    fn succeeds_when_only_the_key_order_and_whitespace_differ() {
+        assert_eq!(
+            compare(
+                "{\"name\": \"specdown\", \"limits\": {\"cpu\": 1, \"memory\": 512}}",
+                "{\n  \"limits\": {\"memory\": 512, \"cpu\": 1.0},\n  \"name\": \"specdown\"\n}\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_with_the_number_of_values_which_differ() {
+        assert_eq!(
+            compare("{\"a\": 1, \"b\": 2}", "{\"a\": 2, \"b\": 3}"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "2 values differ".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_with_the_difference_when_only_one_value_differs() {
+        assert_eq!(
+            compare("{\"a\": 1, \"b\": 2}", "{\"a\": 1, \"b\": 3}"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "values differ at `b` (expected 2, got 3)".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn lists_every_structural_difference() {
+        assert_eq!(
+            differences(
+                "{\"name\": \"specdown\", \"tags\": [\"cli\"], \"spec\": {\"replicas\": 3}}",
+                "{\"tags\": [\"cli\", \"docs\"], \"spec\": {\"replicas\": \"3\"}, \"extra\": null}"
+            ),
+            Ok(vec![
+                "`name` is missing".to_string(),
+                "values differ at `spec.replicas` (expected 3, got \"3\")".to_string(),
+                "`tags[1]` is unexpected".to_string(),
+                "`extra` is unexpected".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn fails_when_the_expected_value_is_not_valid_json() {
+        assert!(matches!(
+            compare("{", "{}"),
+            Some(VerifyFailure::MatcherFailed { reason }) if reason.starts_with("expected value is not valid JSON: ")
+        ));
+    }
+
+    #[test]
+    fn succeeds_when_the_output_is_a_json_object() {
+        assert_eq!(
+            check_valid("{\"name\": \"specdown\", \"tags\": [1, 2]}\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn succeeds_when_the_output_is_a_json_scalar() {
+        assert_eq!(check_valid("42\n"), None);
+    }
+
+    #[test]
+    fn fails_with_the_error_location_when_the_output_is_malformed() {
+        assert_eq!(
+            check_valid("{\n  \"name\": \"specdown\",\n}\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output is not valid JSON: trailing comma at line 3 column 1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_is_empty() {
+        assert_eq!(
+            check_valid(""),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "output is not valid JSON: EOF while parsing a value at line 1 column 0"
+                    .to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::results::VerifyFailure;
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    let expected_count = expected.lines().count();
+    let got_count = got.lines().count();
+
+    if expected_count == got_count {
+        None
+    } else {
+        Some(VerifyFailure::MatcherFailed {
+            reason: format!("expected {expected_count} lines, got {got_count}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, VerifyFailure};
+
+    #[test]
+    fn succeeds_when_the_line_counts_match_with_different_content() {
+        assert_eq!(compare("a\nb\nc\n", "row 1\nrow 2\nrow 3\n"), None);
+    }
+
+    #[test]
+    fn fails_with_both_counts_when_the_line_counts_differ() {
+        assert_eq!(
+            compare("a\nb\nc\n", "row 1\nrow 2\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "expected 3 lines, got 2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn succeeds_when_both_are_empty() {
+        assert_eq!(compare("", ""), None);
+    }
+}
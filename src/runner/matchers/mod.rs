@@ -0,0 +1,74 @@
+use crate::results::VerifyFailure;
+use crate::types::Matcher;
+
+mod anchored;
+mod fuzzy;
+pub mod glob;
+pub mod json;
+mod line_count;
+mod number;
+mod numeric;
+mod pattern;
+mod semver;
+pub mod wildcard;
+pub mod yaml;
+
+pub fn compare(matcher: &Matcher, expected: &str, got: &str) -> Option<VerifyFailure> {
+    match matcher {
+        Matcher::Exact => exact(expected, got),
+        Matcher::Fuzzy { max_distance } => fuzzy::compare(expected, got, *max_distance),
+        Matcher::StartsWith => anchored::starts_with(expected, got),
+        Matcher::EndsWith => anchored::ends_with(expected, got),
+        Matcher::Contains => anchored::contains(expected, got),
+        Matcher::Number { comparison, value } => number::compare(*comparison, *value, got),
+        Matcher::Semver { comparison, value } => semver::compare(*comparison, value, got),
+        Matcher::LineCount => line_count::compare(expected, got),
+        Matcher::ValidJson => json::check_valid(got),
+        Matcher::Json => json::compare(expected, got),
+        Matcher::Regex => pattern::compare(expected, got),
+        Matcher::Yaml => yaml::compare(expected, got),
+        Matcher::Wildcard => wildcard::compare(expected, got),
+        Matcher::Glob => glob::compare(expected, got),
+        Matcher::Numeric { tolerance } => numeric::compare(expected, got, *tolerance),
+    }
+}
+
+// A single structural difference is reported in full, otherwise only the count is
+fn describe_differences(mut differences: Vec<String>) -> Option<VerifyFailure> {
+    let reason = match differences.len() {
+        0 => return None,
+        1 => differences.remove(0),
+        count => format!("{count} values differ"),
+    };
+    Some(VerifyFailure::MatcherFailed { reason })
+}
+
+fn exact(expected: &str, got: &str) -> Option<VerifyFailure> {
+    if expected == got {
+        None
+    } else {
+        Some(VerifyFailure::OutputDoesNotMatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Matcher, VerifyFailure};
+
+    mod exact {
+        use super::{compare, Matcher, VerifyFailure};
+
+        #[test]
+        fn returns_none_when_the_output_is_the_same() {
+            assert_eq!(compare(&Matcher::Exact, "output", "output"), None);
+        }
+
+        #[test]
+        fn returns_output_does_not_match_when_the_output_is_different() {
+            assert_eq!(
+                compare(&Matcher::Exact, "output", "different"),
+                Some(VerifyFailure::OutputDoesNotMatch)
+            );
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::cmp::Ordering;
+
+use crate::results::VerifyFailure;
+use crate::types::Comparison;
+
+pub fn compare(comparison: Comparison, value: i32, got: &str) -> Option<VerifyFailure> {
+    let got = got.trim();
+    let Some(number) = got.parse::<f64>().ok().filter(|number| number.is_finite()) else {
+        return Some(VerifyFailure::MatcherFailed {
+            reason: format!("output {got:?} is not a number"),
+        });
+    };
+
+    let ordering = number.partial_cmp(&f64::from(value));
+    let passes = match comparison {
+        Comparison::GreaterThan => ordering == Some(Ordering::Greater),
+        Comparison::GreaterThanOrEqual => ordering != Some(Ordering::Less),
+        Comparison::LessThan => ordering == Some(Ordering::Less),
+        Comparison::LessThanOrEqual => ordering != Some(Ordering::Greater),
+        Comparison::Equal => ordering == Some(Ordering::Equal),
+        Comparison::NotEqual => ordering != Some(Ordering::Equal),
+    };
+
+    if passes {
+        None
+    } else {
+        Some(VerifyFailure::MatcherFailed {
+            reason: format!("{got} is not {comparison} {value}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Comparison, VerifyFailure};
+
+    fn failed(reason: &str) -> VerifyFailure {
+        VerifyFailure::MatcherFailed {
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn greater_than() {
+        assert_eq!(compare(Comparison::GreaterThan, 100, "101\n"), None);
+        assert_eq!(
+            compare(Comparison::GreaterThan, 100, "100\n"),
+            Some(failed("100 is not > 100"))
+        );
+    }
+
+    #[test]
+    fn greater_than_or_equal() {
+        assert_eq!(compare(Comparison::GreaterThanOrEqual, 100, "100\n"), None);
+        assert_eq!(
+            compare(Comparison::GreaterThanOrEqual, 100, "99.5\n"),
+            Some(failed("99.5 is not >= 100"))
+        );
+    }
+
+    #[test]
+    fn less_than() {
+        assert_eq!(compare(Comparison::LessThan, 100, "-3\n"), None);
+        assert_eq!(
+            compare(Comparison::LessThan, 100, "100\n"),
+            Some(failed("100 is not < 100"))
+        );
+    }
+
+    #[test]
+    fn less_than_or_equal() {
+        assert_eq!(compare(Comparison::LessThanOrEqual, 100, "100\n"), None);
+        assert_eq!(
+            compare(Comparison::LessThanOrEqual, 100, "100.1\n"),
+            Some(failed("100.1 is not <= 100"))
+        );
+    }
+
+    #[test]
+    fn equal() {
+        assert_eq!(compare(Comparison::Equal, 100, "100.0\n"), None);
+        assert_eq!(
+            compare(Comparison::Equal, 100, "101\n"),
+            Some(failed("101 is not == 100"))
+        );
+    }
+
+    #[test]
+    fn not_equal() {
+        assert_eq!(compare(Comparison::NotEqual, 100, "101\n"), None);
+        assert_eq!(
+            compare(Comparison::NotEqual, 100, "100\n"),
+            Some(failed("100 is not != 100"))
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_is_not_a_number() {
+        assert_eq!(
+            compare(Comparison::Equal, 100, "one hundred\n"),
+            Some(failed("output \"one hundred\" is not a number"))
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_is_not_finite() {
+        assert_eq!(
+            compare(Comparison::Equal, 100, "NaN\n"),
+            Some(failed("output \"NaN\" is not a number"))
+        );
+    }
+}
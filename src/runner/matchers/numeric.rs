@@ -0,0 +1,128 @@
+use regex::Regex;
+
+use crate::results::VerifyFailure;
+use crate::types::Tolerance;
+
+// Allows for rounding errors when the difference is exactly the tolerance
+const EPSILON: f64 = 1e-9;
+
+pub fn compare(
+    expected: &str,
+    got: &str,
+    Tolerance(tolerance): Tolerance,
+) -> Option<VerifyFailure> {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let got_lines: Vec<&str> = got.lines().collect();
+
+    if expected_lines.len() != got_lines.len() {
+        return Some(VerifyFailure::MatcherFailed {
+            reason: format!(
+                "expected {} lines, got {}",
+                expected_lines.len(),
+                got_lines.len()
+            ),
+        });
+    }
+
+    let number = Regex::new(r"-?\d+(?:\.\d+)?").expect("a valid number pattern");
+    expected_lines
+        .iter()
+        .zip(&got_lines)
+        .enumerate()
+        .find_map(|(index, (expected, got))| {
+            compare_line(&number, expected, got, tolerance).map(|reason| {
+                VerifyFailure::MatcherFailed {
+                    reason: format!("line {} {reason}", index + 1),
+                }
+            })
+        })
+}
+
+fn compare_line(number: &Regex, expected: &str, got: &str, tolerance: f64) -> Option<String> {
+    let expected_text: Vec<&str> = number.split(expected).collect();
+    let got_text: Vec<&str> = number.split(got).collect();
+    if expected_text != got_text {
+        return Some("differs outside of its numbers".to_string());
+    }
+
+    number
+        .find_iter(expected)
+        .zip(number.find_iter(got))
+        .find_map(|(expected, got)| {
+            let difference = (parse(expected.as_str()) - parse(got.as_str())).abs();
+            if difference - tolerance > EPSILON {
+                Some(format!(
+                    "has {} which is not within {tolerance} of {}",
+                    got.as_str(),
+                    expected.as_str()
+                ))
+            } else {
+                None
+            }
+        })
+}
+
+fn parse(number: &str) -> f64 {
+    number
+        .parse()
+        .expect("a number matched by the number pattern")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Tolerance, VerifyFailure};
+
+    #[test]
+    fn succeeds_when_the_numbers_are_within_the_tolerance() {
+        assert_eq!(
+            compare(
+                "parse: 1.20ms\nrender: 3ms\n",
+                "parse: 1.21ms\nrender: 2ms\n",
+                Tolerance(1.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn succeeds_when_the_difference_is_exactly_the_tolerance() {
+        assert_eq!(
+            compare("took 1.24s\n", "took 1.25s\n", Tolerance(0.01)),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_with_the_first_number_outside_the_tolerance() {
+        assert_eq!(
+            compare(
+                "parse: 1.20ms\nrender: 3.00ms\n",
+                "parse: 1.20ms\nrender: -3.5ms\n",
+                Tolerance(0.5)
+            ),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "line 2 has -3.5 which is not within 0.5 of 3.00".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_the_text_around_the_numbers_differs() {
+        assert_eq!(
+            compare("parse: 1ms\n", "parse: 1s\n", Tolerance(0.5)),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "line 1 differs outside of its numbers".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_the_line_counts_differ() {
+        assert_eq!(
+            compare("1\n", "1\n2\n", Tolerance(0.5)),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "expected 1 lines, got 2".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use regex::RegexBuilder;
+
+use crate::results::VerifyFailure;
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    let pattern = expected.trim_end_matches('\n');
+
+    match RegexBuilder::new(pattern).multi_line(true).build() {
+        Ok(regex) if regex.is_match(got) => None,
+        Ok(_) => Some(VerifyFailure::MatcherFailed {
+            reason: format!("no match for the pattern `{pattern}`"),
+        }),
+        Err(_) => Some(VerifyFailure::MatcherFailed {
+            reason: format!("`{pattern}` is not a valid regular expression"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, VerifyFailure};
+
+    #[test]
+    fn succeeds_when_a_line_matches_the_pattern() {
+        assert_eq!(compare("^listen 80;$\n", "server {\nlisten 80;\n}\n"), None);
+    }
+
+    #[test]
+    fn fails_with_the_pattern_when_nothing_matches() {
+        assert_eq!(
+            compare("^listen 80;$\n", "server {\nlisten 8080;\n}\n"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "no match for the pattern `^listen 80;$`".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn fails_when_the_pattern_is_invalid() {
+        assert_eq!(
+            compare("(unclosed\n", "anything"),
+            Some(VerifyFailure::MatcherFailed {
+                reason: "`(unclosed` is not a valid regular expression".to_string()
+            })
+        );
+    }
+}
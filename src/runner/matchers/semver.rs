@@ -0,0 +1,152 @@
+use crate::parsers::parse_version;
+use crate::results::VerifyFailure;
+use crate::types::{Comparison, Version};
+
+pub fn compare(comparison: Comparison, value: &Version, got: &str) -> Option<VerifyFailure> {
+    let got = got.trim();
+    let version = match parse_version(got) {
+        Ok(version) => version,
+        Err(err) => {
+            return Some(VerifyFailure::MatcherFailed {
+                reason: format!("output {got:?} is not a semantic version because {err}"),
+            })
+        }
+    };
+
+    let passes = match comparison {
+        Comparison::GreaterThan => version > *value,
+        Comparison::GreaterThanOrEqual => version >= *value,
+        Comparison::LessThan => version < *value,
+        Comparison::LessThanOrEqual => version <= *value,
+        Comparison::Equal => version == *value,
+        Comparison::NotEqual => version != *value,
+    };
+
+    if passes {
+        None
+    } else {
+        Some(VerifyFailure::MatcherFailed {
+            reason: format!("{version} is not {comparison} {value}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Comparison, VerifyFailure, Version};
+
+    const VALUE: Version = Version::new(1, 2, 0);
+
+    fn failed(reason: &str) -> VerifyFailure {
+        VerifyFailure::MatcherFailed {
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn greater_than() {
+        assert_eq!(compare(Comparison::GreaterThan, &VALUE, "1.10.0\n"), None);
+        assert_eq!(
+            compare(Comparison::GreaterThan, &VALUE, "1.2.0\n"),
+            Some(failed("1.2.0 is not > 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn greater_than_or_equal() {
+        assert_eq!(
+            compare(Comparison::GreaterThanOrEqual, &VALUE, "1.2.0\n"),
+            None
+        );
+        assert_eq!(
+            compare(Comparison::GreaterThanOrEqual, &VALUE, "1.1.9\n"),
+            Some(failed("1.1.9 is not >= 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn less_than() {
+        assert_eq!(compare(Comparison::LessThan, &VALUE, "0.9.12\n"), None);
+        assert_eq!(
+            compare(Comparison::LessThan, &VALUE, "2.0.0\n"),
+            Some(failed("2.0.0 is not < 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn less_than_or_equal() {
+        assert_eq!(
+            compare(Comparison::LessThanOrEqual, &VALUE, "1.2.0\n"),
+            None
+        );
+        assert_eq!(
+            compare(Comparison::LessThanOrEqual, &VALUE, "1.2.1\n"),
+            Some(failed("1.2.1 is not <= 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn equal() {
+        assert_eq!(compare(Comparison::Equal, &VALUE, "1.2.0\n"), None);
+        assert_eq!(
+            compare(Comparison::Equal, &VALUE, "1.2.1\n"),
+            Some(failed("1.2.1 is not == 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn not_equal() {
+        assert_eq!(compare(Comparison::NotEqual, &VALUE, "1.3.0\n"), None);
+        assert_eq!(
+            compare(Comparison::NotEqual, &VALUE, "1.2.0\n"),
+            Some(failed("1.2.0 is not != 1.2.0"))
+        );
+    }
+
+    #[test]
+    fn a_pre_release_is_less_than_its_release() {
+        assert_eq!(
+            compare(
+                Comparison::LessThan,
+                &VALUE,
+                "1.2.0-rc.1
+"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn accepts_a_leading_v_and_ignores_build_metadata() {
+        assert_eq!(
+            compare(
+                Comparison::Equal,
+                &VALUE,
+                "v1.2.0+build.5
+"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_has_a_leading_zero() {
+        assert_eq!(
+            compare(Comparison::Equal, &VALUE, "01.2.0
+"),
+            Some(failed(
+                "output \"01.2.0\" is not a semantic version because the major version \"01\" is not a number without leading zeros"
+            ))
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_is_not_a_semantic_version() {
+        assert_eq!(
+            compare(Comparison::Equal, &VALUE, "version one\n"),
+            Some(failed(
+                "output \"version one\" is not a semantic version because it does not have the format MAJOR.MINOR.PATCH"
+            ))
+        );
+    }
+}
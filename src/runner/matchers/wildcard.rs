@@ -0,0 +1,191 @@
+use std::ops::Range;
+
+use crate::results::VerifyFailure;
+
+const WILDCARD: &str = "...";
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Consumed {
+    pub line: usize,
+    pub lines: Range<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Expansion {
+    pub expected: String,
+    pub consumed: Vec<Consumed>,
+}
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let got: Vec<&str> = got.lines().collect();
+
+    if align(&expected, &got).is_some() {
+        None
+    } else {
+        Some(VerifyFailure::OutputDoesNotMatch)
+    }
+}
+
+// Replaces each wildcard with the lines of output it consumed so the result can be diffed
+pub fn expand(expected: &str, got: &str) -> Expansion {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let got_lines: Vec<&str> = got.lines().collect();
+    let ranges = align(&expected_lines, &got_lines)
+        .unwrap_or_else(|| align_closest(&expected_lines, &got_lines));
+
+    let mut ranges = ranges.into_iter();
+    let mut lines: Vec<&str> = vec![];
+    let mut consumed = vec![];
+    for (index, line) in expected_lines.iter().copied().enumerate() {
+        if line == WILDCARD {
+            let range = ranges.next().expect("a range for every wildcard");
+            lines.extend_from_slice(&got_lines[range.clone()]);
+            consumed.push(Consumed {
+                line: index + 1,
+                lines: range.start + 1..range.end + 1,
+            });
+        } else {
+            lines.push(line);
+        }
+    }
+
+    let mut expanded = lines.join("\n");
+    if expected.ends_with('\n') {
+        expanded.push('\n');
+    }
+
+    Expansion {
+        expected: expanded,
+        consumed,
+    }
+}
+
+// Returns the range of output lines consumed by each wildcard, preferring the shortest
+fn align(expected: &[&str], got: &[&str]) -> Option<Vec<Range<usize>>> {
+    // matches[i][j] is true when expected[i..] matches got[j..]
+    let mut matches = vec![vec![false; got.len() + 1]; expected.len() + 1];
+    matches[expected.len()][got.len()] = true;
+    for i in (0..expected.len()).rev() {
+        for j in (0..=got.len()).rev() {
+            matches[i][j] = if expected[i] == WILDCARD {
+                matches[i + 1][j] || (j < got.len() && matches[i][j + 1])
+            } else {
+                j < got.len() && expected[i] == got[j] && matches[i + 1][j + 1]
+            };
+        }
+    }
+
+    if !matches[0][0] {
+        return None;
+    }
+
+    let mut ranges = vec![];
+    let mut j = 0;
+    for i in 0..expected.len() {
+        if expected[i] == WILDCARD {
+            let end = (j..=got.len())
+                .find(|&end| matches[i + 1][end])
+                .expect("a matching alignment");
+            ranges.push(j..end);
+            j = end;
+        } else {
+            j += 1;
+        }
+    }
+    Some(ranges)
+}
+
+// When nothing matches, each wildcard consumes up to the next occurrence of the line after it
+fn align_closest(expected: &[&str], got: &[&str]) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut j = 0;
+    for (i, line) in expected.iter().enumerate() {
+        if *line != WILDCARD {
+            j = (j + 1).min(got.len());
+            continue;
+        }
+
+        let end = match expected.get(i + 1) {
+            None => got.len(),
+            Some(&WILDCARD) => j,
+            Some(next) => got[j..]
+                .iter()
+                .position(|line| line == next)
+                .map_or(j, |offset| j + offset),
+        };
+        ranges.push(j..end);
+        j = end;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, expand, Consumed, Expansion, VerifyFailure};
+
+    #[test]
+    fn succeeds_when_a_wildcard_matches_several_lines() {
+        assert_eq!(compare("start\n...\nend\n", "start\none\ntwo\nend\n"), None);
+    }
+
+    #[test]
+    fn succeeds_when_a_wildcard_matches_no_lines() {
+        assert_eq!(compare("start\n...\nend\n", "start\nend\n"), None);
+    }
+
+    #[test]
+    fn succeeds_when_the_wildcard_must_skip_an_earlier_occurrence_of_the_next_line() {
+        assert_eq!(compare("...\na\nb\n", "a\nx\na\nb\n"), None);
+    }
+
+    #[test]
+    fn fails_when_a_line_outside_the_wildcard_differs() {
+        assert_eq!(
+            compare("start\n...\nend\n", "begin\none\nend\n"),
+            Some(VerifyFailure::OutputDoesNotMatch)
+        );
+    }
+
+    #[test]
+    fn fails_when_there_is_extra_output_without_a_wildcard() {
+        assert_eq!(
+            compare("start\n", "start\nmore\n"),
+            Some(VerifyFailure::OutputDoesNotMatch)
+        );
+    }
+
+    #[test]
+    fn expands_each_wildcard_to_the_lines_it_consumed() {
+        assert_eq!(
+            expand("start\n...\nmiddle\n...\n", "start\none\ntwo\nmiddle\n"),
+            Expansion {
+                expected: "start\none\ntwo\nmiddle\n".to_string(),
+                consumed: vec![
+                    Consumed {
+                        line: 2,
+                        lines: 2..4,
+                    },
+                    Consumed {
+                        line: 4,
+                        lines: 5..5,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn expands_up_to_the_next_expected_line_when_the_output_does_not_match() {
+        assert_eq!(
+            expand("start\n...\nend\ndone\n", "start\none\nend\nfinished\n"),
+            Expansion {
+                expected: "start\none\nend\ndone\n".to_string(),
+                consumed: vec![Consumed {
+                    line: 2,
+                    lines: 2..3,
+                }],
+            }
+        );
+    }
+}
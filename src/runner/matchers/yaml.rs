@@ -0,0 +1,210 @@
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::results::VerifyFailure;
+
+pub fn compare(expected: &str, got: &str) -> Option<VerifyFailure> {
+    match differences(expected, got) {
+        Ok(differences) => super::describe_differences(differences),
+        Err(reason) => Some(VerifyFailure::MatcherFailed { reason }),
+    }
+}
+
+pub fn differences(expected: &str, got: &str) -> Result<Vec<String>, String> {
+    let expected = YamlLoader::load_from_str(expected)
+        .map_err(|err| format!("expected value is not valid YAML: {err}"))?;
+    let got =
+        YamlLoader::load_from_str(got).map_err(|err| format!("output is not valid YAML: {err}"))?;
+
+    let (expected, got) = match (&expected[..], &got[..]) {
+        ([expected], [got]) => (expected.clone(), got.clone()),
+        _ => (Yaml::Array(expected), Yaml::Array(got)),
+    };
+
+    let mut differences = vec![];
+    collect_differences("", &expected, &got, &mut differences);
+    Ok(differences)
+}
+
+fn collect_differences(path: &str, expected: &Yaml, got: &Yaml, differences: &mut Vec<String>) {
+    match (expected, got) {
+        (Yaml::Hash(expected), Yaml::Hash(got)) => {
+            for (key, expected_value) in expected {
+                let key_path = format!("{path}.{}", describe_key(key));
+                match got.get(key) {
+                    Some(got_value) => {
+                        collect_differences(&key_path, expected_value, got_value, differences);
+                    }
+                    None => differences.push(format!("`{}` is missing", display_path(&key_path))),
+                }
+            }
+            for key in got.keys().filter(|key| !expected.contains_key(key)) {
+                let key_path = format!("{path}.{}", describe_key(key));
+                differences.push(format!("`{}` is unexpected", display_path(&key_path)));
+            }
+        }
+        (Yaml::Array(expected), Yaml::Array(got)) => {
+            for (index, (expected_value, got_value)) in expected.iter().zip(got).enumerate() {
+                collect_differences(
+                    &format!("{path}[{index}]"),
+                    expected_value,
+                    got_value,
+                    differences,
+                );
+            }
+            for index in got.len()..expected.len() {
+                differences.push(format!(
+                    "`{}` is missing",
+                    display_path(&format!("{path}[{index}]"))
+                ));
+            }
+            for index in expected.len()..got.len() {
+                differences.push(format!(
+                    "`{}` is unexpected",
+                    display_path(&format!("{path}[{index}]"))
+                ));
+            }
+        }
+        _ if scalars_equal(expected, got) => {}
+        _ => differences.push(format!(
+            "values differ at `{}` (expected {}, got {})",
+            display_path(path),
+            describe_value(expected),
+            describe_value(got)
+        )),
+    }
+}
+
+fn scalars_equal(expected: &Yaml, got: &Yaml) -> bool {
+    match (expected, got) {
+        (Yaml::Real(expected), Yaml::Real(got)) => {
+            expected.parse::<f64>().ok() == got.parse::<f64>().ok()
+        }
+        _ => expected == got,
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    let path = path.strip_prefix('.').unwrap_or(path);
+    if path.is_empty() {
+        "."
+    } else {
+        path
+    }
+}
+
+fn describe_key(key: &Yaml) -> String {
+    match key {
+        Yaml::String(key) => key.clone(),
+        other => describe_value(other),
+    }
+}
+
+fn describe_value(value: &Yaml) -> String {
+    match value {
+        Yaml::String(value) => format!("{value:?}"),
+        Yaml::Integer(value) => value.to_string(),
+        Yaml::Real(value) => value.clone(),
+        Yaml::Boolean(value) => value.to_string(),
+        Yaml::Null | Yaml::BadValue => "null".to_string(),
+        Yaml::Hash(_) => "a mapping".to_string(),
+        Yaml::Array(_) => "a sequence".to_string(),
+        Yaml::Alias(_) => "an alias".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, differences, VerifyFailure};
+
+    fn reason(reason: &str) -> VerifyFailure {
+        VerifyFailure::MatcherFailed {
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn succeeds_when_only_the_key_order_and_formatting_differ() {
+        assert_eq!(
+            compare(
+                "name: specdown\ntags: [cli, docs]\nlimits: {cpu: 1.0, memory: 512}\n",
+                "limits:\n  memory: 512\n  cpu: 1.00\ntags:\n  - cli\n  - docs\nname: 'specdown'\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn fails_with_the_path_of_the_first_value_which_differs() {
+        assert_eq!(
+            compare(
+                "spec:\n  replicas: 3\n  image: app:1.0\n",
+                "spec:\n  image: app:1.0\n  replicas: 2\n"
+            ),
+            Some(reason(
+                "values differ at `spec.replicas` (expected 3, got 2)"
+            ))
+        );
+    }
+
+    #[test]
+    fn fails_with_the_path_of_a_differing_sequence_item() {
+        assert_eq!(
+            compare("ports:\n  - 80\n  - 443\n", "ports:\n  - 80\n  - 8443\n"),
+            Some(reason(
+                "values differ at `ports[1]` (expected 443, got 8443)"
+            ))
+        );
+    }
+
+    #[test]
+    fn fails_when_a_key_is_missing_or_unexpected() {
+        assert_eq!(
+            compare("a: 1\nb: 2\n", "a: 1\n"),
+            Some(reason("`b` is missing"))
+        );
+        assert_eq!(
+            compare("a: 1\n", "a: 1\nb: 2\n"),
+            Some(reason("`b` is unexpected"))
+        );
+    }
+
+    #[test]
+    fn fails_with_the_number_of_values_which_differ() {
+        assert_eq!(
+            compare("a: 1\nb: 2\n", "a: 2\nc: 3\n"),
+            Some(reason("3 values differ"))
+        );
+    }
+
+    #[test]
+    fn lists_every_structural_difference() {
+        assert_eq!(
+            differences(
+                "kind: Deployment\nports: [80]\nspec: {replicas: 3}\n",
+                "ports: [80, 443]\nspec: {replicas: 2}\nextra: true\n"
+            ),
+            Ok(vec![
+                "`kind` is missing".to_string(),
+                "`ports[1]` is unexpected".to_string(),
+                "values differ at `spec.replicas` (expected 3, got 2)".to_string(),
+                "`extra` is unexpected".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn fails_when_the_output_is_not_valid_yaml() {
+        assert!(matches!(
+            compare("a: 1\n", "a: [1\n"),
+            Some(VerifyFailure::MatcherFailed { reason }) if reason.starts_with("output is not valid YAML: ")
+        ));
+    }
+
+    #[test]
+    fn fails_when_the_expected_value_is_not_valid_yaml() {
+        assert!(matches!(
+            compare("a: [1\n", "a: 1\n"),
+            Some(VerifyFailure::MatcherFailed { reason }) if reason.starts_with("expected value is not valid YAML: ")
+        ));
+    }
+}
@@ -1,20 +1,37 @@
 pub use error::Error;
-pub use executor::Executor;
+pub use executor::{ExecutionOptions, Executor};
+pub use matchers::{json, wildcard, yaml};
 pub use run_event::RunEvent;
 pub use runnable_action::to_runnable;
 pub use state::State;
 
-use crate::types::Action;
+use crate::types::{
+    Action, CreateFileAction, DirectoryExpectation, NoteAction, ScriptAction, StdinAction, Stream,
+    VerifyAction, VerifyDirectoryAction, VerifyFileAction,
+};
 
+mod binary;
+pub mod directory_listing;
+mod encoding;
+mod environment;
 mod error;
-mod executor;
+pub mod executor;
 mod file;
+mod matchers;
+mod note;
+mod path_mask;
 mod run_event;
 mod runnable_action;
 mod script;
+mod set_env;
 pub mod shell_executor;
+mod skip;
 mod state;
+mod stdin;
+mod template;
+mod unexpected_stderr;
 mod verify;
+mod verify_directory;
 
 pub struct Runner<'a> {
     executor: &'a dyn Executor,
@@ -27,6 +44,14 @@ impl<'a> Runner<'a> {
     }
 
     pub fn run(&mut self, actions: &[Action]) -> Vec<RunEvent> {
+        if self.state.forbids_unexpected_stderr() {
+            return self.run_actions(&unexpected_stderr::forbid_unverified(actions));
+        }
+
+        self.run_actions(actions)
+    }
+
+    fn run_actions(&mut self, actions: &[Action]) -> Vec<RunEvent> {
         actions
             .iter()
             .map(|action| self.run_action(action))
@@ -34,13 +59,89 @@ impl<'a> Runner<'a> {
     }
 
     fn run_action(&mut self, action: &Action) -> RunEvent {
+        log::trace!("running {}", describe_action(action));
+
+        if let Some(message) = unsupported_on_this_platform(action) {
+            return RunEvent::Warning(message);
+        }
+
         to_runnable(action)
             .run(self.state, self.executor)
             .map(|result| {
                 self.state.add_result(&result);
-                RunEvent::TestCompleted(result)
+                if !result.success() && result.failure_allowed() {
+                    RunEvent::FailureAllowed(Box::new(result))
+                } else {
+                    RunEvent::TestCompleted(Box::new(result))
+                }
+            })
+            .or_else::<Error, _>(|error| {
+                if let Action::Script(script_action) = action {
+                    self.state.mark_script_unavailable(script_action);
+                }
+                Ok(RunEvent::ErrorOccurred(error))
             })
-            .or_else::<Error, _>(|error| Ok(RunEvent::ErrorOccurred(error)))
             .unwrap()
     }
 }
+
+fn unsupported_on_this_platform(action: &Action) -> Option<String> {
+    match action {
+        Action::VerifyFile(VerifyFileAction {
+            file_path,
+            mode: Some(_),
+            ..
+        }) if !cfg!(unix) => Some(format!(
+            "Skipped verifying the mode of {}: file modes are only supported on Unix",
+            String::from(file_path.clone())
+        )),
+        _ => None,
+    }
+}
+
+pub fn describe_action(action: &Action) -> String {
+    match action {
+        Action::Script(ScriptAction { script_name, .. }) => format!(
+            "script '{}'",
+            script_name
+                .clone()
+                .map_or("<unnamed>".to_string(), Into::into)
+        ),
+        Action::Verify(VerifyAction { source, .. }) => format!(
+            "verify of {} from '{}'",
+            match source.stream {
+                Stream::StdOut => "stdout",
+                Stream::StdErr => "stderr",
+                Stream::Output => "output",
+            },
+            source
+                .name
+                .clone()
+                .map_or("<last script>".to_string(), Into::into)
+        ),
+        Action::VerifyDirectory(VerifyDirectoryAction {
+            directory,
+            expected: DirectoryExpectation::Golden(golden),
+        }) => format!(
+            "directory verification of {} against {}",
+            String::from(directory.clone()),
+            String::from(golden.clone())
+        ),
+        Action::VerifyDirectory(VerifyDirectoryAction {
+            directory,
+            expected: DirectoryExpectation::Tree(_),
+        }) => format!("tree verification of {}", String::from(directory.clone())),
+        Action::VerifyFile(VerifyFileAction { file_path, .. }) => {
+            format!("file verification of {}", String::from(file_path.clone()))
+        }
+        Action::CreateFile(CreateFileAction { file_path, .. }) => {
+            format!("file creation of {}", String::from(file_path.clone()))
+        }
+        Action::Note(NoteAction { text }) => format!("note {text:?}"),
+        Action::SetEnv(_) => "environment change".to_string(),
+        Action::Stdin(StdinAction { script_name, .. }) => {
+            format!("stdin for '{}'", String::from(script_name.clone()))
+        }
+        Action::Skip(_) => "code block".to_string(),
+    }
+}
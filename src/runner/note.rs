@@ -0,0 +1,25 @@
+use crate::results::{ActionResult, NoteResult};
+use crate::types::NoteAction;
+
+pub fn run(action: &NoteAction) -> ActionResult {
+    ActionResult::Note(NoteResult {
+        action: action.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, ActionResult, NoteAction, NoteResult};
+
+    #[test]
+    fn test_run_records_the_note_without_doing_anything() {
+        let action = NoteAction {
+            text: "Remember to update this example".to_string(),
+        };
+
+        let result = run(&action);
+
+        assert_eq!(result, ActionResult::Note(NoteResult { action }));
+        assert!(result.success());
+    }
+}
@@ -0,0 +1,71 @@
+use std::cmp::Reverse;
+
+pub fn mask(text: &str, path_masks: &[(String, String)]) -> String {
+    let mut masks: Vec<&(String, String)> = path_masks
+        .iter()
+        .filter(|(_, path)| !path.is_empty() && path != "/")
+        .collect();
+    masks.sort_by_key(|(_, path)| Reverse(path.len()));
+
+    masks
+        .iter()
+        .fold(text.to_string(), |masked, (token, path)| {
+            masked.replace(path.as_str(), token)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mask;
+
+    fn masks(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(token, path)| ((*token).to_string(), (*path).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_a_path_with_its_token() {
+        assert_eq!(
+            mask(
+                "Saved to /home/alice/notes.txt",
+                &masks(&[("<HOME>", "/home/alice")])
+            ),
+            "Saved to <HOME>/notes.txt"
+        );
+    }
+
+    #[test]
+    fn replaces_every_occurrence() {
+        assert_eq!(
+            mask(
+                "/home/alice and /home/alice",
+                &masks(&[("<HOME>", "/home/alice")])
+            ),
+            "<HOME> and <HOME>"
+        );
+    }
+
+    #[test]
+    fn prefers_the_longest_matching_path() {
+        assert_eq!(
+            mask(
+                "cwd: /home/alice/project/run",
+                &masks(&[
+                    ("<HOME>", "/home/alice"),
+                    ("<RUNNING_DIR>", "/home/alice/project/run")
+                ])
+            ),
+            "cwd: <RUNNING_DIR>"
+        );
+    }
+
+    #[test]
+    fn ignores_empty_and_root_paths() {
+        assert_eq!(
+            mask("/unchanged", &masks(&[("<HOME>", ""), ("<HOME>", "/")])),
+            "/unchanged"
+        );
+    }
+}
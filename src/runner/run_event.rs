@@ -1,12 +1,18 @@
 use std::path::PathBuf;
 
 use super::Error;
-use crate::results::ActionResult;
+use crate::results::{ActionResult, Coverage};
+use crate::types::SectionPath;
 
 #[derive(Clone)]
 pub enum RunEvent {
     SpecFileStarted(PathBuf),
-    TestCompleted(ActionResult),
+    SectionStarted(SectionPath),
+    TestCompleted(Box<ActionResult>),
+    FailureAllowed(Box<ActionResult>),
     SpecFileCompleted { success: bool },
     ErrorOccurred(Error),
+    Warning(String),
+    Info(String),
+    CoverageCollected(Coverage),
 }
@@ -1,13 +1,25 @@
 use crate::results::ActionResult;
-use crate::types::{Action, CreateFileAction, ScriptAction, VerifyAction};
+use crate::types::{
+    Action, CreateFileAction, NoteAction, ScriptAction, SetEnvAction, SkipAction, StdinAction,
+    VerifyAction, VerifyDirectoryAction, VerifyFileAction,
+};
 
-use super::{error, file, script, verify, Error, Executor, State};
+use super::{
+    error, file, note, script, set_env, skip, stdin, verify, verify_directory, Error,
+    ExecutionOptions, Executor, State,
+};
 
 pub fn to_runnable(action: &Action) -> &dyn RunnableAction {
     match action {
         Action::Script(a) => a,
         Action::Verify(a) => a,
+        Action::VerifyDirectory(a) => a,
+        Action::VerifyFile(a) => a,
         Action::CreateFile(a) => a,
+        Action::Note(a) => a,
+        Action::SetEnv(a) => a,
+        Action::Stdin(a) => a,
+        Action::Skip(a) => a,
     }
 }
 
@@ -16,14 +28,51 @@ pub trait RunnableAction {
 }
 
 impl RunnableAction for ScriptAction {
-    fn run(&self, _state: &State, executor: &dyn Executor) -> Result<ActionResult, Error> {
-        script::run(self, executor)
+    fn run(&self, state: &State, executor: &dyn Executor) -> Result<ActionResult, Error> {
+        let execution_options = ExecutionOptions {
+            stdin: state.stdin(self.script_name.as_ref()).cloned(),
+            ..state.execution_options()
+        };
+        if let Some(reason) = skip::script_reason(&self.options, executor, &execution_options) {
+            return Ok(skip::run(&Action::Script(self.clone()), Some(&reason)));
+        }
+
+        script::run(self, executor, state.bench_iterations(), execution_options)
     }
 }
 
 impl RunnableAction for VerifyAction {
-    fn run(&self, state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
-        verify::run(self, state)
+    fn run(&self, state: &State, executor: &dyn Executor) -> Result<ActionResult, Error> {
+        let execution_options = state.execution_options();
+        if let Some(reason) = skip::verify_reason(&self.options, executor, &execution_options) {
+            return Ok(skip::run(&Action::Verify(self.clone()), Some(&reason)));
+        }
+
+        if state.is_script_skipped(self.source.name.as_ref()) {
+            return Ok(skip::run(
+                &Action::Verify(self.clone()),
+                Some("its script was skipped"),
+            ));
+        }
+
+        verify::run(self, state, state, state, executor)
+    }
+}
+
+impl RunnableAction for VerifyDirectoryAction {
+    fn run(&self, _state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
+        verify_directory::run(self)
+    }
+}
+
+impl RunnableAction for VerifyFileAction {
+    fn run(&self, state: &State, executor: &dyn Executor) -> Result<ActionResult, Error> {
+        let execution_options = state.execution_options();
+        if let Some(reason) = skip::verify_reason(&self.options, executor, &execution_options) {
+            return Ok(skip::run(&Action::VerifyFile(self.clone()), Some(&reason)));
+        }
+
+        verify::run_file(self, state)
     }
 }
 
@@ -32,3 +81,30 @@ impl RunnableAction for CreateFileAction {
         Ok(file::run(self))
     }
 }
+
+impl RunnableAction for NoteAction {
+    fn run(&self, _state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
+        Ok(note::run(self))
+    }
+}
+
+impl RunnableAction for SetEnvAction {
+    fn run(&self, _state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
+        Ok(set_env::run(self))
+    }
+}
+
+impl RunnableAction for StdinAction {
+    fn run(&self, _state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
+        Ok(stdin::run(self))
+    }
+}
+
+impl RunnableAction for SkipAction {
+    fn run(&self, _state: &State, _executor: &dyn Executor) -> Result<ActionResult, Error> {
+        Ok(skip::run(
+            &Action::Skip(self.clone()),
+            self.reason.as_deref(),
+        ))
+    }
+}
@@ -1,24 +1,544 @@
-use crate::results::{ActionResult, ScriptResult};
-use crate::types::{ExitCode, ScriptAction};
+use std::path::PathBuf;
+use std::time::Instant;
 
+use crate::results::{ActionResult, IdempotencyFailure, RawOutput, ScriptResult, StabilityFailure};
+use crate::types::{ExitCode, ScriptAction, ScriptCode, ScriptWorkdir};
+
+use super::environment::Capture;
 use super::error::Error;
-use super::executor::{Executor, Output};
-
-pub fn run(action: &ScriptAction, executor: &dyn Executor) -> Result<ActionResult, Error> {
-    let ScriptAction { script_code, .. } = action;
-
-    executor.execute(script_code).map(
-        |Output {
-             stdout,
-             stderr,
-             exit_code,
-         }| {
-            ActionResult::Script(ScriptResult {
-                action: action.clone(),
-                exit_code: exit_code.map(ExitCode),
-                stdout,
-                stderr,
-            })
+use super::executor::{ExecutionOptions, Executor, Output};
+
+pub fn run(
+    action: &ScriptAction,
+    executor: &dyn Executor,
+    iterations: u32,
+    base_options: ExecutionOptions,
+) -> Result<ActionResult, Error> {
+    let ScriptAction {
+        script_code,
+        options,
+        ..
+    } = action;
+
+    let workdir = match options.workdir {
+        Some(ScriptWorkdir::Temporary) => Some(create_temporary_workdir()?),
+        None => None,
+    };
+
+    let capture = if options.capture_env {
+        Some(Capture::new()?)
+    } else {
+        None
+    };
+    let script_code = &capture
+        .as_ref()
+        .map_or_else(|| script_code.clone(), |capture| capture.wrap(script_code));
+
+    let execution_options = ExecutionOptions {
+        tee: options.tee.clone().map(|path| String::from(path).into()),
+        current_dir: workdir,
+        ..base_options
+    };
+
+    let attempts = options.retries + 1;
+    let mut attempt = 1;
+    loop {
+        let result = ActionResult::Script(ScriptResult {
+            attempts: attempt,
+            ..run_attempt(
+                action,
+                executor,
+                script_code,
+                &execution_options,
+                iterations,
+                capture.as_ref(),
+            )?
+        });
+        if result.success() || attempt == attempts {
+            return Ok(result);
+        }
+
+        log::trace!(
+            "script '{}' failed on attempt {attempt} of {attempts}, retrying",
+            script_name(action)
+        );
+        attempt += 1;
+    }
+}
+
+fn run_attempt(
+    action: &ScriptAction,
+    executor: &dyn Executor,
+    script_code: &ScriptCode,
+    execution_options: &ExecutionOptions,
+    iterations: u32,
+    capture: Option<&Capture>,
+) -> Result<ScriptResult, Error> {
+    let options = &action.options;
+
+    let first_stdout = if options.idempotent {
+        Some(executor.execute(script_code, execution_options)?.stdout)
+    } else {
+        None
+    };
+
+    let earlier_stdouts = (1..options.stable_runs.unwrap_or(1))
+        .map(|_| {
+            executor
+                .execute(script_code, execution_options)
+                .map(|output| output.stdout)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut durations = vec![];
+    let mut output = None;
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        output = Some(executor.execute(script_code, execution_options)?);
+        durations.push(start.elapsed());
+    }
+
+    let Output {
+        stdout,
+        stderr,
+        exit_code,
+        raw_stdout,
+        raw_stderr,
+        combined,
+        raw_combined,
+        pid,
+        signal,
+    } = output.expect("the script to have run at least once");
+
+    let script_name = script_name(action);
+    if let Some(signal) = signal {
+        log::trace!(
+            "script '{}' (pid {}) was killed by signal {}",
+            script_name,
+            pid.map_or("<none>".to_string(), |pid| pid.to_string()),
+            signal
+        );
+    } else {
+        log::trace!(
+            "script '{}' exited with code {}",
+            script_name,
+            exit_code.map_or("<none>".to_string(), |code| code.to_string())
+        );
+    }
+
+    let environment = capture.map(Capture::read).transpose()?.map(Box::new);
+
+    let idempotency_failure = first_stdout.and_then(|first_stdout| {
+        check_idempotency(first_stdout, &stdout, options.changed_marker.as_deref())
+    });
+    let stability_failure = check_stability(earlier_stdouts, &stdout);
+
+    Ok(ScriptResult {
+        action: action.clone(),
+        exit_code: exit_code.map(ExitCode),
+        stdout,
+        stderr,
+        output: combined,
+        raw_output: RawOutput {
+            stdout: raw_stdout,
+            stderr: raw_stderr,
+            output: raw_combined,
         },
-    )
+        durations,
+        idempotency_failure,
+        stability_failure,
+        workdir: execution_options.current_dir.clone(),
+        pid,
+        signal,
+        environment,
+        attempts: 1,
+    })
+}
+
+fn script_name(action: &ScriptAction) -> String {
+    action
+        .script_name
+        .clone()
+        .map_or("<unnamed>".to_string(), Into::into)
+}
+
+fn create_temporary_workdir() -> Result<PathBuf, Error> {
+    tempfile::Builder::new()
+        .prefix("specdown-workdir-")
+        .tempdir()
+        .map(tempfile::TempDir::keep)
+        .map_err(|err| Error::WorkdirFailed {
+            message: err.to_string(),
+        })
+}
+
+fn check_idempotency(
+    first_stdout: String,
+    last_stdout: &str,
+    changed_marker: Option<&str>,
+) -> Option<IdempotencyFailure> {
+    match changed_marker {
+        Some(marker) if last_stdout.contains(marker) => {
+            Some(IdempotencyFailure::ChangedMarkerPrinted {
+                marker: marker.to_string(),
+            })
+        }
+        None if first_stdout != last_stdout => {
+            Some(IdempotencyFailure::OutputChanged { first_stdout })
+        }
+        Some(_) | None => None,
+    }
+}
+
+fn check_stability(earlier_stdouts: Vec<String>, last_stdout: &str) -> Option<StabilityFailure> {
+    let mut stdouts = earlier_stdouts.into_iter();
+    let first_stdout = stdouts.next()?;
+
+    stdouts
+        .chain(std::iter::once(last_stdout.to_string()))
+        .zip(2..)
+        .find(|(stdout, _)| *stdout != first_stdout)
+        .map(|(stdout, run)| StabilityFailure {
+            run,
+            first_stdout,
+            stdout,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use crate::results::{ActionResult, IdempotencyFailure, StabilityFailure};
+    use crate::runner::executor::Output;
+    use crate::runner::{Error, ExecutionOptions, Executor};
+    use crate::types::{
+        ExitCode, ExpectedExitCode, OutputExpectation, ScriptAction, ScriptCode, ScriptOptions,
+        ScriptWorkdir,
+    };
+    use std::cell::{Cell, RefCell};
+    use std::path::PathBuf;
+
+    struct CountingExecutor {
+        runs: Cell<u32>,
+    }
+
+    impl Executor for CountingExecutor {
+        fn execute(&self, _: &ScriptCode, _: &ExecutionOptions) -> Result<Output, Error> {
+            self.runs.set(self.runs.get() + 1);
+            Ok(Output::from_bytes(
+                format!("run {}\n", self.runs.get()).into_bytes(),
+                vec![],
+                Some(0),
+            ))
+        }
+    }
+
+    struct FixedExecutor;
+
+    impl Executor for FixedExecutor {
+        fn execute(&self, _: &ScriptCode, _: &ExecutionOptions) -> Result<Output, Error> {
+            Ok(Output::from_bytes(
+                b"changed: nothing\n".to_vec(),
+                vec![],
+                Some(0),
+            ))
+        }
+    }
+
+    struct FlakyExecutor {
+        succeeds_on_run: u32,
+        runs: Cell<u32>,
+    }
+
+    impl Executor for FlakyExecutor {
+        fn execute(&self, _: &ScriptCode, _: &ExecutionOptions) -> Result<Output, Error> {
+            self.runs.set(self.runs.get() + 1);
+            let exit_code = i32::from(self.runs.get() < self.succeeds_on_run);
+            Ok(Output::from_bytes(vec![], vec![], Some(exit_code)))
+        }
+    }
+
+    struct DirectoryRecordingExecutor {
+        current_dirs: RefCell<Vec<Option<PathBuf>>>,
+    }
+
+    impl Executor for DirectoryRecordingExecutor {
+        fn execute(&self, _: &ScriptCode, options: &ExecutionOptions) -> Result<Output, Error> {
+            self.current_dirs
+                .borrow_mut()
+                .push(options.current_dir.clone());
+            Ok(Output::from_bytes(vec![], vec![], Some(0)))
+        }
+    }
+
+    fn action() -> ScriptAction {
+        ScriptAction {
+            script_name: None,
+            script_code: ScriptCode("echo".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        }
+    }
+
+    #[test]
+    fn runs_the_script_once_for_each_iteration_and_keeps_the_last_output() {
+        let executor = CountingExecutor { runs: Cell::new(0) };
+
+        let Ok(ActionResult::Script(result)) =
+            run(&action(), &executor, 3, ExecutionOptions::default())
+        else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(executor.runs.get(), 3);
+        assert_eq!(result.durations.len(), 3);
+        assert_eq!(result.stdout, "run 3\n");
+    }
+
+    #[test]
+    fn runs_the_script_once_when_iterations_is_zero() {
+        let executor = CountingExecutor { runs: Cell::new(0) };
+
+        run(&action(), &executor, 0, ExecutionOptions::default()).unwrap();
+
+        assert_eq!(executor.runs.get(), 1);
+    }
+
+    fn retried_action(retries: u32) -> ScriptAction {
+        ScriptAction {
+            expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+            options: ScriptOptions {
+                retries,
+                ..ScriptOptions::default()
+            },
+            ..action()
+        }
+    }
+
+    #[test]
+    fn retries_a_failing_script_until_it_succeeds() {
+        let executor = FlakyExecutor {
+            succeeds_on_run: 2,
+            runs: Cell::new(0),
+        };
+
+        let result = run(
+            &retried_action(2),
+            &executor,
+            1,
+            ExecutionOptions::default(),
+        )
+        .unwrap();
+
+        assert!(result.success());
+        let ActionResult::Script(result) = result else {
+            panic!("Expected a script result");
+        };
+        assert_eq!(executor.runs.get(), 2);
+        assert_eq!(result.attempts, 2);
+    }
+
+    #[test]
+    fn stops_retrying_after_the_last_attempt() {
+        let executor = FlakyExecutor {
+            succeeds_on_run: 10,
+            runs: Cell::new(0),
+        };
+
+        let result = run(
+            &retried_action(2),
+            &executor,
+            1,
+            ExecutionOptions::default(),
+        )
+        .unwrap();
+
+        assert!(!result.success());
+        let ActionResult::Script(result) = result else {
+            panic!("Expected a script result");
+        };
+        assert_eq!(executor.runs.get(), 3);
+        assert_eq!(result.attempts, 3);
+        assert_eq!(result.exit_code, Some(ExitCode(1)));
+    }
+
+    fn idempotent_action(changed_marker: Option<&str>) -> ScriptAction {
+        ScriptAction {
+            options: ScriptOptions {
+                idempotent: true,
+                changed_marker: changed_marker.map(ToString::to_string),
+                ..ScriptOptions::default()
+            },
+            ..action()
+        }
+    }
+
+    #[test]
+    fn an_idempotent_script_which_prints_the_same_output_twice_succeeds() {
+        let Ok(ActionResult::Script(result)) = run(
+            &idempotent_action(None),
+            &FixedExecutor,
+            1,
+            ExecutionOptions::default(),
+        ) else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(result.idempotency_failure, None);
+    }
+
+    #[test]
+    fn an_idempotent_script_whose_output_changes_on_the_second_run_fails() {
+        let executor = CountingExecutor { runs: Cell::new(0) };
+
+        let Ok(ActionResult::Script(result)) = run(
+            &idempotent_action(None),
+            &executor,
+            1,
+            ExecutionOptions::default(),
+        ) else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(executor.runs.get(), 2);
+        assert_eq!(result.stdout, "run 2\n");
+        assert_eq!(
+            result.idempotency_failure,
+            Some(IdempotencyFailure::OutputChanged {
+                first_stdout: "run 1\n".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn an_idempotent_script_with_a_changed_marker_ignores_other_differences() {
+        let executor = CountingExecutor { runs: Cell::new(0) };
+
+        let Ok(ActionResult::Script(result)) = run(
+            &idempotent_action(Some("changed:")),
+            &executor,
+            1,
+            ExecutionOptions::default(),
+        ) else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(result.idempotency_failure, None);
+    }
+
+    #[test]
+    fn an_idempotent_script_fails_when_the_second_run_prints_the_changed_marker() {
+        let Ok(ActionResult::Script(result)) = run(
+            &idempotent_action(Some("changed:")),
+            &FixedExecutor,
+            1,
+            ExecutionOptions::default(),
+        ) else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(
+            result.idempotency_failure,
+            Some(IdempotencyFailure::ChangedMarkerPrinted {
+                marker: "changed:".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn runs_in_the_current_directory_when_no_workdir_is_requested() {
+        let executor = DirectoryRecordingExecutor {
+            current_dirs: RefCell::new(vec![]),
+        };
+
+        let Ok(ActionResult::Script(result)) =
+            run(&action(), &executor, 1, ExecutionOptions::default())
+        else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(result.workdir, None);
+        assert_eq!(*executor.current_dirs.borrow(), vec![None]);
+    }
+
+    fn stable_action(runs: u32) -> ScriptAction {
+        ScriptAction {
+            options: ScriptOptions {
+                stable_runs: Some(runs),
+                ..ScriptOptions::default()
+            },
+            ..action()
+        }
+    }
+
+    #[test]
+    fn a_script_which_prints_the_same_output_on_every_stable_run_succeeds() {
+        let Ok(ActionResult::Script(result)) = run(
+            &stable_action(5),
+            &FixedExecutor,
+            1,
+            ExecutionOptions::default(),
+        ) else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(result.stdout, "changed: nothing\n");
+        assert_eq!(result.stability_failure, None);
+    }
+
+    #[test]
+    fn a_script_whose_output_changes_between_stable_runs_fails_with_the_first_differing_run() {
+        let executor = CountingExecutor { runs: Cell::new(0) };
+
+        let Ok(ActionResult::Script(result)) =
+            run(&stable_action(5), &executor, 1, ExecutionOptions::default())
+        else {
+            panic!("Expected a script result");
+        };
+
+        assert_eq!(executor.runs.get(), 5);
+        assert_eq!(result.stdout, "run 5\n");
+        assert_eq!(
+            result.stability_failure,
+            Some(StabilityFailure {
+                run: 2,
+                first_stdout: "run 1\n".to_string(),
+                stdout: "run 2\n".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn each_script_with_a_temporary_workdir_runs_in_a_new_directory() {
+        let executor = DirectoryRecordingExecutor {
+            current_dirs: RefCell::new(vec![]),
+        };
+        let action = ScriptAction {
+            options: ScriptOptions {
+                workdir: Some(ScriptWorkdir::Temporary),
+                ..ScriptOptions::default()
+            },
+            ..action()
+        };
+
+        let workdirs: Vec<PathBuf> = (0..2)
+            .map(
+                |_| match run(&action, &executor, 1, ExecutionOptions::default()) {
+                    Ok(ActionResult::Script(result)) => result.workdir.expect("a workdir"),
+                    _ => panic!("Expected a script result"),
+                },
+            )
+            .collect();
+
+        assert_ne!(workdirs[0], workdirs[1]);
+        assert!(workdirs.iter().all(|workdir| workdir.is_dir()));
+        assert_eq!(
+            *executor.current_dirs.borrow(),
+            vec![Some(workdirs[0].clone()), Some(workdirs[1].clone())]
+        );
+        for workdir in workdirs {
+            std::fs::remove_dir_all(workdir).expect("the workdir to be removed");
+        }
+    }
 }
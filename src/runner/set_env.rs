@@ -0,0 +1,26 @@
+use crate::results::{ActionResult, SetEnvResult};
+use crate::types::SetEnvAction;
+
+pub fn run(action: &SetEnvAction) -> ActionResult {
+    ActionResult::SetEnv(SetEnvResult {
+        action: action.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, ActionResult, SetEnvAction, SetEnvResult};
+
+    #[test]
+    fn test_run_records_the_variables_to_set() {
+        let action = SetEnvAction {
+            variables: vec![("GREETING".to_string(), "hello".to_string())],
+            ..SetEnvAction::default()
+        };
+
+        let result = run(&action);
+
+        assert_eq!(result, ActionResult::SetEnv(SetEnvResult { action }));
+        assert!(result.success());
+    }
+}
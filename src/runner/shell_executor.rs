@@ -5,12 +5,23 @@ use shell_words::ParseError;
 
 use crate::types::ScriptCode;
 
-use super::executor::Output;
+use super::executor::{self, ExecutionOptions, Output};
 use super::{Error, Executor};
 use std::env;
 use std::env::JoinPathsError;
 use std::ffi::{OsStr, OsString};
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EnvInheritance {
+    All,
+    PathOnly,
+    Nothing,
+}
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct ShellExecutor {
@@ -19,6 +30,7 @@ pub struct ShellExecutor {
     env: HashMap<String, String>,
     unset_env: Vec<String>,
     paths: Vec<PathBuf>,
+    env_inheritance: EnvInheritance,
 }
 
 impl ShellExecutor {
@@ -53,9 +65,15 @@ impl ShellExecutor {
             env: env.iter().cloned().collect(),
             unset_env: unset_env.to_vec(),
             paths: paths.iter().map(PathBuf::from).collect(),
+            env_inheritance: EnvInheritance::All,
         }
     }
 
+    pub const fn env_inheritance(mut self, env_inheritance: EnvInheritance) -> Self {
+        self.env_inheritance = env_inheritance;
+        self
+    }
+
     fn parse_error_to_error(shell_command: &str, err: ParseError) -> Error {
         Error::BadShellCommand {
             command: shell_command.to_string(),
@@ -77,25 +95,135 @@ impl ShellExecutor {
     fn path_env_var(&self) -> Result<OsString, JoinPathsError> {
         let mut paths: Vec<PathBuf> = self.paths.clone();
 
-        if let Ok(current_path) = env::var("PATH") {
-            let mut s = env::split_paths(&current_path).collect();
-            paths.append(&mut s);
+        if self.env_inheritance != EnvInheritance::Nothing {
+            if let Ok(current_path) = env::var("PATH") {
+                let mut s = env::split_paths(&current_path).collect();
+                paths.append(&mut s);
+            }
         }
 
         env::join_paths(paths)
     }
+
+    // A shell command set by the spec file replaces the one the executor was created with
+    fn shell(&self, options: &ExecutionOptions) -> Result<(String, Vec<String>), Error> {
+        match &options.shell_command {
+            Some(shell_command) => shell_words::split(shell_command)
+                .map_err(|err| Self::parse_error_to_error(shell_command, err))
+                .and_then(|words| Self::check_is_not_empty(shell_command, &words))
+                .map(|words| (words[0].clone(), words[1..].to_vec())),
+            None => Ok((self.command.clone(), self.args.clone())),
+        }
+    }
+
+    fn program(&self, shell: &str) -> PathBuf {
+        if self.env_inheritance != EnvInheritance::Nothing {
+            return PathBuf::from(shell);
+        }
+
+        // The script's PATH no longer contains the shell, so find it using ours
+        env::var_os("PATH")
+            .and_then(|path| {
+                env::split_paths(&path)
+                    .map(|dir| dir.join(shell))
+                    .find(|candidate| candidate.is_file())
+            })
+            .unwrap_or_else(|| PathBuf::from(shell))
+    }
+
+    fn command_failed(&self, err: &std::io::Error) -> Error {
+        Error::CommandFailed {
+            command: format!("{} {:?}", self.command, self.args),
+            message: err.to_string(),
+        }
+    }
+
+    fn tee_failed(tee_path: &Path, err: &std::io::Error) -> Error {
+        Error::TeeFailed {
+            path: tee_path.display().to_string(),
+            message: err.to_string(),
+        }
+    }
+
+    fn create_tee_file(tee_path: &Path) -> Result<File, Error> {
+        tee_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| File::create(tee_path))
+            .map_err(|err| Self::tee_failed(tee_path, &err))
+    }
+
+    // Stdin is the given input or else empty, and stdout is also written to the tee
+    // file when there is one
+    fn spawn_and_capture(
+        &self,
+        mut command: Command,
+        stdin: Option<&str>,
+        tee_path: Option<&Path>,
+    ) -> Result<Output, Error> {
+        let tee = tee_path
+            .map(|tee_path| Self::create_tee_file(tee_path).map(|file| (file, tee_path)))
+            .transpose()?;
+
+        let mut child = command
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| self.command_failed(&err))?;
+
+        let pid = child.id();
+        let writer = stdin.map(|input| {
+            let mut child_stdin = child.stdin.take().expect("stdin is piped");
+            let input = input.to_string();
+            thread::spawn(move || child_stdin.write_all(input.as_bytes()))
+        });
+
+        // The child is waited for and the stdin writer joined before any error is
+        // returned, so neither is left behind
+        let captured = match tee {
+            Some((tee_file, tee_path)) => executor::capture(&mut child, tee_file)
+                .map_err(|err| Self::tee_failed(tee_path, &err)),
+            None => {
+                executor::capture(&mut child, io::sink()).map_err(|err| self.command_failed(&err))
+            }
+        };
+        let status = child.wait().map_err(|err| self.command_failed(&err));
+        let written = writer.map_or(Ok(()), |writer| {
+            match writer.join().expect("Failed to write stdin") {
+                // The script exited without reading all of its input
+                Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+                result => result.map_err(|err| self.command_failed(&err)),
+            }
+        });
+
+        let (captured, status) = (captured?, status?);
+        written?;
+        Ok(Output::from_process(pid, status, captured))
+    }
 }
 
 impl Executor for ShellExecutor {
-    fn execute(&self, script: &ScriptCode) -> Result<Output, Error> {
+    fn execute(&self, script: &ScriptCode, options: &ExecutionOptions) -> Result<Output, Error> {
         let ScriptCode(code_string) = script;
 
         let path = self.path_env_var();
+        let (shell, args) = self.shell(options)?;
+
+        log::trace!("executing {shell} {args:?} with script {code_string:?}");
 
-        let mut command = Command::new(&self.command);
+        let mut command = Command::new(self.program(&shell));
+
+        if self.env_inheritance != EnvInheritance::All {
+            command.env_clear();
+        }
 
         command
-            .args(&self.args)
+            .args(&args)
             .arg(code_string)
             .envs(&self.env)
             .env("PATH", path.expect("Failed to construct PATH"));
@@ -104,23 +232,55 @@ impl Executor for ShellExecutor {
             command.env_remove(name);
         }
 
-        let output = command.output();
+        for name in &options.unset_env {
+            command.env_remove(name);
+        }
+        command.envs(options.env.iter().map(|(name, value)| (name, value)));
 
-        output
-            .map(Output::from)
-            .map_err(|err| Error::CommandFailed {
-                command: format!("{} {:?}", self.command, self.args),
-                message: err.to_string(),
-            })
+        if let Some(current_dir) = &options.current_dir {
+            command.current_dir(current_dir).env("PWD", current_dir);
+        }
+
+        self.spawn_and_capture(command, options.stdin.as_deref(), options.tee.as_deref())
+    }
+
+    fn has_command(&self, command: &str, options: &ExecutionOptions) -> bool {
+        let path = options
+            .env
+            .iter()
+            .rev()
+            .find(|(name, _)| name == "PATH")
+            .map(|(_, path)| OsString::from(path))
+            .or_else(|| self.path_env_var().ok());
+
+        path.is_some_and(|path| executor::is_on_path(command, &path))
+    }
+
+    fn env_var(&self, name: &str, options: &ExecutionOptions) -> Option<String> {
+        if let Some(value) = options.set_env_var(name) {
+            return Some(value.clone());
+        }
+
+        if options.unsets_env_var(name) || self.unset_env.iter().any(|unset| unset == name) {
+            return None;
+        }
+
+        self.env.get(name).cloned().or_else(|| {
+            if self.env_inheritance == EnvInheritance::All {
+                env::var(name).ok()
+            } else {
+                None
+            }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Error, Executor, ScriptCode, ShellExecutor};
+    use super::{EnvInheritance, Error, ExecutionOptions, Executor, ScriptCode, ShellExecutor};
 
     mod shell {
-        use super::{Error, Executor, ScriptCode, ShellExecutor};
+        use super::{EnvInheritance, Error, ExecutionOptions, Executor, ScriptCode, ShellExecutor};
         #[cfg(not(windows))]
         use std::env;
         use std::path::PathBuf;
@@ -131,7 +291,10 @@ mod tests {
             let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
                 .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("echo $0".to_string()))
+                .execute(
+                    &ScriptCode("echo $0".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             assert_eq!(output.stdout, "bash\n");
         }
@@ -142,7 +305,10 @@ mod tests {
             let shell = ShellExecutor::new::<PathBuf>("cmd.exe /c", &[], &[], &[])
                 .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("echo cmd.exe".to_string()))
+                .execute(
+                    &ScriptCode("echo cmd.exe".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             assert_eq!(output.stdout, "cmd.exe\r\n");
         }
@@ -152,12 +318,46 @@ mod tests {
             let shell =
                 ShellExecutor::new::<PathBuf>("echo", &[], &[], &[]).expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("hello".to_string()))
+                .execute(
+                    &ScriptCode("hello".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             let expected = "hello\n";
             assert_eq!(output.stdout, expected);
         }
 
+        #[cfg(not(windows))]
+        #[test]
+        fn execute_with_a_shell_command_option_uses_that_shell() {
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+            let output = shell
+                .execute(
+                    &ScriptCode("hello".to_string()),
+                    &ExecutionOptions {
+                        shell_command: Some("echo".to_string()),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+            assert_eq!(output.stdout, "hello\n");
+        }
+
+        #[test]
+        fn execute_with_an_empty_shell_command_option() {
+            let shell =
+                ShellExecutor::new::<PathBuf>("echo", &[], &[], &[]).expect("shell to be created");
+            let result = shell.execute(
+                &ScriptCode("hello".to_string()),
+                &ExecutionOptions {
+                    shell_command: Some(String::new()),
+                    ..ExecutionOptions::default()
+                },
+            );
+            assert!(matches!(result, Err(Error::BadShellCommand { .. })));
+        }
+
         #[test]
         fn new_with_empty_command_string() {
             assert_eq!(
@@ -186,7 +386,10 @@ mod tests {
             let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
                 .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("echo '\u{2550}'".to_string()))
+                .execute(
+                    &ScriptCode("echo '\u{2550}'".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             let expected = "\u{2550}\n";
             assert_eq!(output.stdout, expected);
@@ -198,21 +401,60 @@ mod tests {
             let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
                 .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("echo 'test' >&2".to_string()))
+                .execute(
+                    &ScriptCode("echo 'test' >&2".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             let expected = "test\n";
             assert_eq!(output.stderr, expected);
         }
 
+        #[cfg(not(windows))]
+        #[test]
+        fn returning_stdout_and_stderr_interleaved_in_the_order_they_were_written() {
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+            let output = shell
+                .execute(
+                    &ScriptCode(
+                        "echo one; sleep 0.1; echo two >&2; sleep 0.1; echo three".to_string(),
+                    ),
+                    &ExecutionOptions::default(),
+                )
+                .expect("success");
+            assert_eq!(output.combined, "one\ntwo\nthree\n");
+        }
+
         #[cfg(not(windows))]
         #[test]
         fn returning_exit_code() {
             let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
                 .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("exit 12".to_string()))
+                .execute(
+                    &ScriptCode("exit 12".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             assert_eq!(output.exit_code, Some(12));
+            assert_eq!(output.signal, None);
+            assert!(output.pid.is_some());
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn returning_the_signal_which_killed_the_script() {
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+            let output = shell
+                .execute(
+                    &ScriptCode("kill -9 $$".to_string()),
+                    &ExecutionOptions::default(),
+                )
+                .expect("success");
+            assert_eq!(output.exit_code, None);
+            assert_eq!(output.signal, Some(9));
         }
 
         #[cfg(not(windows))]
@@ -226,7 +468,10 @@ mod tests {
             )
             .expect("shell to be created");
             let output = shell
-                .execute(&ScriptCode("echo $MESSAGE".to_string()))
+                .execute(
+                    &ScriptCode("echo $MESSAGE".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             assert_eq!("hello\n", output.stdout);
         }
@@ -245,7 +490,10 @@ mod tests {
             .expect("shell to be created");
 
             let output = shell
-                .execute(&ScriptCode("echo $UNSET_ME".to_string()))
+                .execute(
+                    &ScriptCode("echo $UNSET_ME".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
 
             assert_eq!("\n", output.stdout);
@@ -258,9 +506,259 @@ mod tests {
                 .expect("shell to be created");
             let path = env::var("PATH").expect("PATH environment variable must be set");
             let output = shell
-                .execute(&ScriptCode("echo -n $PATH".to_string()))
+                .execute(
+                    &ScriptCode("echo -n $PATH".to_string()),
+                    &ExecutionOptions::default(),
+                )
                 .expect("success");
             assert_eq!(format!("my/bin:other/bin:{path}"), output.stdout);
         }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_path_only_env_inheritance_does_not_inherit_ambient_variables() {
+            env::set_var("AMBIENT_VARIABLE", "value");
+
+            let shell = ShellExecutor::new::<PathBuf>(
+                "bash -c",
+                &[("MESSAGE".to_string(), "hello".to_string())],
+                &[],
+                &[],
+            )
+            .expect("shell to be created")
+            .env_inheritance(EnvInheritance::PathOnly);
+            let path = env::var("PATH").expect("PATH environment variable must be set");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("echo \"$AMBIENT_VARIABLE|$MESSAGE|$PATH\"".to_string()),
+                    &ExecutionOptions::default(),
+                )
+                .expect("success");
+
+            assert_eq!(format!("|hello|{path}\n"), output.stdout);
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_no_env_inheritance_only_uses_the_added_paths() {
+            let shell = ShellExecutor::new("bash -c", &[], &[], &["my/bin"])
+                .expect("shell to be created")
+                .env_inheritance(EnvInheritance::Nothing);
+
+            let output = shell
+                .execute(
+                    &ScriptCode("echo -n $PATH".to_string()),
+                    &ExecutionOptions::default(),
+                )
+                .expect("success");
+
+            assert_eq!("my/bin", output.stdout);
+        }
+
+        #[test]
+        fn env_var_sees_the_environment_that_scripts_are_run_with() {
+            let shell = ShellExecutor::new::<PathBuf>(
+                "bash -c",
+                &[("DEPLOY_TARGET".to_string(), "staging".to_string())],
+                &["HOME".to_string()],
+                &[],
+            )
+            .expect("shell to be created");
+
+            assert_eq!(
+                shell.env_var("DEPLOY_TARGET", &ExecutionOptions::default()),
+                Some("staging".to_string())
+            );
+            assert_eq!(shell.env_var("HOME", &ExecutionOptions::default()), None);
+            assert_eq!(
+                shell.env_var(
+                    "DEPLOY_TARGET",
+                    &ExecutionOptions {
+                        unset_env: vec!["DEPLOY_TARGET".to_string()],
+                        ..ExecutionOptions::default()
+                    }
+                ),
+                None
+            );
+            assert_eq!(
+                shell.env_var(
+                    "HOME",
+                    &ExecutionOptions {
+                        env: vec![("HOME".to_string(), "/tmp".to_string())],
+                        ..ExecutionOptions::default()
+                    }
+                ),
+                Some("/tmp".to_string())
+            );
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn has_command_looks_in_the_path_that_scripts_are_run_with() {
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created")
+                .env_inheritance(EnvInheritance::Nothing);
+
+            assert!(!shell.has_command("bash", &ExecutionOptions::default()));
+            assert!(shell.has_command(
+                "bash",
+                &ExecutionOptions {
+                    env: vec![("PATH".to_string(), "/bin:/usr/bin".to_string())],
+                    ..ExecutionOptions::default()
+                }
+            ));
+            assert!(!shell.has_command(
+                "specdown-missing-command",
+                &ExecutionOptions {
+                    env: vec![("PATH".to_string(), "/bin:/usr/bin".to_string())],
+                    ..ExecutionOptions::default()
+                }
+            ));
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_env_options_overriding_the_shell_environment() {
+            let shell = ShellExecutor::new::<PathBuf>(
+                "bash -c",
+                &[("MESSAGE".to_string(), "hello".to_string())],
+                &[],
+                &[],
+            )
+            .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("echo $MESSAGE $NAME".to_string()),
+                    &ExecutionOptions {
+                        env: vec![
+                            ("MESSAGE".to_string(), "goodbye".to_string()),
+                            ("NAME".to_string(), "specdown".to_string()),
+                        ],
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.stdout, "goodbye specdown\n");
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_stdin_passes_the_input_to_the_script() {
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("sort".to_string()),
+                    &ExecutionOptions {
+                        stdin: Some("b\nc\na\n".to_string()),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.stdout, "a\nb\nc\n");
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_tee_writes_stdout_to_the_file() {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let tee_path = directory.path().join("logs").join("x.out");
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("echo hello; echo error >&2".to_string()),
+                    &ExecutionOptions {
+                        tee: Some(tee_path.clone()),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.stdout, "hello\n");
+            assert_eq!(output.stderr, "error\n");
+            assert_eq!(
+                std::fs::read_to_string(tee_path).expect("tee file to exist"),
+                "hello\n"
+            );
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_tee_gives_the_script_an_empty_stdin() {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let tee_path = directory.path().join("x.out");
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("wc -c".to_string()),
+                    &ExecutionOptions {
+                        tee: Some(tee_path),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.stdout.trim(), "0");
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_tee_and_stdin_passes_the_input_and_writes_stdout_to_the_file() {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let tee_path = directory.path().join("x.out");
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("sort".to_string()),
+                    &ExecutionOptions {
+                        tee: Some(tee_path.clone()),
+                        stdin: Some("b\nc\na\n".to_string()),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.stdout, "a\nb\nc\n");
+            assert_eq!(
+                std::fs::read_to_string(tee_path).expect("tee file to exist"),
+                "a\nb\nc\n"
+            );
+        }
+
+        #[cfg(not(windows))]
+        #[test]
+        fn with_tee_writes_stdout_to_the_file_when_the_script_fails() {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let tee_path = directory.path().join("x.out");
+            let shell = ShellExecutor::new::<PathBuf>("bash -c", &[], &[], &[])
+                .expect("shell to be created");
+
+            let output = shell
+                .execute(
+                    &ScriptCode("echo partial; exit 3".to_string()),
+                    &ExecutionOptions {
+                        tee: Some(tee_path.clone()),
+                        ..ExecutionOptions::default()
+                    },
+                )
+                .expect("success");
+
+            assert_eq!(output.exit_code, Some(3));
+            assert_eq!(
+                std::fs::read_to_string(tee_path).expect("tee file to exist"),
+                "partial\n"
+            );
+        }
     }
 }
@@ -0,0 +1,206 @@
+use std::env::consts::OS;
+
+use crate::results::{ActionResult, SkippedResult};
+use crate::types::{Action, ScriptOptions, TargetOs, VerifyOptions};
+
+use super::{ExecutionOptions, Executor};
+
+pub fn run(action: &Action, reason: Option<&str>) -> ActionResult {
+    ActionResult::Skipped(SkippedResult {
+        action: action.clone(),
+        reason: reason.map(str::to_string),
+    })
+}
+
+pub fn script_reason(
+    options: &ScriptOptions,
+    executor: &dyn Executor,
+    execution_options: &ExecutionOptions,
+) -> Option<String> {
+    options
+        .skip
+        .clone()
+        .or_else(|| target_os_reason(options.target_os.as_ref()))
+        .or_else(|| missing_command_reason(&options.requires_command, executor, execution_options))
+        .or_else(|| {
+            env_reason(
+                &options.only_if_env,
+                &options.skip_if_env,
+                executor,
+                execution_options,
+            )
+        })
+}
+
+pub fn verify_reason(
+    options: &VerifyOptions,
+    executor: &dyn Executor,
+    execution_options: &ExecutionOptions,
+) -> Option<String> {
+    target_os_reason(options.target_os.as_ref()).or_else(|| {
+        env_reason(
+            &options.only_if_env,
+            &options.skip_if_env,
+            executor,
+            execution_options,
+        )
+    })
+}
+
+fn target_os_reason(target_os: Option<&TargetOs>) -> Option<String> {
+    target_os
+        .filter(|target_os| !target_os.matches(OS))
+        .map(|target_os| format!("target_os is {target_os}"))
+}
+
+fn missing_command_reason(
+    commands: &[String],
+    executor: &dyn Executor,
+    options: &ExecutionOptions,
+) -> Option<String> {
+    commands
+        .iter()
+        .find(|command| !executor.has_command(command, options))
+        .map(|command| format!("{command} is not on the PATH"))
+}
+
+// A variable which is set to an empty value counts as not set
+fn env_reason(
+    only_if_env: &[String],
+    skip_if_env: &[String],
+    executor: &dyn Executor,
+    options: &ExecutionOptions,
+) -> Option<String> {
+    let is_set = |name: &&String| {
+        executor
+            .env_var(name, options)
+            .is_some_and(|value| !value.is_empty())
+    };
+
+    only_if_env
+        .iter()
+        .find(|name| !is_set(name))
+        .map(|name| format!("{name} is not set"))
+        .or_else(|| {
+            skip_if_env
+                .iter()
+                .find(is_set)
+                .map(|name| format!("{name} is set"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        env_reason, missing_command_reason, run, target_os_reason, Action, ActionResult,
+        ExecutionOptions, Executor, SkippedResult, TargetOs, OS,
+    };
+    use crate::runner::executor::Output;
+    use crate::runner::Error;
+    use crate::types::{ScriptCode, SkipAction};
+
+    struct InstalledExecutor;
+
+    impl Executor for InstalledExecutor {
+        fn execute(&self, _: &ScriptCode, _: &ExecutionOptions) -> Result<Output, Error> {
+            unreachable!("scripts are not run")
+        }
+
+        fn has_command(&self, command: &str, _options: &ExecutionOptions) -> bool {
+            command == "git"
+        }
+
+        fn env_var(&self, name: &str, _options: &ExecutionOptions) -> Option<String> {
+            match name {
+                "CI" => Some("true".to_string()),
+                "EMPTY" => Some(String::new()),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_records_the_skipped_action_and_reason() {
+        let action = Action::Skip(SkipAction { reason: None });
+
+        let result = run(&action, Some("needs network access"));
+
+        assert_eq!(
+            result,
+            ActionResult::Skipped(SkippedResult {
+                action,
+                reason: Some("needs network access".to_string()),
+            })
+        );
+        assert!(result.success());
+    }
+
+    #[test]
+    fn test_target_os_reason_is_none_on_a_targeted_os() {
+        assert_eq!(target_os_reason(None), None);
+        assert_eq!(
+            target_os_reason(Some(&TargetOs(vec![OS.to_string()]))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_target_os_reason_names_the_targeted_os() {
+        assert_eq!(
+            target_os_reason(Some(&TargetOs(vec!["fake-os".to_string()]))),
+            Some("target_os is fake-os".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_command_reason_names_the_first_missing_command() {
+        let commands = vec![
+            "git".to_string(),
+            "docker".to_string(),
+            "kubectl".to_string(),
+        ];
+
+        assert_eq!(
+            missing_command_reason(&commands, &InstalledExecutor, &ExecutionOptions::default()),
+            Some("docker is not on the PATH".to_string())
+        );
+        assert_eq!(
+            missing_command_reason(
+                &commands[..1],
+                &InstalledExecutor,
+                &ExecutionOptions::default()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_env_reason_names_the_first_unmet_condition() {
+        let options = ExecutionOptions::default();
+        let names =
+            |names: &[&str]| -> Vec<String> { names.iter().map(ToString::to_string).collect() };
+
+        assert_eq!(
+            env_reason(
+                &names(&["CI"]),
+                &names(&["OFFLINE"]),
+                &InstalledExecutor,
+                &options
+            ),
+            None
+        );
+        assert_eq!(
+            env_reason(&names(&["CI", "EMPTY"]), &[], &InstalledExecutor, &options),
+            Some("EMPTY is not set".to_string())
+        );
+        assert_eq!(
+            env_reason(
+                &[],
+                &names(&["OFFLINE", "CI"]),
+                &InstalledExecutor,
+                &options
+            ),
+            Some("CI is set".to_string())
+        );
+    }
+}
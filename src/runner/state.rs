@@ -1,16 +1,51 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use crate::results::{ActionResult, ScriptResult};
+use crate::results::{
+    ActionResult, CreateFileResult, ScriptResult, SetEnvResult, SkippedResult, StdinResult,
+    VerifyResult,
+};
+use crate::types::{Action, ScriptAction, ScriptName, Stream};
+
+use super::executor::ExecutionOptions;
 
 pub struct State {
     last_script_result: Option<ScriptResult>,
     script_results: HashMap<String, ScriptResult>,
+    verified_lengths: HashMap<(String, Stream), usize>,
+    unavailable_scripts: HashSet<String>,
+    last_script_unavailable: bool,
+    // Includes the key of an unnamed script while the last script was skipped
+    skipped_scripts: HashSet<String>,
+    variables: HashMap<String, String>,
+    // Set by the spec file's front matter before any env() changes
+    file_environment: BTreeMap<String, String>,
+    // Changes made by env(), where None means the variable is unset
+    environment: BTreeMap<String, Option<String>>,
+    shell_command: Option<String>,
+    stdin: HashMap<String, String>,
+    path_masks: Vec<(String, String)>,
+    created_files: Vec<String>,
+    workdirs: Vec<PathBuf>,
+    bench_iterations: u32,
+    forbids_unexpected_stderr: bool,
     is_success: bool,
 }
 
 pub trait ScriptOutput {
     fn get_result(&self, name: &str) -> Option<&ScriptResult>;
     fn get_last_result(&self) -> Option<&ScriptResult>;
+    fn is_script_unavailable(&self, name: &str) -> bool;
+    fn is_last_script_unavailable(&self) -> bool;
+    fn verified_length(&self, name: Option<&ScriptName>, stream: &Stream) -> usize;
+}
+
+pub trait Variables {
+    fn get_variable(&self, name: &str) -> Option<&String>;
+}
+
+pub trait PathMasks {
+    fn path_masks(&self) -> &[(String, String)];
 }
 
 impl State {
@@ -18,25 +53,179 @@ impl State {
         Self {
             last_script_result: None,
             script_results: HashMap::new(),
+            verified_lengths: HashMap::new(),
+            unavailable_scripts: HashSet::new(),
+            last_script_unavailable: false,
+            skipped_scripts: HashSet::new(),
+            variables: HashMap::new(),
+            file_environment: BTreeMap::new(),
+            environment: BTreeMap::new(),
+            shell_command: None,
+            stdin: HashMap::new(),
+            path_masks: Vec::new(),
+            created_files: Vec::new(),
+            workdirs: Vec::new(),
+            bench_iterations: 1,
+            forbids_unexpected_stderr: false,
             is_success: true,
         }
     }
 
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variables.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn set_file_env(&mut self, name: &str, value: &str) {
+        self.file_environment
+            .insert(name.to_string(), value.to_string());
+    }
+
+    pub fn set_shell_command(&mut self, shell_command: &str) {
+        self.shell_command = Some(shell_command.to_string());
+    }
+
+    pub fn set_bench_iterations(&mut self, iterations: u32) {
+        self.bench_iterations = iterations;
+    }
+
+    pub const fn bench_iterations(&self) -> u32 {
+        self.bench_iterations
+    }
+
+    pub fn set_forbids_unexpected_stderr(&mut self, forbids_unexpected_stderr: bool) {
+        self.forbids_unexpected_stderr = forbids_unexpected_stderr;
+    }
+
+    pub const fn forbids_unexpected_stderr(&self) -> bool {
+        self.forbids_unexpected_stderr
+    }
+
+    pub fn add_path_mask(&mut self, token: &str, path: &str) {
+        self.path_masks.push((token.to_string(), path.to_string()));
+    }
+
     pub fn add_result(&mut self, action_result: &ActionResult) {
-        if !(action_result.success()) {
+        if !action_result.success() && !action_result.failure_allowed() {
             self.is_success = false;
         }
 
         if let ActionResult::Script(script_result) = action_result {
-            let script_name = script_result
-                .action
-                .script_name
-                .clone()
-                .map_or("<unknown-script-value".to_string(), Into::into);
+            let script_name = script_key(script_result.action.script_name.as_ref());
+            self.skipped_scripts.remove(&script_name);
+            self.skipped_scripts.remove(&script_key(None));
             self.script_results
                 .insert(script_name, script_result.clone());
             self.last_script_result = Some(script_result.clone());
+            self.last_script_unavailable = false;
+
+            if let Some(tee) = &script_result.action.options.tee {
+                self.created_files.push(tee.clone().into());
+            }
+
+            if let Some(workdir) = &script_result.workdir {
+                self.set_variable("SPECDOWN_SCRIPT_WORKDIR", &workdir.display().to_string());
+                self.workdirs.push(workdir.clone());
+            }
         }
+
+        if let ActionResult::Verify(VerifyResult { action, .. }) = action_result {
+            let name = action.source.name.as_ref();
+            let result = name.map_or(self.last_script_result.as_ref(), |name| {
+                self.script_results.get(&String::from(name))
+            });
+            if let Some(result) = result {
+                let length = match action.source.stream {
+                    Stream::StdOut => result.stdout.len(),
+                    Stream::StdErr => result.stderr.len(),
+                    Stream::Output => result.output.len(),
+                };
+                self.verified_lengths
+                    .insert((script_key(name), action.source.stream.clone()), length);
+            }
+        }
+
+        if let ActionResult::Skipped(SkippedResult {
+            action: Action::Script(action),
+            ..
+        }) = action_result
+        {
+            self.skipped_scripts
+                .insert(script_key(action.script_name.as_ref()));
+            self.skipped_scripts.insert(script_key(None));
+        }
+
+        if let ActionResult::CreateFile(CreateFileResult { action }) = action_result {
+            self.created_files.push(action.file_path.clone().into());
+        }
+
+        if let ActionResult::SetEnv(SetEnvResult { action }) = action_result {
+            if action.reset {
+                self.environment.clear();
+            }
+            for name in &action.unset {
+                self.environment.insert(name.clone(), None);
+            }
+            for (name, value) in &action.variables {
+                self.environment.insert(name.clone(), Some(value.clone()));
+            }
+        }
+
+        if let ActionResult::Stdin(StdinResult { action }) = action_result {
+            self.stdin
+                .insert(action.script_name.clone().into(), action.input.clone());
+        }
+    }
+
+    pub fn stdin(&self, name: Option<&ScriptName>) -> Option<&String> {
+        self.stdin.get(&String::from(name?))
+    }
+
+    pub fn mark_script_unavailable(&mut self, action: &ScriptAction) {
+        self.is_success = false;
+        self.last_script_unavailable = true;
+        self.skipped_scripts.remove(&script_key(None));
+
+        if let Some(script_name) = &action.script_name {
+            self.unavailable_scripts.insert(script_name.clone().into());
+        }
+    }
+
+    pub fn is_script_skipped(&self, name: Option<&ScriptName>) -> bool {
+        self.skipped_scripts.contains(&script_key(name))
+    }
+
+    pub fn execution_options(&self) -> ExecutionOptions {
+        let (env, unset_env): (Vec<_>, Vec<_>) = self
+            .environment
+            .iter()
+            .partition(|(_, value)| value.is_some());
+
+        ExecutionOptions {
+            env: self
+                .file_environment
+                .iter()
+                .filter(|(name, _)| !self.environment.contains_key(*name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .chain(
+                    env.into_iter()
+                        .filter_map(|(name, value)| Some((name.clone(), value.clone()?))),
+                )
+                .collect(),
+            unset_env: unset_env
+                .into_iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            shell_command: self.shell_command.clone(),
+            ..ExecutionOptions::default()
+        }
+    }
+
+    pub fn created_files(&self) -> &[String] {
+        &self.created_files
+    }
+
+    pub fn workdirs(&self) -> impl Iterator<Item = &Path> {
+        self.workdirs.iter().map(PathBuf::as_path)
     }
 
     pub const fn is_success(&self) -> bool {
@@ -52,15 +241,50 @@ impl ScriptOutput for State {
     fn get_last_result(&self) -> Option<&ScriptResult> {
         self.last_script_result.as_ref()
     }
+
+    fn is_script_unavailable(&self, name: &str) -> bool {
+        self.unavailable_scripts.contains(name)
+    }
+
+    fn is_last_script_unavailable(&self) -> bool {
+        self.last_script_unavailable
+    }
+
+    fn verified_length(&self, name: Option<&ScriptName>, stream: &Stream) -> usize {
+        self.verified_lengths
+            .get(&(script_key(name), stream.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+fn script_key(name: Option<&ScriptName>) -> String {
+    name.map_or("<unknown-script-value".to_string(), Into::into)
+}
+
+impl Variables for State {
+    fn get_variable(&self, name: &str) -> Option<&String> {
+        self.variables.get(name)
+    }
+}
+
+impl PathMasks for State {
+    fn path_masks(&self) -> &[(String, String)] {
+        &self.path_masks
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ActionResult, ScriptOutput, State};
-    use crate::results::{CreateFileResult, ScriptResult, VerifyResult};
+    use super::{
+        Action, ActionResult, ExecutionOptions, PathMasks, ScriptOutput, SetEnvResult,
+        SkippedResult, State, StdinResult, Variables,
+    };
+    use crate::results::{CreateFileResult, RawOutput, ScriptResult, VerifyFailure, VerifyResult};
     use crate::types::{
-        CreateFileAction, ExitCode, FileContent, FilePath, OutputExpectation, ScriptAction,
-        ScriptCode, ScriptName, Source, Stream, VerifyAction, VerifyValue,
+        CreateFileAction, CreateFileOptions, ExitCode, ExpectedExitCode, FileContent, FilePath,
+        OutputExpectation, ScriptAction, ScriptCode, ScriptName, ScriptOptions, SetEnvAction,
+        Source, StdinAction, Stream, VerifyAction, VerifyOptions, VerifyValue,
     };
 
     #[test]
@@ -76,12 +300,23 @@ mod tests {
             script_code: ScriptCode("script1".to_string()),
             expected_exit_code: None,
             expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
         };
         let script_result1 = ActionResult::Script(ScriptResult {
             action,
             exit_code: Some(ExitCode(0)),
             stdout: "stdout1".to_string(),
             stderr: "stderr1".to_string(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
         });
         let mut state = State::new();
         state.add_result(&script_result1);
@@ -93,25 +328,70 @@ mod tests {
         let action = ScriptAction {
             script_name: Some(ScriptName("script1".to_string())),
             script_code: ScriptCode("script1".to_string()),
-            expected_exit_code: Some(ExitCode(1)),
+            expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(1))),
             expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
         };
         let script_result1 = ActionResult::Script(ScriptResult {
             action,
             exit_code: Some(ExitCode(2)),
             stdout: "stdout1".to_string(),
             stderr: "stderr1".to_string(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
         });
         let mut state = State::new();
         state.add_result(&script_result1);
         assert!(!state.is_success());
     }
 
+    #[test]
+    fn succeeds_when_a_script_which_is_allowed_to_fail_failed() {
+        let action = ScriptAction {
+            script_name: Some(ScriptName("script1".to_string())),
+            script_code: ScriptCode("script1".to_string()),
+            expected_exit_code: Some(ExpectedExitCode::Exactly(ExitCode(0))),
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions {
+                allow_failure: true,
+                ..ScriptOptions::default()
+            },
+        };
+        let script_result1 = ActionResult::Script(ScriptResult {
+            action,
+            exit_code: Some(ExitCode(2)),
+            stdout: String::new(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        });
+        let mut state = State::new();
+        state.add_result(&script_result1);
+        assert!(state.is_success());
+    }
+
     #[test]
     fn does_not_update_success_when_file_result_is_added() {
         let action = CreateFileAction {
             file_path: FilePath("example.txt".to_string()),
             file_content: FileContent(String::new()),
+            options: CreateFileOptions::default(),
         };
         let file_result = ActionResult::CreateFile(CreateFileResult { action });
         let mut state = State::new();
@@ -119,6 +399,26 @@ mod tests {
         assert!(state.is_success());
     }
 
+    #[test]
+    fn records_the_paths_of_created_files() {
+        let file_result = |path: &str| {
+            ActionResult::CreateFile(CreateFileResult {
+                action: CreateFileAction {
+                    file_path: FilePath(path.to_string()),
+                    file_content: FileContent(String::new()),
+                    options: CreateFileOptions::default(),
+                },
+            })
+        };
+        let mut state = State::new();
+        state.add_result(&file_result("one.txt"));
+        state.add_result(&file_result("dir/two.txt"));
+        assert_eq!(
+            state.created_files(),
+            &["one.txt".to_string(), "dir/two.txt".to_string()]
+        );
+    }
+
     #[test]
     fn get_result_returns_the_result_when_script_result_exists() {
         let script_result1 = ScriptResult {
@@ -127,10 +427,21 @@ mod tests {
                 script_code: ScriptCode("script1".to_string()),
                 expected_exit_code: None,
                 expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
             },
             exit_code: Some(ExitCode(0)),
             stdout: "stdout1".to_string(),
             stderr: "stderr1".to_string(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
         };
         let script_result2 = ScriptResult {
             action: ScriptAction {
@@ -138,10 +449,21 @@ mod tests {
                 script_code: ScriptCode("script1".to_string()),
                 expected_exit_code: None,
                 expected_output: OutputExpectation::Any,
+                options: ScriptOptions::default(),
             },
             exit_code: Some(ExitCode(0)),
             stdout: "stdout2".to_string(),
             stderr: "stderr2".to_string(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
         };
         let mut state = State::new();
         state.add_result(&ActionResult::Script(script_result1.clone()));
@@ -165,8 +487,11 @@ mod tests {
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue("expected".to_string()),
+                options: VerifyOptions::default(),
             },
             got: "expected".to_string(),
+            failure: None,
+            displayed: None,
         });
         let mut state = State::new();
         state.add_result(&verify_result);
@@ -182,8 +507,11 @@ mod tests {
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue("expected".to_string()),
+                options: VerifyOptions::default(),
             },
             got: "different".to_string(),
+            failure: Some(VerifyFailure::OutputDoesNotMatch),
+            displayed: None,
         });
         let verify_result_success = ActionResult::Verify(VerifyResult {
             action: VerifyAction {
@@ -192,8 +520,11 @@ mod tests {
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue("expected".to_string()),
+                options: VerifyOptions::default(),
             },
             got: "expected".to_string(),
+            failure: None,
+            displayed: None,
         });
         let mut state = State::new();
         state.add_result(&verify_result_failure);
@@ -210,14 +541,202 @@ mod tests {
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue("expected".to_string()),
+                options: VerifyOptions::default(),
             },
             got: "not expected".to_string(),
+            failure: Some(VerifyFailure::OutputDoesNotMatch),
+            displayed: None,
         });
         let mut state = State::new();
         state.add_result(&failed_verify_result);
         assert!(!state.is_success());
     }
 
+    #[test]
+    fn get_variable_returns_none_when_variable_is_not_set() {
+        assert_eq!(None, State::new().get_variable("NAME"));
+    }
+
+    #[test]
+    fn get_variable_returns_the_value_when_variable_is_set() {
+        let mut state = State::new();
+        state.set_variable("NAME", "value");
+        assert_eq!(Some(&"value".to_string()), state.get_variable("NAME"));
+    }
+
+    fn set_env(state: &mut State, action: SetEnvAction) {
+        state.add_result(&ActionResult::SetEnv(SetEnvResult { action }));
+    }
+
+    #[test]
+    fn set_env_results_override_earlier_values() {
+        let mut state = State::new();
+        for value in ["first", "second"] {
+            set_env(
+                &mut state,
+                SetEnvAction {
+                    variables: vec![("NAME".to_string(), value.to_string())],
+                    ..SetEnvAction::default()
+                },
+            );
+        }
+
+        assert_eq!(
+            state.execution_options().env,
+            [("NAME".to_string(), "second".to_string())]
+        );
+    }
+
+    #[test]
+    fn set_env_results_unset_variables_until_they_are_set_again() {
+        let mut state = State::new();
+        set_env(
+            &mut state,
+            SetEnvAction {
+                variables: vec![("A".to_string(), "value".to_string())],
+                unset: vec!["A".to_string(), "B".to_string()],
+                reset: false,
+            },
+        );
+
+        let options = state.execution_options();
+        assert_eq!(options.env, [("A".to_string(), "value".to_string())]);
+        assert_eq!(options.unset_env, ["B".to_string()]);
+    }
+
+    #[test]
+    fn set_env_results_reset_earlier_changes() {
+        let mut state = State::new();
+        set_env(
+            &mut state,
+            SetEnvAction {
+                variables: vec![("A".to_string(), "value".to_string())],
+                unset: vec!["B".to_string()],
+                reset: false,
+            },
+        );
+        set_env(
+            &mut state,
+            SetEnvAction {
+                reset: true,
+                ..SetEnvAction::default()
+            },
+        );
+
+        assert_eq!(state.execution_options(), ExecutionOptions::default());
+    }
+
+    #[test]
+    fn set_env_results_override_file_env_variables() {
+        let mut state = State::new();
+        state.set_file_env("A", "file");
+        state.set_file_env("B", "file");
+        set_env(
+            &mut state,
+            SetEnvAction {
+                variables: vec![("A".to_string(), "block".to_string())],
+                unset: vec!["B".to_string()],
+                reset: false,
+            },
+        );
+
+        let options = state.execution_options();
+        assert_eq!(options.env, [("A".to_string(), "block".to_string())]);
+        assert_eq!(options.unset_env, ["B".to_string()]);
+    }
+
+    #[test]
+    fn execution_options_include_the_file_shell_command() {
+        let mut state = State::new();
+        state.set_shell_command("sh -c");
+
+        assert_eq!(
+            state.execution_options().shell_command,
+            Some("sh -c".to_string())
+        );
+    }
+
+    #[test]
+    fn marking_a_script_unavailable_fails_the_state() {
+        let action = ScriptAction {
+            script_name: Some(ScriptName("broken".to_string())),
+            script_code: ScriptCode("broken".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        };
+        let mut state = State::new();
+        state.mark_script_unavailable(&action);
+        assert!(!state.is_success());
+        assert!(state.is_script_unavailable("broken"));
+        assert!(state.is_last_script_unavailable());
+        assert!(!state.is_script_unavailable("other"));
+    }
+
+    #[test]
+    fn records_skipped_scripts_without_failing_the_state() {
+        let action = ScriptAction {
+            script_name: Some(ScriptName("deploy".to_string())),
+            script_code: ScriptCode("deploy".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        };
+        let mut state = State::new();
+        state.add_result(&ActionResult::Skipped(SkippedResult {
+            action: Action::Script(action),
+            reason: None,
+        }));
+        assert!(state.is_success());
+        assert!(state.is_script_skipped(Some(&ScriptName("deploy".to_string()))));
+        assert!(state.is_script_skipped(None));
+        assert!(!state.is_script_skipped(Some(&ScriptName("other".to_string()))));
+    }
+
+    #[test]
+    fn last_script_is_available_again_after_a_script_result_is_added() {
+        let action = ScriptAction {
+            script_name: None,
+            script_code: ScriptCode("script1".to_string()),
+            expected_exit_code: None,
+            expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
+        };
+        let mut state = State::new();
+        state.mark_script_unavailable(&action);
+        state.add_result(&ActionResult::Script(ScriptResult {
+            action,
+            exit_code: Some(ExitCode(0)),
+            stdout: String::new(),
+            stderr: String::new(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
+        }));
+        assert!(!state.is_last_script_unavailable());
+    }
+
+    #[test]
+    fn path_masks_returns_the_added_masks_in_order() {
+        let mut state = State::new();
+        state.add_path_mask("<HOME>", "/home/user");
+        state.add_path_mask("<RUNNING_DIR>", "/tmp/run");
+        assert_eq!(
+            state.path_masks(),
+            &[
+                ("<HOME>".to_string(), "/home/user".to_string()),
+                ("<RUNNING_DIR>".to_string(), "/tmp/run".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn get_last_result_returns_none_when_no_scripts_have_been_run() {
         assert_eq!(None, State::new().get_last_result());
@@ -230,15 +749,93 @@ mod tests {
             script_code: ScriptCode("script1".to_string()),
             expected_exit_code: None,
             expected_output: OutputExpectation::Any,
+            options: ScriptOptions::default(),
         };
         let script_result = ScriptResult {
             action,
             exit_code: Some(ExitCode(0)),
             stdout: "stdout1".to_string(),
             stderr: "stderr1".to_string(),
+            output: String::new(),
+            raw_output: RawOutput::default(),
+            durations: vec![],
+            idempotency_failure: None,
+            stability_failure: None,
+            workdir: None,
+            pid: None,
+            signal: None,
+            environment: None,
+            attempts: 1,
         };
         let mut state = State::new();
         state.add_result(&ActionResult::Script(script_result.clone()));
         assert_eq!(Some(&script_result), state.get_last_result());
     }
+
+    #[test]
+    fn records_the_length_of_each_verified_stream_and_keeps_it_when_the_script_reruns() {
+        let script_result = |stdout: &str| {
+            ActionResult::Script(ScriptResult {
+                action: ScriptAction {
+                    script_name: Some(ScriptName("log".to_string())),
+                    script_code: ScriptCode("cat app.log".to_string()),
+                    expected_exit_code: None,
+                    expected_output: OutputExpectation::Any,
+                    options: ScriptOptions::default(),
+                },
+                exit_code: Some(ExitCode(0)),
+                stdout: stdout.to_string(),
+                stderr: String::new(),
+                output: String::new(),
+                raw_output: RawOutput::default(),
+                durations: vec![],
+                idempotency_failure: None,
+                stability_failure: None,
+                workdir: None,
+                pid: None,
+                signal: None,
+                environment: None,
+                attempts: 1,
+            })
+        };
+        let verify_result = ActionResult::Verify(VerifyResult {
+            action: VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("log".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue("started\n".to_string()),
+                options: VerifyOptions::default(),
+            },
+            got: "started\n".to_string(),
+            failure: None,
+            displayed: None,
+        });
+        let log = ScriptName("log".to_string());
+        let mut state = State::new();
+
+        state.add_result(&script_result("started\n"));
+        assert_eq!(state.verified_length(Some(&log), &Stream::StdOut), 0);
+
+        state.add_result(&verify_result);
+        state.add_result(&script_result("started\nready\n"));
+        assert_eq!(state.verified_length(Some(&log), &Stream::StdOut), 8);
+        assert_eq!(state.verified_length(Some(&log), &Stream::StdErr), 0);
+    }
+
+    #[test]
+    fn stdin_is_recorded_for_the_named_script() {
+        let mut state = State::new();
+        let sort = ScriptName("sort".to_string());
+        state.add_result(&ActionResult::Stdin(StdinResult {
+            action: StdinAction {
+                script_name: sort.clone(),
+                input: "b\na\n".to_string(),
+            },
+        }));
+
+        assert_eq!(state.stdin(Some(&sort)), Some(&"b\na\n".to_string()));
+        assert_eq!(state.stdin(Some(&ScriptName("other".to_string()))), None);
+        assert_eq!(state.stdin(None), None);
+    }
 }
@@ -0,0 +1,27 @@
+use crate::results::{ActionResult, StdinResult};
+use crate::types::StdinAction;
+
+pub fn run(action: &StdinAction) -> ActionResult {
+    ActionResult::Stdin(StdinResult {
+        action: action.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, ActionResult, StdinAction, StdinResult};
+    use crate::types::ScriptName;
+
+    #[test]
+    fn test_run_records_the_input_for_the_script() {
+        let action = StdinAction {
+            script_name: ScriptName("sort".to_string()),
+            input: "b\na\n".to_string(),
+        };
+
+        let result = run(&action);
+
+        assert_eq!(result, ActionResult::Stdin(StdinResult { action }));
+        assert!(result.success());
+    }
+}
@@ -0,0 +1,98 @@
+use super::state::Variables;
+use super::Error;
+
+pub fn render(template: &str, variables: &dyn Variables) -> Result<String, Error> {
+    let mut rendered = String::new();
+    let mut remaining = template;
+
+    while let Some(start) = remaining.find("${") {
+        rendered.push_str(&remaining[..start]);
+        let after_open = &remaining[start + 2..];
+
+        match after_open.find('}') {
+            Some(end) if is_variable_name(&after_open[..end]) => {
+                let name = &after_open[..end];
+                let value = variables
+                    .get_variable(name)
+                    .ok_or_else(|| Error::UnknownVariable {
+                        variable_name: name.to_string(),
+                    })?;
+                rendered.push_str(value);
+                remaining = &after_open[end + 1..];
+            }
+            _ => {
+                rendered.push_str("${");
+                remaining = after_open;
+            }
+        }
+    }
+
+    rendered.push_str(remaining);
+    Ok(rendered)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Error, Variables};
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    struct MockVariables {
+        variables: HashMap<String, String>,
+    }
+
+    impl Variables for MockVariables {
+        fn get_variable(&self, name: &str) -> Option<&String> {
+            self.variables.get(name)
+        }
+    }
+
+    fn variables() -> MockVariables {
+        MockVariables {
+            variables: hashmap! {
+                "NAME".to_string() => "World".to_string(),
+                "_OTHER_1".to_string() => "other".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn returns_the_template_when_it_contains_no_variables() {
+        assert_eq!(render("Hello!\n", &variables()), Ok("Hello!\n".to_string()));
+    }
+
+    #[test]
+    fn replaces_variables_with_their_values() {
+        assert_eq!(
+            render("Hello ${NAME}! ${_OTHER_1}", &variables()),
+            Ok("Hello World! other".to_string())
+        );
+    }
+
+    #[test]
+    fn leaves_text_which_is_not_a_variable_untouched() {
+        assert_eq!(
+            render("${not a variable} ${ $ {NAME} ${NAME", &variables()),
+            Ok("${not a variable} ${ $ {NAME} ${NAME".to_string())
+        );
+    }
+
+    #[test]
+    fn returns_an_error_when_a_variable_is_unknown() {
+        assert_eq!(
+            render("Hello ${UNKNOWN}!", &variables()),
+            Err(Error::UnknownVariable {
+                variable_name: "UNKNOWN".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use crate::types::{Action, OutputExpectation, ScriptAction, Source, Stream, VerifyAction};
+
+pub fn forbid_unverified(actions: &[Action]) -> Vec<Action> {
+    let verified = scripts_with_verified_stderr(actions);
+
+    actions
+        .iter()
+        .enumerate()
+        .map(|(index, action)| match action {
+            Action::Script(script)
+                if script.expected_output == OutputExpectation::Any
+                    && !verified.contains(&index) =>
+            {
+                Action::Script(ScriptAction {
+                    expected_output: OutputExpectation::StdOut,
+                    ..script.clone()
+                })
+            }
+            action => action.clone(),
+        })
+        .collect()
+}
+
+fn scripts_with_verified_stderr(actions: &[Action]) -> HashSet<usize> {
+    actions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, action)| match action {
+            Action::Verify(VerifyAction {
+                source:
+                    Source {
+                        name,
+                        stream: Stream::StdErr | Stream::Output,
+                    },
+                ..
+            }) => actions[..index].iter().rposition(|action| match action {
+                Action::Script(script) => name.is_none() || &script.script_name == name,
+                _ => false,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::forbid_unverified;
+    use crate::types::{
+        Action, OutputExpectation, ScriptAction, ScriptCode, ScriptName, ScriptOptions, Source,
+        Stream, VerifyAction, VerifyOptions, VerifyValue,
+    };
+
+    fn script(name: &str, expected_output: OutputExpectation) -> Action {
+        Action::Script(ScriptAction {
+            script_name: Some(ScriptName(name.to_string())),
+            script_code: ScriptCode(String::new()),
+            expected_exit_code: None,
+            expected_output,
+            options: ScriptOptions::default(),
+        })
+    }
+
+    fn verify(name: Option<&str>, stream: Stream) -> Action {
+        Action::Verify(VerifyAction {
+            source: Source {
+                name: name.map(|name| ScriptName(name.to_string())),
+                stream,
+            },
+            expected_value: VerifyValue(String::new()),
+            options: VerifyOptions::default(),
+        })
+    }
+
+    #[test]
+    fn expects_only_stdout_from_scripts_without_a_stderr_verify() {
+        assert_eq!(
+            forbid_unverified(&[
+                script("logs", OutputExpectation::Any),
+                verify(Some("logs"), Stream::StdOut),
+            ]),
+            vec![
+                script("logs", OutputExpectation::StdOut),
+                verify(Some("logs"), Stream::StdOut),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_scripts_with_a_stderr_verify_unchanged() {
+        let actions = vec![
+            script("named", OutputExpectation::Any),
+            script("unnamed", OutputExpectation::Any),
+            verify(None, Stream::StdErr),
+            verify(Some("named"), Stream::StdErr),
+        ];
+
+        assert_eq!(forbid_unverified(&actions), actions);
+    }
+
+    #[test]
+    fn leaves_scripts_with_a_combined_output_verify_unchanged() {
+        let actions = vec![
+            script("build", OutputExpectation::Any),
+            verify(Some("build"), Stream::Output),
+        ];
+
+        assert_eq!(forbid_unverified(&actions), actions);
+    }
+
+    #[test]
+    fn leaves_scripts_with_an_explicit_output_expectation_unchanged() {
+        let actions = vec![
+            script("quiet", OutputExpectation::None),
+            script("errors", OutputExpectation::StdErr),
+        ];
+
+        assert_eq!(forbid_unverified(&actions), actions);
+    }
+}
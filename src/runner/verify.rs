@@ -1,13 +1,30 @@
 use crate::ansi::strip_ansi_escape_chars;
-use crate::results::{ActionResult, VerifyResult};
-use crate::runner::state::ScriptOutput;
-use crate::types::{Source, Stream, VerifyAction};
+use crate::results::{
+    ActionResult, Displayed, ScriptResult, VerifyFailure, VerifyFileResult, VerifyResult,
+};
+use crate::runner::state::{PathMasks, ScriptOutput, Variables};
+use crate::types::{
+    Case, Encoding, FileMode, Matcher, OutputRange, Rendering, ScriptCode, ScriptName, Source,
+    Stream, StreamUsage, Substitution, VerifyAction, VerifyFileAction, VerifyOptions, VerifyValue,
+};
+use regex::Regex;
+use std::fs;
+use std::path::Path;
 
-use super::Error;
+use super::{binary, encoding, matchers, path_mask, template, Error, ExecutionOptions, Executor};
 
-pub fn run(action: &VerifyAction, script_output: &dyn ScriptOutput) -> Result<ActionResult, Error> {
+pub fn run(
+    action: &VerifyAction,
+    script_output: &dyn ScriptOutput,
+    variables: &dyn Variables,
+    path_masks: &dyn PathMasks,
+    executor: &dyn Executor,
+) -> Result<ActionResult, Error> {
+    let action = &render_expected_value(action, variables)?;
     let Source { name, stream } = action.source.clone();
 
+    check_source_script_is_available(name.as_ref(), script_output)?;
+
     let result = name
         .as_ref()
         .map(|script_name| script_output.get_result(&String::from(script_name)))
@@ -16,35 +33,421 @@ pub fn run(action: &VerifyAction, script_output: &dyn ScriptOutput) -> Result<Ac
 
     let script_name = result.and_then(|r| r.action.script_name.clone());
 
-    result
+    if let (Some(variable), Some(result)) = (&action.options.env_changed, result) {
+        return check_environment(action.with_script_name(script_name), variable, result)
+            .map(ActionResult::Verify);
+    }
+
+    if let (Some(stream_usage), Some(result)) = (&action.options.stream_usage, result) {
+        return Ok(ActionResult::Verify(check_stream_usage(
+            action.with_script_name(script_name),
+            stream_usage,
+            result,
+        )));
+    }
+
+    let (got, raw_got) = result
         .map(|result| match stream {
-            Stream::StdErr => result.stderr.clone(),
-            Stream::StdOut => result.stdout.clone(),
-        })
-        .map(|got| {
-            ActionResult::Verify(VerifyResult {
-                action: action.with_script_name(script_name),
-                got: strip_ansi_escape_chars(&got),
-            })
+            Stream::StdErr => (result.stderr.clone(), &result.raw_output.stderr),
+            Stream::Output => (result.output.clone(), &result.raw_output.output),
+            Stream::StdOut => (result.stdout.clone(), &result.raw_output.stdout),
         })
         .ok_or(Error::ScriptOutputMissing {
             missing_script_name: name.map_or("<unnamed>".to_string(), String::from),
+        })?;
+    let got = if action.options.output_range == OutputRange::SinceLast {
+        since(
+            &got,
+            script_output.verified_length(script_name.as_ref(), &stream),
+        )
+    } else {
+        got
+    };
+
+    let got = strip_ansi_escape_chars(&got);
+    let got = if action.options.mask_paths {
+        path_mask::mask(&got, path_masks.path_masks())
+    } else {
+        got
+    };
+    let got = substitute(&action.options.substitutions, got);
+    let (action, got) = match (&action.options.normalize_with, &action.options.encoding) {
+        (Some(command), None) => (
+            map_expected_values(action, |VerifyValue(expected)| {
+                normalize(executor, command, expected).map(VerifyValue)
+            })?,
+            normalize(executor, command, &got)?,
+        ),
+        _ => (action.clone(), got),
+    };
+    let action = &action;
+    let failure = match &action.options.encoding {
+        Some(Encoding::Base64) => binary::compare(&action.expected_value, raw_got)?,
+        Some(expected_encoding) => encoding::check(expected_encoding, raw_got),
+        None if action.options.alternatives.is_empty() => {
+            compare(&action.options, &action.expected_value, &got)
+        }
+        None => compare_alternatives(action, &got),
+    };
+    let displayed = match (&action.options.display, &action.options.encoding) {
+        (Some(command), None) => Some(transform_for_display(executor, command, action, &got)),
+        _ => None,
+    };
+    let result = VerifyResult {
+        action: action.with_script_name(script_name),
+        got,
+        failure,
+        displayed,
+    };
+    trace_comparison(&result);
+    Ok(ActionResult::Verify(result))
+}
+
+pub fn run_file(
+    action: &VerifyFileAction,
+    variables: &dyn Variables,
+) -> Result<ActionResult, Error> {
+    let expected_value = if action.options.rendering == Rendering::Raw {
+        action.expected_value.clone()
+    } else {
+        let VerifyValue(template) = &action.expected_value;
+        VerifyValue(template::render(template, variables)?)
+    };
+    let mut action = VerifyFileAction {
+        expected_value,
+        ..action.clone()
+    };
+
+    if let Some(exists) = action.exists {
+        return Ok(ActionResult::VerifyFile(check_file_exists(action, exists)));
+    }
+
+    // The content is also checked when the code block is not empty
+    if let Some(mode) = action.mode {
+        let result = check_file_mode(action, mode);
+        if result.failure.is_some() || result.action.expected_value.0.is_empty() {
+            return Ok(ActionResult::VerifyFile(result));
+        }
+        action = result.action;
+    }
+
+    let result = match fs::read_to_string(String::from(action.file_path.clone())) {
+        Ok(got) => {
+            let got = substitute(&action.options.substitutions, got);
+            VerifyFileResult {
+                failure: compare(&action.options, &action.expected_value, &got),
+                action,
+                got,
+            }
+        }
+        Err(_) => VerifyFileResult {
+            action,
+            got: String::new(),
+            failure: Some(VerifyFailure::FileMissing),
+        },
+    };
+
+    Ok(ActionResult::VerifyFile(result))
+}
+
+fn check_file_exists(action: VerifyFileAction, expected: bool) -> VerifyFileResult {
+    let failure = match (Path::new(&action.file_path.0).exists(), expected) {
+        (true, false) => Some(VerifyFailure::FileExists),
+        (false, true) => Some(VerifyFailure::FileMissing),
+        _ => None,
+    };
+
+    VerifyFileResult {
+        action,
+        got: String::new(),
+        failure,
+    }
+}
+
+fn check_file_mode(action: VerifyFileAction, expected: FileMode) -> VerifyFileResult {
+    let failure = match file_mode(Path::new(&action.file_path.0)) {
+        Ok(got) if got == expected => None,
+        Ok(got) => Some(VerifyFailure::ModeDoesNotMatch { expected, got }),
+        Err(_) => Some(VerifyFailure::FileMissing),
+    };
+
+    VerifyFileResult {
+        action,
+        got: String::new(),
+        failure,
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> std::io::Result<FileMode> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).map(|metadata| FileMode(metadata.permissions().mode() & 0o7777))
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> std::io::Result<FileMode> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "file modes are only supported on Unix",
+    ))
+}
+
+fn check_source_script_is_available(
+    name: Option<&ScriptName>,
+    script_output: &dyn ScriptOutput,
+) -> Result<(), Error> {
+    let is_unavailable = name.map_or_else(
+        || script_output.is_last_script_unavailable(),
+        |script_name| script_output.is_script_unavailable(&String::from(script_name)),
+    );
+
+    if is_unavailable {
+        Err(Error::SourceScriptFailed {
+            script_name: name.map_or("<unnamed>".to_string(), String::from),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn render_expected_value(
+    action: &VerifyAction,
+    variables: &dyn Variables,
+) -> Result<VerifyAction, Error> {
+    if action.options.rendering == Rendering::Raw {
+        return Ok(action.clone());
+    }
+
+    map_expected_values(action, |VerifyValue(template)| {
+        template::render(template, variables).map(VerifyValue)
+    })
+}
+
+fn check_stream_usage(
+    action: VerifyAction,
+    stream_usage: &StreamUsage,
+    result: &ScriptResult,
+) -> VerifyResult {
+    let (used, unexpected_stream, unexpected) = match stream_usage {
+        StreamUsage::StdOutOnly => (&result.stdout, Stream::StdErr, &result.stderr),
+        StreamUsage::StdErrOnly => (&result.stderr, Stream::StdOut, &result.stdout),
+    };
+
+    if unexpected.is_empty() {
+        VerifyResult {
+            action,
+            got: used.clone(),
+            failure: None,
+            displayed: None,
+        }
+    } else {
+        VerifyResult {
+            action,
+            got: unexpected.clone(),
+            failure: Some(VerifyFailure::UnexpectedStream {
+                stream: unexpected_stream,
+            }),
+            displayed: None,
+        }
+    }
+}
+
+fn check_environment(
+    action: VerifyAction,
+    variable: &str,
+    result: &ScriptResult,
+) -> Result<VerifyResult, Error> {
+    let environment = result
+        .environment
+        .as_ref()
+        .ok_or_else(|| Error::EnvironmentNotCaptured {
+            script_name: result
+                .action
+                .script_name
+                .clone()
+                .map_or("<unnamed>".to_string(), String::from),
+        })?;
+    let before = environment.before.get(variable).cloned();
+    let after = environment.after.get(variable).cloned();
+
+    // An empty block only checks that the variable changed
+    let VerifyValue(expected) = &action.expected_value;
+    let expected = expected.strip_suffix('\n').unwrap_or(expected);
+    let matches = before != after && (expected.is_empty() || after.as_deref() == Some(expected));
+
+    Ok(VerifyResult {
+        got: after.clone().unwrap_or_default(),
+        failure: (!matches).then(|| VerifyFailure::EnvironmentDoesNotMatch {
+            name: variable.to_string(),
+            before,
+            after,
+        }),
+        action,
+        displayed: None,
+    })
+}
+
+fn map_expected_values<F>(action: &VerifyAction, f: F) -> Result<VerifyAction, Error>
+where
+    F: Fn(&VerifyValue) -> Result<VerifyValue, Error>,
+{
+    let mut mapped = action.with_expected_value(f(&action.expected_value)?);
+    mapped.options.alternatives = action
+        .options
+        .alternatives
+        .iter()
+        .map(f)
+        .collect::<Result<_, _>>()?;
+    Ok(mapped)
+}
+
+fn since(got: &str, offset: usize) -> String {
+    // The output is compared in full if it was truncated since the last verify
+    got.get(offset..).unwrap_or(got).to_string()
+}
+
+fn substitute(substitutions: &[Substitution], got: String) -> String {
+    substitutions.iter().fold(got, |got, substitution| {
+        Regex::new(&substitution.pattern)
+            .expect("substitution patterns are validated by the parser")
+            .replace_all(&got, &substitution.replacement[..])
+            .into_owned()
+    })
+}
+
+fn normalize(executor: &dyn Executor, command: &str, value: &str) -> Result<String, Error> {
+    pipe_through(executor, command, value).map_err(|message| Error::NormalizerFailed {
+        command: command.to_string(),
+        message,
+    })
+}
+
+fn transform_for_display(
+    executor: &dyn Executor,
+    command: &str,
+    action: &VerifyAction,
+    got: &str,
+) -> Displayed {
+    let expected = String::from(action.expected_value.clone());
+    pipe_through(executor, command, &expected)
+        .and_then(|expected| {
+            pipe_through(executor, command, got).map(|got| Displayed::Transformed { expected, got })
         })
+        .unwrap_or_else(|message| Displayed::TransformFailed {
+            command: command.to_string(),
+            message,
+        })
+}
+
+fn pipe_through(executor: &dyn Executor, command: &str, value: &str) -> Result<String, String> {
+    let output = executor
+        .execute(
+            &ScriptCode(command.to_string()),
+            &ExecutionOptions {
+                stdin: Some(value.to_string()),
+                ..ExecutionOptions::default()
+            },
+        )
+        .map_err(|err| match err {
+            Error::CommandFailed { message, .. } => message,
+            err => format!("{err:?}"),
+        })?;
+
+    let exit_code = match output.exit_code {
+        Some(0) => return Ok(output.stdout),
+        exit_code => exit_code.map_or("<none>".to_string(), |code| code.to_string()),
+    };
+
+    match output.stderr.trim_end() {
+        "" => Err(format!("exited {exit_code}")),
+        stderr => Err(format!("exited {exit_code}: {stderr}")),
+    }
+}
+
+fn compare_alternatives(action: &VerifyAction, got: &str) -> Option<VerifyFailure> {
+    let matches_any = std::iter::once(&action.expected_value)
+        .chain(&action.options.alternatives)
+        .any(|expected| compare(&action.options, expected, got).is_none());
+
+    if matches_any {
+        None
+    } else {
+        Some(VerifyFailure::NoAlternativeMatched)
+    }
+}
+
+fn compare(options: &VerifyOptions, expected: &VerifyValue, got: &str) -> Option<VerifyFailure> {
+    let VerifyValue(expected) = expected;
+    let expected = strip_ansi_escape_chars(expected);
+
+    let expected = options.trim_whitespace(&expected);
+    let got = options.trim_whitespace(got);
+
+    match (&options.matcher, options.case) {
+        (Matcher::Regex, Case::Insensitive) => {
+            matchers::compare(&options.matcher, &format!("(?i){expected}"), &got)
+        }
+        (matcher, Case::Insensitive) => {
+            matchers::compare(matcher, &expected.to_lowercase(), &got.to_lowercase())
+        }
+        (matcher, Case::Sensitive) => matchers::compare(matcher, &expected, &got),
+    }
+}
+
+fn trace_comparison(result: &VerifyResult) {
+    let VerifyValue(expected) = &result.action.expected_value;
+
+    log::trace!(
+        "comparing {} from '{}': expected {} bytes, got {} bytes ({})",
+        match result.action.source.stream {
+            Stream::StdOut => "stdout",
+            Stream::StdErr => "stderr",
+            Stream::Output => "output",
+        },
+        result
+            .action
+            .source
+            .name
+            .clone()
+            .map_or("<unnamed>".to_string(), Into::into),
+        expected.len(),
+        result.got.len(),
+        if result.failure.is_none() {
+            "matched"
+        } else {
+            "did not match"
+        }
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{run, ActionResult, Error, ScriptOutput};
-    use crate::results::ScriptResult;
-    use crate::types::{OutputExpectation, ScriptAction, ScriptCode, ScriptName};
+    use super::{
+        run, ActionResult, Error, ExecutionOptions, Executor, PathMasks, ScriptOutput, Variables,
+    };
+    use crate::results::{EnvironmentSnapshot, RawOutput, ScriptResult};
+    use crate::runner::executor::Output;
+    use crate::types::{
+        OutputExpectation, ScriptAction, ScriptCode, ScriptName, ScriptOptions, Stream,
+    };
+    use std::collections::HashMap;
 
     struct MockScriptOutput {
         result: Option<ScriptResult>,
+        unavailable_script: Option<String>,
+        variables: HashMap<String, String>,
+        path_masks: Vec<(String, String)>,
     }
 
     impl MockScriptOutput {
-        const fn without_result() -> Self {
-            Self { result: None }
+        fn without_result() -> Self {
+            Self {
+                result: None,
+                unavailable_script: None,
+                variables: HashMap::new(),
+                path_masks: Vec::new(),
+            }
         }
 
         fn with_result(name: &str, stdout: &str, stderr: &str) -> Self {
@@ -55,12 +458,39 @@ mod tests {
                         script_code: ScriptCode(String::new()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::Any,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: stdout.to_string(),
                     stderr: stderr.to_string(),
+                    output: format!("{stdout}{stderr}"),
+                    raw_output: RawOutput {
+                        stdout: stdout.as_bytes().to_vec(),
+                        stderr: stderr.as_bytes().to_vec(),
+                        output: format!("{stdout}{stderr}").into_bytes(),
+                    },
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 }),
+                unavailable_script: None,
+                variables: HashMap::new(),
+                path_masks: Vec::new(),
+            }
+        }
+
+        fn with_raw_stdout(name: &str, stdout: &[u8]) -> Self {
+            let mut script_output = Self::with_result(name, "", "");
+            if let Some(result) = script_output.result.as_mut() {
+                result.stdout = String::from_utf8_lossy(stdout).to_string();
+                result.raw_output.stdout = stdout.to_vec();
             }
+            script_output
         }
 
         fn with_unnamed_result(stdout: &str, stderr: &str) -> Self {
@@ -71,11 +501,29 @@ mod tests {
                         script_code: ScriptCode(String::new()),
                         expected_exit_code: None,
                         expected_output: OutputExpectation::Any,
+                        options: ScriptOptions::default(),
                     },
                     exit_code: None,
                     stdout: stdout.to_string(),
                     stderr: stderr.to_string(),
+                    output: format!("{stdout}{stderr}"),
+                    raw_output: RawOutput {
+                        stdout: stdout.as_bytes().to_vec(),
+                        stderr: stderr.as_bytes().to_vec(),
+                        output: format!("{stdout}{stderr}").into_bytes(),
+                    },
+                    durations: vec![],
+                    idempotency_failure: None,
+                    stability_failure: None,
+                    workdir: None,
+                    pid: None,
+                    signal: None,
+                    environment: None,
+                    attempts: 1,
                 }),
+                unavailable_script: None,
+                variables: HashMap::new(),
+                path_masks: Vec::new(),
             }
         }
     }
@@ -96,13 +544,101 @@ mod tests {
         fn get_last_result(&self) -> Option<&ScriptResult> {
             self.result.as_ref()
         }
+
+        fn is_script_unavailable(&self, name: &str) -> bool {
+            self.unavailable_script.as_deref() == Some(name)
+        }
+
+        fn is_last_script_unavailable(&self) -> bool {
+            self.unavailable_script.is_some()
+        }
+
+        fn verified_length(&self, _name: Option<&ScriptName>, _stream: &Stream) -> usize {
+            0
+        }
+    }
+
+    impl MockScriptOutput {
+        fn with_unavailable_script(name: &str) -> Self {
+            Self {
+                result: None,
+                unavailable_script: Some(name.to_string()),
+                variables: HashMap::new(),
+                path_masks: Vec::new(),
+            }
+        }
+
+        fn with_path_mask(mut self, token: &str, path: &str) -> Self {
+            self.path_masks.push((token.to_string(), path.to_string()));
+            self
+        }
+
+        fn with_environment(mut self, before: &[(&str, &str)], after: &[(&str, &str)]) -> Self {
+            let to_map = |variables: &[(&str, &str)]| {
+                variables
+                    .iter()
+                    .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                    .collect()
+            };
+            if let Some(result) = self.result.as_mut() {
+                result.environment = Some(Box::new(EnvironmentSnapshot {
+                    before: to_map(before),
+                    after: to_map(after),
+                }));
+            }
+            self
+        }
+
+        fn with_variable(mut self, name: &str, value: &str) -> Self {
+            self.variables.insert(name.to_string(), value.to_string());
+            self
+        }
+    }
+
+    impl Variables for MockScriptOutput {
+        fn get_variable(&self, name: &str) -> Option<&String> {
+            self.variables.get(name)
+        }
+    }
+
+    impl PathMasks for MockScriptOutput {
+        fn path_masks(&self) -> &[(String, String)] {
+            &self.path_masks
+        }
+    }
+
+    impl Executor for MockScriptOutput {
+        fn execute(
+            &self,
+            script: &ScriptCode,
+            options: &ExecutionOptions,
+        ) -> Result<Output, Error> {
+            let input = options.stdin.clone().unwrap_or_default();
+            match &script.0[..] {
+                "sort" => {
+                    let mut lines: Vec<&str> = input.lines().collect();
+                    lines.sort_unstable();
+                    let sorted = format!("{}\n", lines.join("\n"));
+                    Ok(Output::from_bytes(sorted.into_bytes(), vec![], Some(0)))
+                }
+                _ => Ok(Output::from_bytes(
+                    vec![],
+                    b"not found\n".to_vec(),
+                    Some(127),
+                )),
+            }
+        }
     }
 
     mod test {
-        use crate::results::VerifyResult;
-        use crate::types::{ScriptName, Source, Stream, VerifyAction, VerifyValue};
+        use crate::results::{Displayed, VerifyFailure, VerifyResult};
+        use crate::types::{
+            Case, Encoding, Matcher, OutputRange, Rendering, ScriptName, Source, Stream,
+            StreamUsage, Substitution, VerifyAction, VerifyOptions, VerifyValue, Whitespace,
+        };
 
         use super::{run, ActionResult, Error, MockScriptOutput};
+        use crate::runner::State;
 
         #[test]
         fn returns_result_for_stdout_verification_with_unnamed_script() {
@@ -116,13 +652,22 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action,
                     got: "hello world".to_string(),
+                    failure: None,
+                    displayed: None,
                 }))
             );
         }
@@ -139,13 +684,22 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action: action.with_script_name(Some(ScriptName("example_script".to_string()))),
                     got: "hello world".to_string(),
+                    failure: None,
+                    displayed: None,
                 }))
             );
         }
@@ -162,13 +716,53 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action: action.with_script_name(Some(ScriptName("example_script".to_string()))),
                     got: "hello world".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn returns_result_for_combined_output_verification() {
+            let source = Source {
+                name: Some(ScriptName("my_script".to_string())),
+                stream: Stream::Output,
+            };
+            let script_output = MockScriptOutput::with_result("my_script", "out\n", "err\n");
+
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("out\nerr\n".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "out\nerr\n".to_string(),
+                    failure: None,
+                    displayed: None,
                 }))
             );
         }
@@ -185,13 +779,22 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action,
                     got: "hello world".to_string(),
+                    failure: None,
+                    displayed: None,
                 }))
             );
         }
@@ -209,13 +812,22 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action,
                     got: "error message".to_string(),
+                    failure: None,
+                    displayed: None,
                 }))
             );
         }
@@ -231,10 +843,17 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Err(Error::ScriptOutputMissing {
                     missing_script_name: "missing_script".to_string()
                 })
@@ -251,16 +870,77 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Err(Error::ScriptOutputMissing {
                     missing_script_name: "<unnamed>".to_string()
                 })
             );
         }
 
+        #[test]
+        fn returns_error_when_source_script_failed_to_run() {
+            let source = Source {
+                name: Some(ScriptName("broken_script".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_unavailable_script("broken_script");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("output".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Err(Error::SourceScriptFailed {
+                    script_name: "broken_script".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn returns_error_when_unnamed_source_script_failed_to_run() {
+            let source = Source {
+                name: None,
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_unavailable_script("broken_script");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("output".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Err(Error::SourceScriptFailed {
+                    script_name: "<unnamed>".to_string()
+                })
+            );
+        }
+
         #[test]
         fn ignore_ansi_escape_characters_in_output_and_verify_value() {
             let source = Source {
@@ -273,13 +953,1095 @@ mod tests {
             let action = VerifyAction {
                 source,
                 expected_value: verify_value,
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
-                run(&action, &script_output),
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
                 Ok(ActionResult::Verify(VerifyResult {
                     action,
                     got: "This is coloured".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn renders_variables_in_the_expected_value() {
+            let source = Source {
+                name: Some(ScriptName("greeting".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("greeting", "Hello World!", "")
+                .with_variable("NAME", "World");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("Hello ${NAME}!".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.with_expected_value(VerifyValue("Hello World!".to_string())),
+                    got: "Hello World!".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn does_not_render_variables_when_raw() {
+            let source = Source {
+                name: Some(ScriptName("greeting".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("greeting", "Hello ${NAME}!", "")
+                .with_variable("NAME", "World");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("Hello ${NAME}!".to_string()),
+                options: VerifyOptions {
+                    rendering: Rendering::Raw,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "Hello ${NAME}!".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn masks_paths_in_the_output_when_mask_paths_is_enabled() {
+            let source = Source {
+                name: Some(ScriptName("home".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output =
+                MockScriptOutput::with_result("home", "Config: /home/alice/.config/app\n", "")
+                    .with_path_mask("<HOME>", "/home/alice");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("Config: <HOME>/.config/app\n".to_string()),
+                options: VerifyOptions {
+                    mask_paths: true,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "Config: <HOME>/.config/app\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn does_not_mask_paths_in_the_output_by_default() {
+            let source = Source {
+                name: Some(ScriptName("home".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output =
+                MockScriptOutput::with_result("home", "Config: /home/alice/.config/app\n", "")
+                    .with_path_mask("<HOME>", "/home/alice");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("Config: <HOME>/.config/app\n".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "Config: /home/alice/.config/app\n".to_string(),
+                    failure: Some(VerifyFailure::OutputDoesNotMatch),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_the_output_is_in_the_expected_encoding() {
+            let source = Source {
+                name: Some(ScriptName("encoded".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output =
+                MockScriptOutput::with_raw_stdout("encoded", "caf\u{e9}".as_bytes());
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue(String::new()),
+                options: VerifyOptions {
+                    encoding: Some(Encoding::Utf8),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "caf\u{e9}".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_when_the_output_is_not_in_the_expected_encoding() {
+            let source = Source {
+                name: Some(ScriptName("encoded".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_raw_stdout("encoded", b"caf\xe9");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue(String::new()),
+                options: VerifyOptions {
+                    encoding: Some(Encoding::Utf8),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "caf\u{fffd}".to_string(),
+                    failure: Some(VerifyFailure::InvalidEncoding {
+                        reason: "invalid UTF-8 at byte offset 3".to_string()
+                    }),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_the_output_matches_the_base64_expected_value() {
+            let source = Source {
+                name: Some(ScriptName("binary".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_raw_stdout("binary", b"\xffab\xfe");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("/2Fi/g==\n".to_string()),
+                options: VerifyOptions {
+                    encoding: Some(Encoding::Base64),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "\u{fffd}ab\u{fffd}".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_with_the_offset_of_the_first_byte_which_differs_from_the_base64_expected_value() {
+            let source = Source {
+                name: Some(ScriptName("binary".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_raw_stdout("binary", b"\xffac\xfe");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("/2Fi/g==\n".to_string()),
+                options: VerifyOptions {
+                    encoding: Some(Encoding::Base64),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "\u{fffd}ac\u{fffd}".to_string(),
+                    failure: Some(VerifyFailure::BytesDoNotMatch { offset: 2 }),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn ignores_trailing_whitespace_when_trim_trailing_whitespace_is_enabled() {
+            let source = Source {
+                name: Some(ScriptName("list".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("list", "apple  \nbanana", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("apple\nbanana\n".to_string()),
+                options: VerifyOptions {
+                    whitespace: Whitespace::TrimTrailing,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "apple  \nbanana".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn ignores_case_when_case_sensitive_is_disabled() {
+            let source = Source {
+                name: Some(ScriptName("drive".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("drive", "C:\\Users\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("c:\\users\n".to_string()),
+                options: VerifyOptions {
+                    case: Case::Insensitive,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "C:\\Users\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn matches_a_regex_ignoring_case_when_case_sensitive_is_disabled() {
+            let source = Source {
+                name: Some(ScriptName("drive".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output =
+                MockScriptOutput::with_result("drive", "Content-Type: text/html\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("^content-type: \\S+$\n".to_string()),
+                options: VerifyOptions {
+                    matcher: Matcher::Regex,
+                    case: Case::Insensitive,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "Content-Type: text/html\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_the_output_matches_one_of_the_alternatives() {
+            let source = Source {
+                name: Some(ScriptName("version".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("version", "tool 2.x\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("tool 1.x\n".to_string()),
+                options: VerifyOptions {
+                    any_of: true,
+                    alternatives: vec![
+                        VerifyValue("tool 2.x\n".to_string()),
+                        VerifyValue("tool 3.x\n".to_string()),
+                    ],
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "tool 2.x\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_when_the_output_matches_none_of_the_alternatives() {
+            let source = Source {
+                name: Some(ScriptName("version".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("version", "tool 4.x\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("tool 1.x\n".to_string()),
+                options: VerifyOptions {
+                    any_of: true,
+                    alternatives: vec![
+                        VerifyValue("tool 2.x\n".to_string()),
+                        VerifyValue("tool 3.x\n".to_string()),
+                    ],
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "tool 4.x\n".to_string(),
+                    failure: Some(VerifyFailure::NoAlternativeMatched),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_the_output_matches_after_substituting_the_timestamp() {
+            let source = Source {
+                name: Some(ScriptName("report".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output =
+                MockScriptOutput::with_result("report", "generated at 2024-01-31T09:30:00Z\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("generated at <TIMESTAMP>\n".to_string()),
+                options: VerifyOptions {
+                    substitutions: vec![Substitution {
+                        pattern: "[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9:]+Z".to_string(),
+                        replacement: "<TIMESTAMP>".to_string(),
+                    }],
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "generated at <TIMESTAMP>\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_with_the_substituted_output_when_it_does_not_match() {
+            let source = Source {
+                name: Some(ScriptName("report".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result(
+                "report",
+                "generated at 2024-01-31T09:30:00Z by bob\n",
+                "",
+            );
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("generated at <TIMESTAMP>\n".to_string()),
+                options: VerifyOptions {
+                    substitutions: vec![Substitution {
+                        pattern: "[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9:]+Z".to_string(),
+                        replacement: "<TIMESTAMP>".to_string(),
+                    }],
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action,
+                    got: "generated at <TIMESTAMP> by bob\n".to_string(),
+                    failure: Some(VerifyFailure::OutputDoesNotMatch),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_the_normalized_output_matches_the_normalized_expected_value() {
+            let source = Source {
+                name: Some(ScriptName("unordered".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("unordered", "b\nc\na\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("c\na\nb\n".to_string()),
+                options: VerifyOptions {
+                    normalize_with: Some("sort".to_string()),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.with_expected_value(VerifyValue("a\nb\nc\n".to_string())),
+                    got: "a\nb\nc\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn compares_the_raw_output_when_it_is_transformed_for_display() {
+            let source = Source {
+                name: Some(ScriptName("unordered".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("unordered", "b\na\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("a\nb\n".to_string()),
+                options: VerifyOptions {
+                    display: Some("sort".to_string()),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "b\na\n".to_string(),
+                    failure: Some(VerifyFailure::OutputDoesNotMatch),
+                    displayed: Some(Displayed::Transformed {
+                        expected: "a\nb\n".to_string(),
+                        got: "a\nb\n".to_string(),
+                    }),
+                }))
+            );
+        }
+
+        #[test]
+        fn keeps_the_result_when_the_display_transform_fails() {
+            let source = Source {
+                name: Some(ScriptName("unordered".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("unordered", "a\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("a\n".to_string()),
+                options: VerifyOptions {
+                    display: Some("srot".to_string()),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "a\n".to_string(),
+                    failure: None,
+                    displayed: Some(Displayed::TransformFailed {
+                        command: "srot".to_string(),
+                        message: "exited 127: not found".to_string(),
+                    }),
+                }))
+            );
+        }
+
+        #[test]
+        fn succeeds_when_a_stdout_only_script_wrote_nothing_to_stderr() {
+            let script_output = MockScriptOutput::with_result("clean", "result\n", "");
+            let action = VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("clean".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue(String::new()),
+                options: VerifyOptions {
+                    stream_usage: Some(StreamUsage::StdOutOnly),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "result\n".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_with_the_unexpected_stream_when_a_script_wrote_to_both_streams() {
+            let script_output =
+                MockScriptOutput::with_result("noisy", "result\n", "debug: connecting\n");
+            let action = VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("noisy".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue(String::new()),
+                options: VerifyOptions {
+                    stream_usage: Some(StreamUsage::StdErrOnly),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "result\n".to_string(),
+                    failure: Some(VerifyFailure::UnexpectedStream {
+                        stream: Stream::StdOut
+                    }),
+                    displayed: None,
+                }))
+            );
+        }
+
+        fn env_action(variable: &str, expected: &str) -> VerifyAction {
+            VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("configure".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue(expected.to_string()),
+                options: VerifyOptions {
+                    env_changed: Some(variable.to_string()),
+                    ..VerifyOptions::default()
+                },
+            }
+        }
+
+        #[test]
+        fn succeeds_when_the_script_exported_the_expected_value() {
+            let script_output = MockScriptOutput::with_result("configure", "", "")
+                .with_environment(&[], &[("GREETING", "hello")]);
+            let action = env_action("GREETING", "hello\n");
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "hello".to_string(),
+                    failure: None,
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_with_both_values_when_the_variable_did_not_change() {
+            let script_output = MockScriptOutput::with_result("configure", "", "")
+                .with_environment(&[("GREETING", "hello")], &[("GREETING", "hello")]);
+            let action = env_action("GREETING", "");
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Ok(ActionResult::Verify(VerifyResult {
+                    action: action.clone(),
+                    got: "hello".to_string(),
+                    failure: Some(VerifyFailure::EnvironmentDoesNotMatch {
+                        name: "GREETING".to_string(),
+                        before: Some("hello".to_string()),
+                        after: Some("hello".to_string()),
+                    }),
+                    displayed: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn returns_error_when_the_environment_was_not_captured() {
+            let script_output = MockScriptOutput::with_result("configure", "", "");
+
+            assert_eq!(
+                run(
+                    &env_action("GREETING", ""),
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Err(Error::EnvironmentNotCaptured {
+                    script_name: "configure".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn successive_since_last_verifies_only_compare_the_newly_appended_output() {
+            let script_result = |stdout: &str| {
+                let script_output = MockScriptOutput::with_result("log", stdout, "");
+                ActionResult::Script(script_output.result.expect("a script result"))
+            };
+            let action = |expected: &str| VerifyAction {
+                source: Source {
+                    name: Some(ScriptName("log".to_string())),
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue(expected.to_string()),
+                options: VerifyOptions {
+                    output_range: OutputRange::SinceLast,
+                    ..VerifyOptions::default()
+                },
+            };
+            let executor = MockScriptOutput::without_result();
+            let mut state = State::new();
+
+            state.add_result(&script_result("started\n"));
+            let first = run(&action("started\n"), &state, &state, &state, &executor)
+                .expect("the first verify to run");
+            assert!(first.success());
+            state.add_result(&first);
+
+            state.add_result(&script_result("started\nready\n"));
+            let second = run(&action("ready\n"), &state, &state, &state, &executor)
+                .expect("the second verify to run");
+            assert!(second.success());
+            state.add_result(&second);
+
+            state.add_result(&script_result("started\nready\nstopped\n"));
+            let ActionResult::Verify(third) =
+                run(&action("ready\n"), &state, &state, &state, &executor)
+                    .expect("the third verify to run")
+            else {
+                panic!("Expected a verify result");
+            };
+            assert_eq!(third.got, "stopped\n");
+            assert_eq!(third.failure, Some(VerifyFailure::OutputDoesNotMatch));
+        }
+
+        #[test]
+        fn returns_error_when_the_normalizer_fails() {
+            let source = Source {
+                name: Some(ScriptName("unordered".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("unordered", "b\na\n", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("a\nb\n".to_string()),
+                options: VerifyOptions {
+                    normalize_with: Some("srot".to_string()),
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Err(Error::NormalizerFailed {
+                    command: "srot".to_string(),
+                    message: "exited 127: not found".to_string(),
+                })
+            );
+        }
+
+        #[test]
+        fn returns_error_when_the_expected_value_contains_an_unknown_variable() {
+            let source = Source {
+                name: Some(ScriptName("greeting".to_string())),
+                stream: Stream::StdOut,
+            };
+            let script_output = MockScriptOutput::with_result("greeting", "Hello World!", "");
+            let action = VerifyAction {
+                source,
+                expected_value: VerifyValue("Hello ${NAME}!".to_string()),
+                options: VerifyOptions::default(),
+            };
+
+            assert_eq!(
+                run(
+                    &action,
+                    &script_output,
+                    &script_output,
+                    &script_output,
+                    &script_output
+                ),
+                Err(Error::UnknownVariable {
+                    variable_name: "NAME".to_string()
+                })
+            );
+        }
+    }
+
+    mod file {
+        use crate::results::{VerifyFailure, VerifyFileResult};
+        use crate::types::{
+            FileMode, FilePath, Matcher, VerifyFileAction, VerifyOptions, VerifyValue,
+        };
+
+        use super::super::run_file;
+        use super::{ActionResult, MockScriptOutput};
+
+        fn action(file_path: &str, pattern: &str) -> VerifyFileAction {
+            VerifyFileAction {
+                file_path: FilePath(file_path.to_string()),
+                expected_value: VerifyValue(format!("{pattern}\n")),
+                mode: None,
+                exists: None,
+                options: VerifyOptions {
+                    matcher: Matcher::Regex,
+                    ..VerifyOptions::default()
+                },
+            }
+        }
+
+        fn config_file() -> (tempfile::TempDir, String) {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let path = directory.path().join("nginx.conf");
+            std::fs::write(&path, "server {\n    listen 80;\n}\n").expect("Failed to write file");
+            (directory, path.display().to_string())
+        }
+
+        #[test]
+        fn succeeds_when_the_file_matches_the_pattern() {
+            let (_directory, path) = config_file();
+            let action = action(&path, "^\\s+listen 80;$");
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: "server {\n    listen 80;\n}\n".to_string(),
+                    failure: None,
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_when_the_file_does_not_match_the_pattern() {
+            let (_directory, path) = config_file();
+            let action = action(&path, "listen 443");
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: "server {\n    listen 80;\n}\n".to_string(),
+                    failure: Some(VerifyFailure::MatcherFailed {
+                        reason: "no match for the pattern `listen 443`".to_string()
+                    }),
+                }))
+            );
+        }
+
+        #[test]
+        fn fails_distinctly_when_the_file_does_not_exist() {
+            let directory = tempfile::tempdir().expect("Failed to create a temporary directory");
+            let path = directory.path().join("missing.conf").display().to_string();
+            let action = action(&path, "listen");
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: String::new(),
+                    failure: Some(VerifyFailure::FileMissing),
+                }))
+            );
+        }
+
+        fn exists_action(file_path: &str, exists: bool) -> VerifyFileAction {
+            VerifyFileAction {
+                file_path: FilePath(file_path.to_string()),
+                expected_value: VerifyValue(String::new()),
+                mode: None,
+                exists: Some(exists),
+                options: VerifyOptions::default(),
+            }
+        }
+
+        #[test]
+        fn checks_that_a_file_exists() {
+            let (directory, path) = config_file();
+            let missing = directory.path().join("missing.conf").display().to_string();
+
+            for (path, failure) in [(path, None), (missing, Some(VerifyFailure::FileMissing))] {
+                let action = exists_action(&path, true);
+                assert_eq!(
+                    run_file(&action, &MockScriptOutput::without_result()),
+                    Ok(ActionResult::VerifyFile(VerifyFileResult {
+                        action,
+                        got: String::new(),
+                        failure,
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn checks_that_a_file_does_not_exist() {
+            let (directory, path) = config_file();
+            let missing = directory.path().join("missing.conf").display().to_string();
+
+            for (path, failure) in [(missing, None), (path, Some(VerifyFailure::FileExists))] {
+                let action = exists_action(&path, false);
+                assert_eq!(
+                    run_file(&action, &MockScriptOutput::without_result()),
+                    Ok(ActionResult::VerifyFile(VerifyFileResult {
+                        action,
+                        got: String::new(),
+                        failure,
+                    }))
+                );
+            }
+        }
+
+        #[cfg(unix)]
+        fn mode_action(file_path: &str, mode: u32) -> VerifyFileAction {
+            VerifyFileAction {
+                file_path: FilePath(file_path.to_string()),
+                expected_value: VerifyValue(String::new()),
+                mode: Some(FileMode(mode)),
+                exists: None,
+                options: VerifyOptions::default(),
+            }
+        }
+
+        #[cfg(unix)]
+        fn helper_script(mode: u32) -> (tempfile::TempDir, String) {
+            use std::os::unix::fs::PermissionsExt;
+
+            let (directory, path) = config_file();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+                .expect("Failed to set the mode");
+            (directory, path)
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn succeeds_when_the_file_has_the_expected_mode() {
+            let (_directory, path) = helper_script(0o755);
+            let action = mode_action(&path, 0o755);
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: String::new(),
+                    failure: None,
+                }))
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn compares_the_content_as_well_when_the_mode_matches() {
+            let (_directory, path) = helper_script(0o755);
+            let action = VerifyFileAction {
+                expected_value: VerifyValue("server {}\n".to_string()),
+                ..mode_action(&path, 0o755)
+            };
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: "server {\n    listen 80;\n}\n".to_string(),
+                    failure: Some(VerifyFailure::OutputDoesNotMatch),
+                }))
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn fails_with_both_modes_when_the_file_has_a_different_mode() {
+            let (_directory, path) = helper_script(0o644);
+            let action = mode_action(&path, 0o755);
+
+            assert_eq!(
+                run_file(&action, &MockScriptOutput::without_result()),
+                Ok(ActionResult::VerifyFile(VerifyFileResult {
+                    action,
+                    got: String::new(),
+                    failure: Some(VerifyFailure::ModeDoesNotMatch {
+                        expected: FileMode(0o755),
+                        got: FileMode(0o644),
+                    }),
                 }))
             );
         }
@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::results::{ActionResult, VerifyDirectoryResult};
+use crate::types::{DirectoryExpectation, FilePath, VerifyDirectoryAction};
+
+use super::directory_listing;
+use super::error::Error;
+use super::matchers::glob;
+
+pub fn run(action: &VerifyDirectoryAction) -> Result<ActionResult, Error> {
+    let VerifyDirectoryAction {
+        directory: FilePath(directory),
+        expected,
+    } = action;
+    let directory = Path::new(directory);
+
+    match expected {
+        DirectoryExpectation::Golden(FilePath(golden)) => {
+            compare_with_golden(action, directory, Path::new(golden))
+        }
+        DirectoryExpectation::Tree(paths) => Ok(compare_with_tree(action, directory, paths)),
+    }
+}
+
+fn compare_with_golden(
+    action: &VerifyDirectoryAction,
+    directory: &Path,
+    golden: &Path,
+) -> Result<ActionResult, Error> {
+    if !golden.is_dir() {
+        return Err(Error::RunFailed {
+            message: format!("The golden directory {} does not exist", golden.display()),
+        });
+    }
+
+    let got = directory_listing::list_files(directory);
+    let expected = directory_listing::list_files(golden);
+
+    Ok(ActionResult::VerifyDirectory(VerifyDirectoryResult {
+        action: action.clone(),
+        missing: to_strings(expected.difference(&got)),
+        extra: to_strings(got.difference(&expected)),
+        differing: to_strings(expected.intersection(&got).filter(|file| {
+            fs::read(directory.join(file)).ok() != fs::read(golden.join(file)).ok()
+        })),
+    }))
+}
+
+// Each path must match at least one file and each file must match at least one path
+fn compare_with_tree(
+    action: &VerifyDirectoryAction,
+    directory: &Path,
+    paths: &[String],
+) -> ActionResult {
+    let files: Vec<String> = directory_listing::list_files(directory)
+        .iter()
+        .map(|file| to_tree_path(file))
+        .collect();
+
+    ActionResult::VerifyDirectory(VerifyDirectoryResult {
+        action: action.clone(),
+        missing: paths
+            .iter()
+            .filter(|path| !files.iter().any(|file| glob::matches(path, file)))
+            .cloned()
+            .collect(),
+        extra: files
+            .iter()
+            .filter(|file| !paths.iter().any(|path| glob::matches(path, file)))
+            .cloned()
+            .collect(),
+        differing: vec![],
+    })
+}
+
+// Paths are always listed with `/` so the same tree is expected on every platform
+fn to_tree_path(file: &Path) -> String {
+    file.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn to_strings<'a>(files: impl Iterator<Item = &'a PathBuf>) -> Vec<String> {
+    files.map(|file| file.display().to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use crate::results::{ActionResult, VerifyDirectoryResult};
+    use crate::runner::Error;
+    use crate::types::{DirectoryExpectation, FilePath, VerifyDirectoryAction};
+    use std::fs;
+    use std::path::Path;
+
+    fn write_files(directory: &Path, files: &[(&str, &str)]) {
+        for (name, content) in files {
+            let path = directory.join(name);
+            fs::create_dir_all(path.parent().unwrap()).expect("Failed to create directory");
+            fs::write(path, content).expect("Failed to write file");
+        }
+    }
+
+    fn verify(directory: &Path, golden: &Path) -> Result<ActionResult, Error> {
+        run(&VerifyDirectoryAction {
+            directory: FilePath(directory.display().to_string()),
+            expected: DirectoryExpectation::Golden(FilePath(golden.display().to_string())),
+        })
+    }
+
+    fn result(directory: &Path, golden: &Path) -> VerifyDirectoryResult {
+        match verify(directory, golden) {
+            Ok(ActionResult::VerifyDirectory(result)) => result,
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn succeeds_when_the_trees_match() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        let files = [("a.txt", "A"), ("sub/b.txt", "B")];
+        write_files(&root.path().join("out"), &files);
+        write_files(&root.path().join("golden"), &files);
+
+        let result = verify(&root.path().join("out"), &root.path().join("golden")).unwrap();
+
+        assert!(result.success());
+    }
+
+    #[test]
+    fn reports_extra_files() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        write_files(
+            &root.path().join("out"),
+            &[("a.txt", "A"), ("extra.txt", "E")],
+        );
+        write_files(&root.path().join("golden"), &[("a.txt", "A")]);
+
+        let result = result(&root.path().join("out"), &root.path().join("golden"));
+
+        assert_eq!(result.extra, vec!["extra.txt".to_string()]);
+        assert!(result.missing.is_empty());
+        assert!(result.differing.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        write_files(&root.path().join("out"), &[("a.txt", "A")]);
+        write_files(
+            &root.path().join("golden"),
+            &[("a.txt", "A"), ("sub/b.txt", "B")],
+        );
+
+        let result = result(&root.path().join("out"), &root.path().join("golden"));
+
+        assert_eq!(result.missing, vec!["sub/b.txt".to_string()]);
+        assert!(result.extra.is_empty());
+    }
+
+    #[test]
+    fn reports_differing_files() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        write_files(
+            &root.path().join("out"),
+            &[("a.txt", "A"), ("b.txt", "changed")],
+        );
+        write_files(
+            &root.path().join("golden"),
+            &[("a.txt", "A"), ("b.txt", "B")],
+        );
+
+        let result = result(&root.path().join("out"), &root.path().join("golden"));
+
+        assert_eq!(result.differing, vec!["b.txt".to_string()]);
+    }
+
+    #[test]
+    fn fails_when_the_golden_directory_does_not_exist() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        let golden = root.path().join("golden");
+
+        assert_eq!(
+            verify(&root.path().join("out"), &golden),
+            Err(Error::RunFailed {
+                message: format!("The golden directory {} does not exist", golden.display())
+            })
+        );
+    }
+
+    fn tree_result(directory: &Path, paths: &[&str]) -> VerifyDirectoryResult {
+        match run(&VerifyDirectoryAction {
+            directory: FilePath(directory.display().to_string()),
+            expected: DirectoryExpectation::Tree(paths.iter().map(ToString::to_string).collect()),
+        }) {
+            Ok(ActionResult::VerifyDirectory(result)) => result,
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn succeeds_when_every_file_matches_a_listed_path() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        write_files(
+            root.path(),
+            &[
+                ("index.html", ""),
+                ("assets/app.js", ""),
+                ("assets/vendor.js", ""),
+            ],
+        );
+
+        let result = tree_result(root.path(), &["index.html", "assets/*.js"]);
+
+        assert!(result.missing.is_empty());
+        assert!(result.extra.is_empty());
+    }
+
+    #[test]
+    fn reports_unmatched_paths_as_missing_and_unlisted_files_as_extra() {
+        let root = tempfile::tempdir().expect("Failed to create a temporary directory");
+        write_files(root.path(), &[("index.html", ""), ("debug.log", "")]);
+
+        let result = tree_result(root.path(), &["index.html", "assets/*.js"]);
+
+        assert_eq!(result.missing, vec!["assets/*.js".to_string()]);
+        assert_eq!(result.extra, vec!["debug.log".to_string()]);
+    }
+}
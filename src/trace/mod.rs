@@ -0,0 +1,48 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", format_record(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+pub fn enable() {
+    log::set_logger(&LOGGER)
+        .map(|()| log::set_max_level(LevelFilter::Trace))
+        .expect("Failed to initialise trace logging");
+}
+
+fn format_record(record: &Record<'_>) -> String {
+    format!("[trace] {}", record.args())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_record;
+    use log::{Level, Record};
+
+    #[test]
+    fn format_record_prefixes_the_message() {
+        assert_eq!(
+            format_record(
+                &Record::builder()
+                    .level(Level::Trace)
+                    .args(format_args!("running script 'x'"))
+                    .build()
+            ),
+            "[trace] running script 'x'"
+        );
+    }
+}
@@ -1,11 +1,35 @@
-#[derive(Clone, Debug, Eq, PartialEq)]
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Stream {
     StdOut,
     StdErr,
+    Output,
 }
 
+// Operating systems as named by `std::env::consts::OS`, where a leading `!` excludes one
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct TargetOs(pub String);
+pub struct TargetOs(pub Vec<String>);
+
+impl TargetOs {
+    pub fn matches(&self, os: &str) -> bool {
+        let (excluded, included): (Vec<&String>, Vec<&String>) =
+            self.0.iter().partition(|target| target.starts_with('!'));
+
+        (included.is_empty() || included.iter().any(|target| *target == os))
+            && !excluded.iter().any(|target| &target[1..] == os)
+    }
+}
+
+impl fmt::Display for TargetOs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.split_last() {
+            Some((last, rest)) if !rest.is_empty() => write!(f, "{} or {last}", rest.join(", ")),
+            _ => write!(f, "{}", self.0.join("")),
+        }
+    }
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ScriptName(pub String);
@@ -73,6 +97,41 @@ impl From<ExitCode> for i32 {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpectedExitCode {
+    Exactly(ExitCode),
+    Range { from: ExitCode, to: ExitCode },
+    OneOf(Vec<ExitCode>),
+}
+
+impl ExpectedExitCode {
+    pub fn matches(&self, exit_code: ExitCode) -> bool {
+        match self {
+            Self::Exactly(expected) => *expected == exit_code,
+            Self::Range { from, to } => (from.0..=to.0).contains(&exit_code.0),
+            Self::OneOf(expected) => expected.contains(&exit_code),
+        }
+    }
+}
+
+impl fmt::Display for ExpectedExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exactly(expected) => write!(f, "{}", expected.0),
+            Self::Range { from, to } => write!(f, "{}..{}", from.0, to.0),
+            Self::OneOf(expected) => {
+                let codes: Vec<String> = expected.iter().map(|code| code.0.to_string()).collect();
+                match codes.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        write!(f, "{} or {last}", rest.join(", "))
+                    }
+                    _ => write!(f, "{}", codes.join("")),
+                }
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum OutputExpectation {
     Any,
@@ -81,18 +140,196 @@ pub enum OutputExpectation {
     None,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScriptWorkdir {
+    Temporary,
+}
+
+// Cleanup scripts are run after every other block in the file
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScriptPhase {
+    #[default]
+    Main,
+    Cleanup,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ScriptOptions {
+    pub tee: Option<FilePath>,
+    pub idempotent: bool,
+    pub changed_marker: Option<String>,
+    pub workdir: Option<ScriptWorkdir>,
+    pub capture_env: bool,
+    pub tags: Vec<String>,
+    pub stable_runs: Option<u32>,
+    pub expected_signal: Option<i32>,
+    pub retries: u32,
+    pub allow_failure: bool,
+    pub skip: Option<String>,
+    pub target_os: Option<TargetOs>,
+    pub requires_command: Vec<String>,
+    pub only_if_env: Vec<String>,
+    pub skip_if_env: Vec<String>,
+    pub phase: ScriptPhase,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ScriptAction {
     pub script_name: Option<ScriptName>,
     pub script_code: ScriptCode,
-    pub expected_exit_code: Option<ExitCode>,
+    pub expected_exit_code: Option<ExpectedExitCode>,
     pub expected_output: OutputExpectation,
+    pub options: ScriptOptions,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Matcher {
+    #[default]
+    Exact,
+    Fuzzy {
+        max_distance: u32,
+    },
+    StartsWith,
+    EndsWith,
+    Contains,
+    Number {
+        comparison: Comparison,
+        value: i32,
+    },
+    Semver {
+        comparison: Comparison,
+        value: Version,
+    },
+    LineCount,
+    ValidJson,
+    Regex,
+    Yaml,
+    Wildcard,
+    Glob,
+    Numeric {
+        tolerance: Tolerance,
+    },
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Self::GreaterThan => ">",
+            Self::GreaterThanOrEqual => ">=",
+            Self::LessThan => "<",
+            Self::LessThanOrEqual => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Ascii,
+    Base64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamUsage {
+    StdOutOnly,
+    StdErrOnly,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Substitution {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+// Whether variables in the expected value are rendered before comparing
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rendering {
+    #[default]
+    Template,
+    Raw,
+}
+
+// Which part of the script's output is compared
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputRange {
+    #[default]
+    All,
+    SinceLast,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Whitespace {
+    #[default]
+    Keep,
+    Trim,
+    TrimTrailing,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Case {
+    #[default]
+    Sensitive,
+    Insensitive,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VerifyOptions {
+    pub rendering: Rendering,
+    pub matcher: Matcher,
+    pub mask_paths: bool,
+    pub encoding: Option<Encoding>,
+    pub whitespace: Whitespace,
+    pub case: Case,
+    pub message: Option<String>,
+    pub any_of: bool,
+    pub alternatives: Vec<VerifyValue>,
+    pub normalize_with: Option<String>,
+    pub display: Option<String>,
+    pub stream_usage: Option<StreamUsage>,
+    pub substitutions: Vec<Substitution>,
+    pub output_range: OutputRange,
+    pub env_changed: Option<String>,
+    pub allow_failure: bool,
+    pub target_os: Option<TargetOs>,
+    pub only_if_env: Vec<String>,
+    pub skip_if_env: Vec<String>,
+}
+
+impl VerifyOptions {
+    pub fn trim_whitespace(&self, text: &str) -> String {
+        match self.whitespace {
+            Whitespace::Trim => text.trim().to_string(),
+            Whitespace::TrimTrailing => text
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim_end()
+                .to_string(),
+            Whitespace::Keep => text.to_string(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct VerifyAction {
     pub source: Source,
     pub expected_value: VerifyValue,
+    pub options: VerifyOptions,
 }
 
 impl VerifyAction {
@@ -103,26 +340,281 @@ impl VerifyAction {
                 stream: self.source.stream.clone(),
             },
             expected_value: self.expected_value.clone(),
+            options: self.options.clone(),
+        }
+    }
+
+    pub fn with_expected_value(&self, expected_value: VerifyValue) -> Self {
+        Self {
+            source: self.source.clone(),
+            expected_value,
+            options: self.options.clone(),
         }
     }
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CreateFileOptions {
+    pub final_newline: Option<bool>,
+    pub append: bool,
+    pub mode: Option<FileMode>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CreateFileAction {
     pub file_path: FilePath,
     pub file_content: FileContent,
+    pub options: CreateFileOptions,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DirectoryExpectation {
+    Golden(FilePath),
+    // Paths relative to the directory, each of which may contain `*` and `?`
+    Tree(Vec<String>),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyDirectoryAction {
+    pub directory: FilePath,
+    pub expected: DirectoryExpectation,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FileMode(pub u32);
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:03o}", self.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance(pub f64);
+
+// Tolerances are parsed from the spec so are never NaN
+impl Eq for Tolerance {}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyFileAction {
+    pub file_path: FilePath,
+    pub expected_value: VerifyValue,
+    pub mode: Option<FileMode>,
+    pub exists: Option<bool>,
+    pub options: VerifyOptions,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NoteAction {
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SkipAction {
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StdinAction {
+    pub script_name: ScriptName,
+    pub input: String,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SetEnvAction {
+    pub variables: Vec<(String, String)>,
+    pub unset: Vec<String>,
+    pub reset: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Action {
     Script(ScriptAction),
     Verify(VerifyAction),
+    VerifyDirectory(VerifyDirectoryAction),
+    VerifyFile(VerifyFileAction),
     CreateFile(CreateFileAction),
+    Note(NoteAction),
+    SetEnv(SetEnvAction),
+    Stdin(StdinAction),
+    Skip(SkipAction),
+}
+
+// Lets the parser's passes work on plain actions and on actions carrying a section
+impl AsRef<Self> for Action {
+    fn as_ref(&self) -> &Self {
+        self
+    }
+}
+
+impl AsMut<Self> for Action {
+    fn as_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+// Numeric identifiers sort before alphanumeric ones, as semver requires
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum PreRelease {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl fmt::Display for PreRelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numeric(number) => write!(f, "{number}"),
+            Self::Alphanumeric(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+// Build metadata is kept for display but ignored when comparing versions
+#[derive(Clone, Debug)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub pre_release: Vec<PreRelease>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre_release: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// A pre-release version has lower precedence than the release it precedes
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(
+                || match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => self.pre_release.cmp(&other.pre_release),
+                },
+            )
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre_release.is_empty() {
+            let identifiers: Vec<String> =
+                self.pre_release.iter().map(ToString::to_string).collect();
+            write!(f, "-{}", identifiers.join("."))?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Metadata {
+    pub min_specdown_version: Option<Version>,
+    pub tags: Vec<String>,
+    pub shell: Option<String>,
+    pub env: Vec<(String, String)>,
+}
+
+// The headings an action sits under, outermost first
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SectionPath(pub Vec<String>);
+
+impl fmt::Display for SectionPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(" > "))
+    }
+}
+
+// `sections` holds the section path of the action at the same index
+#[derive(Debug, Eq, PartialEq)]
+pub struct Spec {
+    pub metadata: Metadata,
+    pub actions: Vec<Action>,
+    pub sections: Vec<SectionPath>,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ExitCode, FilePath, ScriptName, Source, Stream, VerifyAction, VerifyValue};
+    use super::{
+        ExitCode, ExpectedExitCode, FileMode, FilePath, Rendering, ScriptName, SectionPath, Source,
+        Stream, TargetOs, VerifyAction, VerifyOptions, VerifyValue, Whitespace,
+    };
+
+    mod target_os {
+        use super::TargetOs;
+
+        fn target_os(targets: &[&str]) -> TargetOs {
+            TargetOs(targets.iter().map(ToString::to_string).collect())
+        }
+
+        #[test]
+        fn matches_any_of_the_listed_operating_systems() {
+            assert!(target_os(&["linux", "macos"]).matches("macos"));
+            assert!(!target_os(&["linux", "macos"]).matches("windows"));
+        }
+
+        #[test]
+        fn matches_every_operating_system_which_is_not_excluded() {
+            assert!(target_os(&["!windows"]).matches("linux"));
+            assert!(!target_os(&["!windows"]).matches("windows"));
+            assert!(!target_os(&["!windows", "!macos"]).matches("macos"));
+        }
+
+        #[test]
+        fn displays_the_listed_operating_systems() {
+            assert_eq!(target_os(&["windows"]).to_string(), "windows");
+            assert_eq!(
+                target_os(&["linux", "macos", "!windows"]).to_string(),
+                "linux, macos or !windows"
+            );
+        }
+    }
+
+    mod section_path {
+        use super::SectionPath;
+
+        #[test]
+        fn displays_the_headings_separated_by_arrows() {
+            let path = SectionPath(vec!["Installation".to_string(), "From source".to_string()]);
+
+            assert_eq!(path.to_string(), "Installation > From source");
+        }
+    }
 
     mod script_name {
         use super::ScriptName;
@@ -144,6 +636,33 @@ mod tests {
         }
     }
 
+    mod verify_options {
+        use super::{VerifyOptions, Whitespace};
+
+        #[test]
+        fn trims_trailing_whitespace_from_each_line_and_the_end() {
+            let options = VerifyOptions {
+                whitespace: Whitespace::TrimTrailing,
+                ..VerifyOptions::default()
+            };
+
+            assert_eq!(
+                options.trim_whitespace("  first  \nsecond\t\r\n\n"),
+                "  first\nsecond"
+            );
+        }
+
+        #[test]
+        fn trim_removes_leading_whitespace_as_well() {
+            let options = VerifyOptions {
+                whitespace: Whitespace::Trim,
+                ..VerifyOptions::default()
+            };
+
+            assert_eq!(options.trim_whitespace("  first  \n"), "first");
+        }
+    }
+
     mod verify_value {
         use super::VerifyValue;
 
@@ -168,6 +687,17 @@ mod tests {
         }
     }
 
+    mod file_mode {
+        use super::FileMode;
+
+        #[test]
+        fn displays_as_octal() {
+            assert_eq!(FileMode(0o755).to_string(), "755");
+            assert_eq!(FileMode(0o4755).to_string(), "4755");
+            assert_eq!(FileMode(0o44).to_string(), "044");
+        }
+    }
+
     mod exit_code {
         use super::ExitCode;
 
@@ -182,8 +712,48 @@ mod tests {
         }
     }
 
+    mod expected_exit_code {
+        use super::{ExitCode, ExpectedExitCode};
+
+        #[test]
+        fn matches_exit_codes_within_an_inclusive_range() {
+            let expected = ExpectedExitCode::Range {
+                from: ExitCode(1),
+                to: ExitCode(5),
+            };
+            assert!(expected.matches(ExitCode(1)));
+            assert!(expected.matches(ExitCode(5)));
+            assert!(!expected.matches(ExitCode(0)));
+            assert!(!expected.matches(ExitCode(6)));
+        }
+
+        #[test]
+        fn matches_any_exit_code_in_a_list() {
+            let expected = ExpectedExitCode::OneOf(vec![ExitCode(1), ExitCode(4)]);
+            assert!(expected.matches(ExitCode(4)));
+            assert!(!expected.matches(ExitCode(2)));
+        }
+
+        #[test]
+        fn displays_the_acceptable_exit_codes() {
+            assert_eq!(ExpectedExitCode::Exactly(ExitCode(2)).to_string(), "2");
+            assert_eq!(
+                ExpectedExitCode::Range {
+                    from: ExitCode(1),
+                    to: ExitCode(5)
+                }
+                .to_string(),
+                "1..5"
+            );
+            assert_eq!(
+                ExpectedExitCode::OneOf(vec![ExitCode(1), ExitCode(2), ExitCode(4)]).to_string(),
+                "1, 2 or 4"
+            );
+        }
+    }
+
     mod verify_action {
-        use super::{Source, Stream, VerifyAction, VerifyValue};
+        use super::{Rendering, Source, Stream, VerifyAction, VerifyOptions, VerifyValue};
         use crate::types::ScriptName;
 
         #[test]
@@ -194,6 +764,7 @@ mod tests {
                     stream: Stream::StdOut,
                 },
                 expected_value: VerifyValue(String::new()),
+                options: VerifyOptions::default(),
             };
 
             assert_eq!(
@@ -202,10 +773,41 @@ mod tests {
                         name: Some(ScriptName("new_name".to_string())),
                         stream: Stream::StdOut,
                     },
-                    expected_value: VerifyValue(String::new())
+                    expected_value: VerifyValue(String::new()),
+                    options: VerifyOptions::default(),
                 },
                 action.with_script_name(Some(ScriptName("new_name".to_string())))
             );
         }
+
+        #[test]
+        fn with_expected_value_returns_an_instance_with_expected_value_updated() {
+            let action = VerifyAction {
+                source: Source {
+                    name: None,
+                    stream: Stream::StdOut,
+                },
+                expected_value: VerifyValue("old".to_string()),
+                options: VerifyOptions {
+                    rendering: Rendering::Raw,
+                    ..VerifyOptions::default()
+                },
+            };
+
+            assert_eq!(
+                VerifyAction {
+                    source: Source {
+                        name: None,
+                        stream: Stream::StdOut,
+                    },
+                    expected_value: VerifyValue("new".to_string()),
+                    options: VerifyOptions {
+                        rendering: Rendering::Raw,
+                        ..VerifyOptions::default()
+                    },
+                },
+                action.with_expected_value(VerifyValue("new".to_string()))
+            );
+        }
     }
 }
@@ -137,6 +137,32 @@ fn test_doc_errors() {
     assert_ok(&result);
 }
 
+#[cfg(not(windows))]
+#[test]
+fn test_doc_verifying_directories() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/verifying_directories.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_verifying_files() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/verifying_files.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
 #[test]
 fn test_doc_skipping_code_blocks() {
     let result = Command::cargo_bin("specdown")
@@ -149,6 +175,174 @@ fn test_doc_skipping_code_blocks() {
     assert_ok(&result);
 }
 
+#[test]
+fn test_doc_tagging_scripts() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/tagging_scripts.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_teeing_script_output() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/teeing_script_output.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_checking_idempotency() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/checking_idempotency.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_isolating_script_workdirs() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/isolating_script_workdirs.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_setting_environment_variables() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/setting_environment_variables.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_providing_script_input() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/providing_script_input.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_cleaning_up() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/cleaning_up.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_setup_and_teardown() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/setup_and_teardown.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_including_spec_files() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/including_spec_files.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_file_settings() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/file_settings.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_hiding_code_blocks() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/hiding_code_blocks.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_attribute_syntax() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/attribute_syntax.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_doc_combining_functions() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/specs/combining_functions.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
 #[cfg(not(windows))]
 #[test]
 fn test_doc_completion() {
@@ -174,6 +368,19 @@ fn test_doc_stripping_specs() {
     assert_ok(&result);
 }
 
+#[cfg(not(windows))]
+#[test]
+fn test_doc_tracing() {
+    let result = Command::cargo_bin("specdown")
+        .unwrap()
+        .arg("run")
+        .arg("--temporary-workspace-dir")
+        .arg("docs/cli/tracing.md")
+        .ok();
+
+    assert_ok(&result);
+}
+
 #[test]
 fn test_displays_error_when_required_args_are_missing() {
     #[cfg(windows)]